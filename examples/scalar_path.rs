@@ -0,0 +1,148 @@
+//! Times `PSO::update` on scalar positions against a hand-written swarm of the same update.
+//!
+//! The ratio bounds the overhead of the generic implementation on the scalar path. Cloning an
+//! `f64` position is a copy, so most of it is the bookkeeping `update` does besides moving the
+//! particles, such as the stagnation counters, the archive and the recorded bests.
+//!
+//! Evals and then positions were relaxed from `Copy` to `Clone`. Against the `PSO::update` of
+//! those versions, the same 40 runs took 18.0 ms both before and after the evals were relaxed,
+//! and 17.3 ms before and 17.2 ms after the positions were, so neither `Clone` bound costs
+//! anything measurable. The scalar path has since slowed to about 26 ms. The largest step, about
+//! 20%, came with `PSO::set_comparator`, when every comparison of evals started checking for a
+//! comparator. `update` now checks once per update and compares with `PartialOrd` directly when
+//! none is set, which brought the ratio down from about 1.26 to 1.16. The rest accumulated with
+//! the later per-update bookkeeping.
+//!
+//! Run with `cargo run --release --example scalar_path`.
+
+extern crate meta_heuristics;
+extern crate rand;
+
+use std::time::{Duration, Instant};
+
+use meta_heuristics::{pso, Candidate, RandomCandidate};
+use rand::{Closed01, Rng, SeedableRng, XorShiftRng};
+
+const PARTICLES: usize = 64;
+// Short runs over many seeds, as converged swarms slow down on subnormal velocities.
+const RUNS: u32 = 40;
+const UPDATES: usize = 500;
+const INERTIA: f64 = 0.7;
+const C_LOCAL: f64 = 1.4;
+const C_GLOBAL: f64 = 1.4;
+
+fn eval_func(x: f64) -> f64 {
+    -x * x
+}
+
+#[derive(Clone, Copy)]
+struct Particle {
+    pos: f64,
+    vel: f64,
+    best: (f64, f64),
+}
+
+impl Particle {
+    fn at(x: f64) -> Self {
+        Particle { pos: x, vel: 0.0, best: (x, eval_func(x)) }
+    }
+}
+
+impl Candidate for Particle {
+    type Pos = f64;
+    type Eval = f64;
+
+    fn eval(&self) -> f64 {
+        eval_func(self.pos)
+    }
+
+    fn pos(&self) -> f64 {
+        self.pos
+    }
+
+    fn pos_mut(&mut self) -> &mut f64 {
+        &mut self.pos
+    }
+}
+
+impl RandomCandidate for Particle {
+    fn new_random() -> Self {
+        let Closed01(x) = rand::random::<Closed01<f64>>();
+        Particle::at(10.0 * x - 5.0)
+    }
+}
+
+impl pso::Particle for Particle {
+    fn vel(&self) -> f64 {
+        self.vel
+    }
+
+    fn best(&self) -> (f64, f64) {
+        self.best
+    }
+
+    fn vel_mut(&mut self) -> &mut f64 {
+        &mut self.vel
+    }
+
+    fn best_mut(&mut self) -> &mut (f64, f64) {
+        &mut self.best
+    }
+}
+
+/// The standard update with the global topology, on `Copy` positions only. Returns the best
+/// eval.
+fn hand_written(rng: &mut XorShiftRng) -> f64 {
+    let mut particles: Vec<Particle> = (0..PARTICLES)
+        .map(|_| Particle::at(10.0 * rng.gen::<Closed01<f64>>().0 - 5.0))
+        .collect();
+    let mut best = particles[0].best;
+    for p in &particles {
+        if p.best.1 > best.1 {
+            best = p.best;
+        }
+    }
+
+    for _ in 0..UPDATES {
+        for p in &mut particles {
+            p.pos += p.vel;
+            let r_local = rng.gen::<Closed01<f64>>().0;
+            let r_global = rng.gen::<Closed01<f64>>().0;
+            p.vel = p.vel * INERTIA + (p.best.0 - p.pos) * C_LOCAL * r_local +
+                    (best.0 - p.pos) * C_GLOBAL * r_global;
+        }
+        for p in &mut particles {
+            let e = eval_func(p.pos);
+            if e > p.best.1 {
+                p.best = (p.pos, e);
+            }
+            if e > best.1 {
+                best = (p.pos, e);
+            }
+        }
+    }
+    best.1
+}
+
+fn main() {
+    let mut baseline = Duration::new(0, 0);
+    let mut generic = Duration::new(0, 0);
+    for seed in 1..RUNS + 1 {
+        let start = Instant::now();
+        hand_written(&mut XorShiftRng::from_seed([seed, 2, 3, 4]));
+        baseline += start.elapsed();
+
+        let start = Instant::now();
+        let rng = XorShiftRng::from_seed([seed, 2, 3, 4]);
+        let mut swarm: pso::PSO<Particle, _> =
+            pso::PSO::new_with_rng(PARTICLES, INERTIA, C_LOCAL, C_GLOBAL, rng);
+        for _ in 0..UPDATES {
+            swarm.update();
+        }
+        generic += start.elapsed();
+    }
+
+    println!("hand-written: {:?}", baseline);
+    println!("PSO::update:  {:?}", generic);
+    println!("ratio:        {:.2}", generic.as_secs_f64() / baseline.as_secs_f64());
+}
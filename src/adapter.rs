@@ -0,0 +1,381 @@
+//! Adapters presenting a candidate type under a different objective.
+//!
+//! # Example
+//! ```
+//! use meta_heuristics::adapter::EvalMap;
+//!
+//! // A logarithmic compression which, unlike `ln`, is defined and increasing on all reals.
+//! #[derive(Clone, Copy, Default)]
+//! struct SymLog;
+//!
+//! impl EvalMap<f64> for SymLog {
+//!     type Output = f64;
+//!
+//!     fn map(&self, e: f64) -> f64 {
+//!         e.signum() * e.abs().ln_1p()
+//!     }
+//! }
+//!
+//! assert_eq!(SymLog.map(-(1f64.exp() - 1.0)), -1.0);
+//! ```
+//!
+//! `MapEval<T, SymLog>` then searches the compressed objective of any candidate type `T`, with
+//! any optimizer. A closure maps evals as well, but `RandomCandidate::new_random` has no closure
+//! to take, so the candidates then come from a sampler:
+//!
+//! ```
+//! use meta_heuristics::adapter::MapEval;
+//! use meta_heuristics::pso::{Particle, PSO};
+//!
+//! // Squashes the evals into (-π/2, π/2), at a scale picked at run time.
+//! fn squashed<T, S>(mut sampler: S, scale: f64) -> PSO<MapEval<T, impl Fn(f64) -> f64 + Clone>>
+//!     where T: Particle<Eval = f64> + Clone,
+//!           S: FnMut() -> T + Send + 'static
+//! {
+//!     let squash = move |e: f64| (e / scale).atan();
+//!     PSO::new_with_sampler(16, move || MapEval::new_with_map(sampler(), squash), 0.9, 0.9, 0.9)
+//! }
+//! # fn main() {}
+//! ```
+
+use std::error::Error;
+use rand::Rng;
+
+use {Candidate, Components, RandomCandidate, SeededCandidate};
+use pso::{Axes, ComponentBounds, Gaussian, GrowDimension, Levy, Metric, Particle, RandomVelocity,
+          RegionSampling, VelocityNorm};
+use firefly::{Brightness, Firefly, RandomStep, ScaledStep};
+use woa::Componentwise;
+
+/// A transformation of evals, applied by `MapEval`.
+///
+/// Closures from `E` implement this. `MapEval` also implements `RandomCandidate` for maps which
+/// are `Default`, as `RandomCandidate::new_random` creates candidates without an instance to
+/// take the map from; a unit type serves there. The transformation should be monotonically
+/// increasing to keep the optimum where it is, but any map reshaping the selection pressure is
+/// allowed.
+pub trait EvalMap<E> {
+    type Output: Clone + PartialOrd;
+
+    fn map(&self, e: E) -> Self::Output;
+}
+
+impl<E, O, G> EvalMap<E> for G
+    where G: Fn(E) -> O,
+          O: Clone + PartialOrd
+{
+    type Output = O;
+
+    fn map(&self, e: E) -> O {
+        self(e)
+    }
+}
+
+/// A candidate whose eval is the eval of the wrapped candidate `T` transformed by `F`.
+///
+/// Everything else is delegated to `T`, except that the personal best used by `pso::Particle`
+/// is kept by the adapter, in terms of the transformed eval; the wrapped candidate's own
+/// personal best is not updated. `Particle::eval_batch` takes a slice of candidates, so it
+/// cannot be delegated, and the adapter evaluates one candidate at a time instead.
+pub struct MapEval<T: Candidate, F: EvalMap<T::Eval>> {
+    inner: T,
+    best: (T::Pos, F::Output),
+    map: F,
+}
+
+impl<T, F> MapEval<T, F>
+    where T: Particle,
+          F: EvalMap<T::Eval>
+{
+    /// Wraps `inner`, taking over its personal best with the eval transformed.
+    pub fn new(inner: T) -> Self
+        where F: Default
+    {
+        Self::new_with_map(inner, F::default())
+    }
+
+    /// Wraps `inner` like `new`, transforming evals with `map`.
+    pub fn new_with_map(inner: T, map: F) -> Self {
+        let (pos, e) = inner.best();
+        Self {
+            inner,
+            best: (pos, map.map(e)),
+            map,
+        }
+    }
+}
+
+impl<T, F> MapEval<T, F>
+    where T: Candidate,
+          F: EvalMap<T::Eval>
+{
+    /// Wraps `inner`, starting the personal best at its current position. For firefly
+    /// candidates, which have no personal best.
+    pub fn from_candidate(inner: T) -> Self
+        where F: Default
+    {
+        Self::from_candidate_with_map(inner, F::default())
+    }
+
+    /// Wraps `inner` like `from_candidate`, transforming evals with `map`.
+    pub fn from_candidate_with_map(inner: T, map: F) -> Self {
+        let best = (inner.pos(), map.map(inner.eval()));
+        Self { inner, best, map }
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T, F> Clone for MapEval<T, F>
+    where T: Candidate + Clone,
+          F: EvalMap<T::Eval> + Clone
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            best: self.best.clone(),
+            map: self.map.clone(),
+        }
+    }
+}
+
+impl<T, F> Copy for MapEval<T, F>
+    where T: Candidate + Copy,
+          T::Pos: Copy,
+          F: EvalMap<T::Eval> + Copy,
+          F::Output: Copy
+{
+}
+
+impl<T, F> Candidate for MapEval<T, F>
+    where T: Candidate,
+          F: EvalMap<T::Eval>
+{
+    type Pos = T::Pos;
+    type Eval = F::Output;
+
+    fn eval(&self) -> Self::Eval {
+        self.map.map(self.inner.eval())
+    }
+
+    fn eval_at(&self, iteration: usize) -> Self::Eval {
+        self.map.map(self.inner.eval_at(iteration))
+    }
+
+    fn try_eval(&self) -> Result<Self::Eval, Box<dyn Error>> {
+        self.inner.try_eval().map(|e| self.map.map(e))
+    }
+
+    fn pos(&self) -> Self::Pos {
+        self.inner.pos()
+    }
+    fn pos_mut(&mut self) -> &mut Self::Pos {
+        self.inner.pos_mut()
+    }
+
+    fn repair(&mut self) {
+        self.inner.repair();
+    }
+
+    fn is_valid(&self) -> bool {
+        self.inner.is_valid()
+    }
+
+    fn is_finite(&self) -> bool {
+        self.inner.is_finite()
+    }
+
+    fn dim(&self) -> usize {
+        self.inner.dim()
+    }
+}
+
+impl<T, F> Components for MapEval<T, F>
+    where T: Components,
+          F: EvalMap<T::Eval>
+{
+    fn components(&self) -> Vec<f64> {
+        self.inner.components()
+    }
+}
+
+impl<T, F> RandomCandidate for MapEval<T, F>
+    where T: RandomCandidate,
+          F: EvalMap<T::Eval> + Default
+{
+    fn new_random() -> Self {
+        Self::from_candidate(T::new_random())
+    }
+}
+
+impl<T, F> SeededCandidate for MapEval<T, F>
+    where T: SeededCandidate,
+          F: EvalMap<T::Eval> + Default
+{
+    fn new_random_seeded(seed: u64) -> Self {
+        Self::from_candidate(T::new_random_seeded(seed))
+    }
+}
+
+impl<T, F> Particle for MapEval<T, F>
+    where T: Particle,
+          F: EvalMap<T::Eval>
+{
+    fn vel(&self) -> Self::Pos {
+        self.inner.vel()
+    }
+    fn best(&self) -> (Self::Pos, Self::Eval) {
+        self.best.clone()
+    }
+    fn vel_mut(&mut self) -> &mut Self::Pos {
+        self.inner.vel_mut()
+    }
+    fn best_mut(&mut self) -> &mut (Self::Pos, Self::Eval) {
+        &mut self.best
+    }
+}
+
+impl<T, F> VelocityNorm for MapEval<T, F>
+    where T: VelocityNorm,
+          F: EvalMap<T::Eval>
+{
+    fn vel_magnitude(&self) -> f64 {
+        self.inner.vel_magnitude()
+    }
+}
+
+impl<T, F> Metric for MapEval<T, F>
+    where T: Metric,
+          F: EvalMap<T::Eval>
+{
+    fn pos_distance(a: &Self::Pos, b: &Self::Pos) -> f64 {
+        T::pos_distance(a, b)
+    }
+}
+
+impl<T, F> ComponentBounds for MapEval<T, F>
+    where T: ComponentBounds,
+          F: EvalMap<T::Eval>
+{
+    fn component_min(a: &Self::Pos, b: &Self::Pos) -> Self::Pos {
+        T::component_min(a, b)
+    }
+
+    fn component_max(a: &Self::Pos, b: &Self::Pos) -> Self::Pos {
+        T::component_max(a, b)
+    }
+}
+
+impl<T, F> RegionSampling for MapEval<T, F>
+    where T: RegionSampling,
+          F: EvalMap<T::Eval> + Default
+{
+    fn random_in<R: Rng>(lower: &Self::Pos, upper: &Self::Pos, rng: &mut R) -> Self {
+        Self::new(T::random_in(lower, upper, rng))
+    }
+}
+
+impl<T, F> RandomVelocity for MapEval<T, F>
+    where T: RandomVelocity,
+          F: EvalMap<T::Eval>
+{
+    fn random_vel<R: Rng>(scale: f64, rng: &mut R) -> Self::Pos {
+        T::random_vel(scale, rng)
+    }
+}
+
+impl<T, F> Levy for MapEval<T, F>
+    where T: Levy,
+          F: EvalMap<T::Eval>
+{
+    fn levy_step<R: Rng>(&mut self, exponent: f64, rng: &mut R) {
+        self.inner.levy_step(exponent, rng);
+    }
+}
+
+impl<T, F> Gaussian for MapEval<T, F>
+    where T: Gaussian,
+          F: EvalMap<T::Eval>
+{
+    fn gaussian_sample<R: Rng>(mean: &Self::Pos, std: &Self::Pos, rng: &mut R) -> Self::Pos {
+        T::gaussian_sample(mean, std, rng)
+    }
+}
+
+impl<T, F> Axes for MapEval<T, F>
+    where T: Axes,
+          F: EvalMap<T::Eval>
+{
+    fn axis(&self, i: usize) -> Self::Pos {
+        self.inner.axis(i)
+    }
+}
+
+impl<T, F> GrowDimension for MapEval<T, F>
+    where T: GrowDimension,
+          F: EvalMap<T::Eval>
+{
+    fn push_dimension<R: Rng>(&mut self, value: f64, rng: &mut R) {
+        self.inner.push_dimension(value, rng);
+    }
+}
+
+impl<T, F> Firefly for MapEval<T, F>
+    where T: Firefly,
+          F: EvalMap<T::Eval>
+{
+    fn distance(&self, rhs: &Self) -> f64 {
+        self.inner.distance(&rhs.inner)
+    }
+}
+
+impl<T, F> RandomStep for MapEval<T, F>
+    where T: RandomStep,
+          F: EvalMap<T::Eval>
+{
+    fn random_step<R: Rng>(scale: f64, rng: &mut R) -> Self::Pos {
+        T::random_step(scale, rng)
+    }
+}
+
+impl<T, F> ScaledStep for MapEval<T, F>
+    where T: ScaledStep,
+          F: EvalMap<T::Eval>
+{
+    fn random_step_in<R: Rng>(range: &Self::Pos, rng: &mut R) -> Self::Pos {
+        T::random_step_in(range, rng)
+    }
+}
+
+impl<T, F> Brightness for MapEval<T, F>
+    where T: Brightness,
+          F: EvalMap<T::Eval>
+{
+    fn brightness(&self) -> f64 {
+        self.inner.brightness()
+    }
+}
+
+impl<T, F> Componentwise for MapEval<T, F>
+    where T: Componentwise,
+          F: EvalMap<T::Eval>
+{
+    fn encircle<R: Rng>(pos: &Self::Pos,
+                        leader: &Self::Pos,
+                        random: &Self::Pos,
+                        a: f64,
+                        rng: &mut R)
+                        -> Self::Pos {
+        T::encircle(pos, leader, random, a, rng)
+    }
+
+    fn abs(pos: &Self::Pos) -> Self::Pos {
+        T::abs(pos)
+    }
+}
@@ -0,0 +1,160 @@
+//! A record of the best solution ever seen, independent of any population.
+//!
+//! # Example
+//! ```
+//! use meta_heuristics::Candidate;
+//! use meta_heuristics::archive::Archive;
+//!
+//! #[derive(Clone, Copy, Debug, PartialEq)]
+//! struct Point {
+//!     pos: f64,
+//! }
+//!
+//! impl Candidate for Point {
+//!     type Pos = f64;
+//!     type Eval = f64;
+//!
+//!     fn eval(&self) -> f64 {
+//!         -self.pos * self.pos
+//!     }
+//!     fn pos(&self) -> f64 {
+//!         self.pos
+//!     }
+//!     fn pos_mut(&mut self) -> &mut f64 {
+//!         &mut self.pos
+//!     }
+//! }
+//!
+//! let mut archive = Archive::new();
+//! assert!(archive.best().is_none());
+//!
+//! assert!(archive.offer(&Point { pos: 2.0 }, &-4.0));
+//! assert!(archive.offer(&Point { pos: 1.0 }, &-1.0));
+//! assert!(!archive.offer(&Point { pos: 3.0 }, &-9.0));
+//! assert_eq!(archive.best(), Some((&Point { pos: 1.0 }, &-1.0)));
+//! ```
+
+use std::cmp;
+
+use Candidate;
+
+/// The minimum improvement, in evals converted to `f64`, and the conversion.
+type Tolerance<E> = (f64, fn(&E) -> f64);
+
+/// The best candidate offered so far and its eval.
+///
+/// Optimizers forget their incumbent when their population is re-randomized; an archive they
+/// feed keeps it.
+#[derive(Clone, Debug)]
+pub struct Archive<T: Candidate> {
+    best: Option<(T, T::Eval)>,
+    tolerance: Option<Tolerance<T::Eval>>,
+}
+
+impl<T: Candidate + Clone> Archive<T> {
+    pub fn new() -> Self {
+        Archive {
+            best: None,
+            tolerance: None,
+        }
+    }
+
+    /// Keeps a copy of `candidate` if `eval` is higher than that of the archived one, by at
+    /// least the tolerance if one is set, and returns whether it did. The first candidate
+    /// offered is always kept.
+    pub fn offer(&mut self, candidate: &T, eval: &T::Eval) -> bool {
+        self.offer_by(candidate,
+                      eval,
+                      |a, b| a.partial_cmp(b).unwrap_or(cmp::Ordering::Equal))
+    }
+
+    /// Keeps a copy of `candidate` like `offer`, but ordering the evals by `comparator`. The
+    /// tolerance is measured in the comparator's order too, so a comparator preferring lower
+    /// evals needs a decrease by at least the tolerance.
+    pub fn offer_by<F>(&mut self, candidate: &T, eval: &T::Eval, comparator: F) -> bool
+        where F: Fn(&T::Eval, &T::Eval) -> cmp::Ordering
+    {
+        let better = match self.best {
+            Some((_, ref best)) => {
+                comparator(eval, best) == cmp::Ordering::Greater &&
+                match self.tolerance {
+                    // Better by the comparator, so the distance is the gain in its order.
+                    Some((eps, to_f64)) => (to_f64(eval) - to_f64(best)).abs() >= eps,
+                    None => true,
+                }
+            }
+            None => true,
+        };
+        if better {
+            self.best = Some((candidate.clone(), eval.clone()));
+        }
+        better
+    }
+
+    /// Returns the archived candidate and its eval, or `None` if nothing was offered since the
+    /// archive was created or cleared.
+    pub fn best(&self) -> Option<(&T, &T::Eval)> {
+        self.best.as_ref().map(|(c, e)| (c, e))
+    }
+
+    pub fn clear(&mut self) {
+        self.best = None;
+    }
+}
+
+impl<T> Archive<T>
+    where T: Candidate + Clone,
+          T::Eval: Into<f64>
+{
+    /// Creates an archive which only replaces its candidate by one whose eval is higher by at
+    /// least `tolerance`, so that noise in the evals does not churn it. This is the archive's
+    /// counterpart of `PSO::set_improvement_epsilon`.
+    ///
+    /// # Panics
+    /// Panics if `tolerance` is negative or NaN.
+    ///
+    /// # Example
+    /// ```
+    /// # use meta_heuristics::Candidate;
+    /// # use meta_heuristics::archive::Archive;
+    /// # #[derive(Clone, Copy, Debug, PartialEq)]
+    /// # struct Point { pos: f64 }
+    /// # impl Candidate for Point {
+    /// #     type Pos = f64;
+    /// #     type Eval = f64;
+    /// #     fn eval(&self) -> f64 { -self.pos * self.pos }
+    /// #     fn pos(&self) -> f64 { self.pos }
+    /// #     fn pos_mut(&mut self) -> &mut f64 { &mut self.pos }
+    /// # }
+    /// let mut archive = Archive::with_tolerance(1e-3);
+    /// assert!(archive.offer(&Point { pos: 1.0 }, &-1.0));
+    ///
+    /// // Each of these improves on the archived eval by less than the tolerance.
+    /// for (i, &e) in [-0.9999, -0.9995, -0.9992].iter().enumerate() {
+    ///     assert!(!archive.offer(&Point { pos: 0.9 - 0.1 * i as f64 }, &e));
+    ///     assert_eq!(archive.best(), Some((&Point { pos: 1.0 }, &-1.0)));
+    /// }
+    ///
+    /// assert!(archive.offer(&Point { pos: 0.5 }, &-0.25));
+    /// assert_eq!(archive.best(), Some((&Point { pos: 0.5 }, &-0.25)));
+    ///
+    /// // Preferring lower evals, only decreases by at least the tolerance count.
+    /// let lower = |a: &f64, b: &f64| b.partial_cmp(a).unwrap();
+    /// assert!(!archive.offer_by(&Point { pos: 0.4999 }, &-0.2499, lower));
+    /// assert!(!archive.offer_by(&Point { pos: 0.5001 }, &-0.2501, lower));
+    /// assert!(archive.offer_by(&Point { pos: 1.0 }, &-1.0, lower));
+    /// ```
+    pub fn with_tolerance(tolerance: f64) -> Self {
+        assert!(tolerance >= 0.0, "negative or NaN tolerance");
+        Archive {
+            best: None,
+            tolerance: Some((tolerance, ::eval_to_f64::<T::Eval>)),
+        }
+    }
+}
+
+impl<T: Candidate + Clone> Default for Archive<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
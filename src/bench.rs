@@ -0,0 +1,394 @@
+//! The experimental protocol of comparing optimizers over independent trials.
+//!
+//! # Example
+//! ```
+//! extern crate meta_heuristics;
+//! extern crate rand;
+//!
+//! use meta_heuristics::{bench, pso, Candidate, RandomCandidate};
+//! use rand::{SeedableRng, StdRng};
+//!
+//! #[derive(Clone, Copy)]
+//! struct Particle {
+//!     pos: f64,
+//!     vel: f64,
+//!     best: (f64, f64),
+//! }
+//!
+//! impl Candidate for Particle {
+//!     type Pos = f64;
+//!     type Eval = f64;
+//!
+//!     fn eval(&self) -> f64 {
+//!         bench::sphere(&[self.pos])
+//!     }
+//!     fn pos(&self) -> f64 {
+//!         self.pos
+//!     }
+//!     fn pos_mut(&mut self) -> &mut f64 {
+//!         &mut self.pos
+//!     }
+//! }
+//!
+//! impl RandomCandidate for Particle {
+//!     fn new_random() -> Self {
+//!         use rand::{random, Closed01};
+//!
+//!         let Closed01(x) = random::<Closed01<f64>>();
+//!         let x = 10.0 * x - 5.0;
+//!         Self { pos: x, vel: 0.0, best: (x, bench::sphere(&[x])) }
+//!     }
+//! }
+//!
+//! impl pso::Particle for Particle {
+//!     fn vel(&self) -> f64 {
+//!         self.vel
+//!     }
+//!     fn best(&self) -> (f64, f64) {
+//!         self.best
+//!     }
+//!     fn vel_mut(&mut self) -> &mut f64 {
+//!         &mut self.vel
+//!     }
+//!     fn best_mut(&mut self) -> &mut (f64, f64) {
+//!         &mut self.best
+//!     }
+//! }
+//!
+//! fn main() {
+//!     let factory = |seed: usize| {
+//!         let rng = StdRng::from_seed(&[seed][..]);
+//!         pso::PSO::<Particle, _>::new_with_rng(16, 0.7, 1.4, 1.4, rng)
+//!     };
+//!     let problem = bench::Problem {
+//!         optimum: 0.0,
+//!         tolerance: 1e-3,
+//!     };
+//!     let stats = bench::run_trials(factory, problem, 5, 200);
+//!
+//!     assert_eq!(stats.trials, 5);
+//!     assert!(stats.best <= 0.0 && stats.best >= stats.mean);
+//!     assert!(stats.std >= 0.0);
+//!     assert!(stats.success_rate() > 0.0 && stats.success_rate() <= 1.0);
+//!
+//!     // A swarm of 16 evaluates 16 particles initially and per step.
+//!     let evals = stats.mean_evals_to_target().unwrap();
+//!     assert!(evals >= 16.0 && evals <= 16.0 * 201.0);
+//!     assert!(stats.sp1().unwrap() >= evals);
+//! }
+//! ```
+
+use std::f64;
+
+use Optimizer;
+
+/// The Sphere function `-Σ x_i²`, negated to be maximized. Its optimum is 0, at the origin.
+pub fn sphere(x: &[f64]) -> f64 {
+    -x.iter().map(|x| x * x).sum::<f64>()
+}
+
+/// The Rastrigin function `-(10 n + Σ (x_i² - 10 cos(2π x_i)))`, negated to be maximized. Its
+/// optimum is 0, at the origin, surrounded by a regular grid of local optima. It is usually
+/// searched over `[-5.12, 5.12]` in each dimension.
+pub fn rastrigin(x: &[f64]) -> f64 {
+    let sum: f64 = x.iter()
+        .map(|x| x * x - 10.0 * (2.0 * f64::consts::PI * x).cos())
+        .sum();
+    -(10.0 * x.len() as f64 + sum)
+}
+
+/// The Rosenbrock function `-Σ (100 (x_{i+1} - x_i²)² + (1 - x_i)²)`, negated to be maximized.
+/// Its optimum is 0, at `(1, ..., 1)` at the end of a long, curved valley. It is usually searched
+/// over `[-5, 10]` in each dimension.
+pub fn rosenbrock(x: &[f64]) -> f64 {
+    -x.windows(2)
+        .map(|w| 100.0 * (w[1] - w[0] * w[0]).powi(2) + (1.0 - w[0]).powi(2))
+        .sum::<f64>()
+}
+
+/// The Ackley function `-(-20 exp(-0.2 √(Σ x_i² / n)) - exp(Σ cos(2π x_i) / n) + 20 + e)`,
+/// negated to be maximized. Its optimum is 0, at the origin, in the middle of an almost flat,
+/// rippled outer region. It is usually searched over `[-32.768, 32.768]` in each dimension.
+pub fn ackley(x: &[f64]) -> f64 {
+    let n = x.len() as f64;
+    let squares = x.iter().map(|x| x * x).sum::<f64>() / n;
+    let cosines = x.iter().map(|x| (2.0 * f64::consts::PI * x).cos()).sum::<f64>() / n;
+    20.0 * (-0.2 * squares.sqrt()).exp() + cosines.exp() - 20.0 - f64::consts::E
+}
+
+/// A benchmark function together with its known optimum and usual search box, so that success
+/// rates can be computed without restating them.
+///
+/// Like the plain functions of this module, `evaluate` is negated to be maximized.
+///
+/// # Example
+/// ```
+/// use meta_heuristics::bench::{self, BenchmarkFunction};
+///
+/// let functions: Vec<Box<dyn BenchmarkFunction>> = vec![Box::new(bench::Sphere(3)),
+///                                                       Box::new(bench::Rastrigin(3)),
+///                                                       Box::new(bench::Rosenbrock(3)),
+///                                                       Box::new(bench::Ackley(3))];
+/// for f in &functions {
+///     let x = f.optimum_location();
+///     assert!((f.evaluate(&x) - f.optimum_value()).abs() < 1e-12);
+///
+///     let (lower, upper) = f.bounds();
+///     assert_eq!((lower.len(), upper.len(), x.len()), (3, 3, 3));
+///     assert!((0..3).all(|i| lower[i] <= x[i] && x[i] <= upper[i]));
+/// }
+///
+/// let problem = bench::Problem::of(&bench::Rastrigin(3), 1e-3);
+/// assert_eq!(problem, bench::Problem { optimum: 0.0, tolerance: 1e-3 });
+/// ```
+pub trait BenchmarkFunction {
+    fn evaluate(&self, x: &[f64]) -> f64;
+    fn optimum_value(&self) -> f64;
+    fn optimum_location(&self) -> Vec<f64>;
+
+    /// Returns the lower and upper corners of the box the function is usually searched over.
+    fn bounds(&self) -> (Vec<f64>, Vec<f64>);
+}
+
+/// `sphere` in the given number of dimensions, searched over `[-5.12, 5.12]`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sphere(pub usize);
+
+/// `rastrigin` in the given number of dimensions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rastrigin(pub usize);
+
+/// `rosenbrock` in the given number of dimensions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rosenbrock(pub usize);
+
+/// `ackley` in the given number of dimensions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ackley(pub usize);
+
+impl BenchmarkFunction for Sphere {
+    fn evaluate(&self, x: &[f64]) -> f64 {
+        sphere(x)
+    }
+    fn optimum_value(&self) -> f64 {
+        0.0
+    }
+    fn optimum_location(&self) -> Vec<f64> {
+        vec![0.0; self.0]
+    }
+    fn bounds(&self) -> (Vec<f64>, Vec<f64>) {
+        (vec![-5.12; self.0], vec![5.12; self.0])
+    }
+}
+
+impl BenchmarkFunction for Rastrigin {
+    fn evaluate(&self, x: &[f64]) -> f64 {
+        rastrigin(x)
+    }
+    fn optimum_value(&self) -> f64 {
+        0.0
+    }
+    fn optimum_location(&self) -> Vec<f64> {
+        vec![0.0; self.0]
+    }
+    fn bounds(&self) -> (Vec<f64>, Vec<f64>) {
+        (vec![-5.12; self.0], vec![5.12; self.0])
+    }
+}
+
+impl BenchmarkFunction for Rosenbrock {
+    fn evaluate(&self, x: &[f64]) -> f64 {
+        rosenbrock(x)
+    }
+    fn optimum_value(&self) -> f64 {
+        0.0
+    }
+    fn optimum_location(&self) -> Vec<f64> {
+        vec![1.0; self.0]
+    }
+    fn bounds(&self) -> (Vec<f64>, Vec<f64>) {
+        (vec![-5.0; self.0], vec![10.0; self.0])
+    }
+}
+
+impl BenchmarkFunction for Ackley {
+    fn evaluate(&self, x: &[f64]) -> f64 {
+        ackley(x)
+    }
+    fn optimum_value(&self) -> f64 {
+        0.0
+    }
+    fn optimum_location(&self) -> Vec<f64> {
+        vec![0.0; self.0]
+    }
+    fn bounds(&self) -> (Vec<f64>, Vec<f64>) {
+        (vec![-32.768; self.0], vec![32.768; self.0])
+    }
+}
+
+/// The known optimum of a benchmark problem, which decides whether a trial succeeded.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Problem {
+    pub optimum: f64,
+    /// How close to `optimum` the final best eval has to be for the trial to succeed.
+    pub tolerance: f64,
+}
+
+impl Problem {
+    /// Creates the problem of reaching the known optimum value of `function` within `tolerance`.
+    pub fn of<B: BenchmarkFunction + ?Sized>(function: &B, tolerance: f64) -> Self {
+        Problem {
+            optimum: function.optimum_value(),
+            tolerance,
+        }
+    }
+}
+
+/// The outcome of one trial of `run_trials`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Trial {
+    /// The best eval at the end of the trial.
+    pub final_eval: f64,
+    /// The evaluations made until the best eval first came within the problem's tolerance of
+    /// its optimum, or `None` if it never did and the trial failed.
+    pub evals_to_target: Option<usize>,
+}
+
+/// Statistics of the trials of `run_trials`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TrialStats {
+    pub trials: usize,
+    /// The mean of the final best evals.
+    pub mean: f64,
+    pub best: f64,
+    /// The population standard deviation of the final best evals.
+    pub std: f64,
+    /// The number of trials which reached the problem's optimum within its tolerance.
+    pub successes: usize,
+    /// The evaluations to reach the optimum, summed over the successful trials.
+    pub success_evals: usize,
+}
+
+impl TrialStats {
+    /// Summarizes the outcomes of `trials`.
+    ///
+    /// # Panics
+    /// Panics if `trials` is empty.
+    ///
+    /// # Example
+    /// ```
+    /// use meta_heuristics::bench::{Trial, TrialStats};
+    ///
+    /// let trials = [Trial { final_eval: 0.0, evals_to_target: Some(300) },
+    ///               Trial { final_eval: -2.0, evals_to_target: None },
+    ///               Trial { final_eval: 0.0, evals_to_target: Some(500) },
+    ///               Trial { final_eval: -2.0, evals_to_target: None }];
+    /// let stats = TrialStats::from_trials(&trials);
+    ///
+    /// assert_eq!((stats.trials, stats.mean, stats.best, stats.std), (4, -1.0, 0.0, 1.0));
+    /// assert_eq!(stats.success_rate(), 0.5);
+    /// assert_eq!(stats.mean_evals_to_target(), Some(400.0));
+    /// assert_eq!(stats.sp1(), Some(800.0));
+    ///
+    /// let failures = TrialStats::from_trials(&trials[1..2]);
+    /// assert_eq!(failures.success_rate(), 0.0);
+    /// assert_eq!(failures.mean_evals_to_target(), None);
+    /// assert_eq!(failures.sp1(), None);
+    /// ```
+    pub fn from_trials(trials: &[Trial]) -> Self {
+        assert!(!trials.is_empty(), "no trials");
+
+        let n = trials.len() as f64;
+        let mean = trials.iter().map(|t| t.final_eval).sum::<f64>() / n;
+        let variance = trials
+            .iter()
+            .map(|t| (t.final_eval - mean).powi(2))
+            .sum::<f64>() / n;
+        let successes: Vec<usize> = trials.iter().filter_map(|t| t.evals_to_target).collect();
+
+        TrialStats {
+            trials: trials.len(),
+            mean,
+            best: trials
+                .iter()
+                .map(|t| t.final_eval)
+                .fold(f64::NEG_INFINITY, f64::max),
+            std: variance.sqrt(),
+            successes: successes.len(),
+            success_evals: successes.iter().sum(),
+        }
+    }
+
+    /// Returns the fraction of the trials which succeeded.
+    pub fn success_rate(&self) -> f64 {
+        self.successes as f64 / self.trials as f64
+    }
+
+    /// Returns the mean evaluations to reach the optimum over the successful trials, or `None`
+    /// if no trial succeeded.
+    pub fn mean_evals_to_target(&self) -> Option<f64> {
+        if self.successes == 0 {
+            None
+        } else {
+            Some(self.success_evals as f64 / self.successes as f64)
+        }
+    }
+
+    /// Returns the SP1 measure, the mean evaluations to reach the optimum divided by the
+    /// success rate, which estimates the evaluations needed to succeed once when restarting
+    /// failed trials. `None` if no trial succeeded.
+    pub fn sp1(&self) -> Option<f64> {
+        self.mean_evals_to_target().map(|evals| evals / self.success_rate())
+    }
+}
+
+/// Runs `trials` independent trials of `budget` steps each and summarizes them.
+///
+/// `factory` creates the optimizer of each trial from the trial's index, which serves as the
+/// seed of its random number generator to make the trials reproducible. `budget` counts
+/// `Optimizer::step` calls, as optimizers evaluate different numbers of candidates per step.
+///
+/// A trial succeeds once its best eval comes within the problem's tolerance of its optimum,
+/// which is checked before the first step and after every step. Its evaluations to the target
+/// are those counted by `Optimizer::eval_count` by then, including the whole step that reached
+/// it. The trial still runs its whole budget.
+///
+/// # Panics
+/// Panics if `trials` is 0.
+pub fn run_trials<O, F>(mut factory: F,
+                        problem: Problem,
+                        trials: usize,
+                        budget: usize)
+                        -> TrialStats
+    where O: Optimizer,
+          O::Eval: Into<f64>,
+          F: FnMut(usize) -> O
+{
+    assert!(trials > 0, "no trials");
+
+    let reached = |optimizer: &O| {
+        let best: f64 = optimizer.best().1.into();
+        (best - problem.optimum).abs() <= problem.tolerance
+    };
+
+    let trials: Vec<Trial> = (0..trials)
+        .map(|seed| {
+                 let mut optimizer = factory(seed);
+                 let mut evals_to_target = None;
+                 for i in 0..budget + 1 {
+                     if i > 0 {
+                         optimizer.step();
+                     }
+                     if evals_to_target.is_none() && reached(&optimizer) {
+                         evals_to_target = Some(optimizer.eval_count());
+                     }
+                 }
+                 Trial {
+                     final_eval: optimizer.best().1.into(),
+                     evals_to_target,
+                 }
+             })
+        .collect();
+
+    TrialStats::from_trials(&trials)
+}
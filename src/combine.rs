@@ -0,0 +1,110 @@
+//! Operators combining existing solutions into new ones.
+//!
+//! # Example
+//! ```
+//! extern crate meta_heuristics;
+//! extern crate rand;
+//!
+//! use meta_heuristics::{combine, pso, Candidate, RandomCandidate};
+//!
+//! #[derive(Clone, Copy)]
+//! struct Particle {
+//!     pos: f64,
+//!     vel: f64,
+//!     best: (f64, f64),
+//! }
+//!
+//! fn eval_func(x: f64) -> f64 {
+//!     1.0 - ((x - 3.0) * x + 2.0) * x * x
+//! }
+//!
+//! impl Candidate for Particle {
+//!     type Pos = f64;
+//!     type Eval = f64;
+//!
+//!     fn eval(&self) -> Self::Eval {
+//!         eval_func(self.pos)
+//!     }
+//!
+//!     fn pos(&self) -> Self::Pos {
+//!         self.pos
+//!     }
+//!     fn pos_mut(&mut self) -> &mut Self::Pos {
+//!         &mut self.pos
+//!     }
+//! }
+//!
+//! impl RandomCandidate for Particle {
+//!     fn new_random() -> Self {
+//!         use rand::{random, Closed01};
+//!
+//!         let Closed01(x) = random::<Closed01<f64>>();
+//!         let x = 4.0 * x - 1.0;
+//!         Self {
+//!             pos: x,
+//!             vel: 0.0,
+//!             best: (x, eval_func(x)),
+//!         }
+//!     }
+//! }
+//!
+//! impl pso::Particle for Particle {
+//!     fn vel(&self) -> Self::Pos {
+//!         self.vel
+//!     }
+//!     fn best(&self) -> (Self::Pos, Self::Eval) {
+//!         self.best
+//!     }
+//!     fn vel_mut(&mut self) -> &mut Self::Pos {
+//!         &mut self.vel
+//!     }
+//!     fn best_mut(&mut self) -> &mut (Self::Pos, Self::Eval) {
+//!         &mut self.best
+//!     }
+//! }
+//!
+//! fn main() {
+//!     let a = Particle { pos: 0.0, vel: 0.0, best: (0.0, eval_func(0.0)) };
+//!     let b = Particle { pos: 2.5, vel: 0.0, best: (2.5, eval_func(2.5)) };
+//!
+//!     // The maximum near x = 1.64 lies between the two endpoints.
+//!     let c = combine::path_relink(&a, &b, 99);
+//!     assert!((c.pos - 1.65).abs() < 0.03);
+//!     assert!(eval_func(c.pos) > eval_func(a.pos).max(eval_func(b.pos)));
+//! }
+//! ```
+
+use Candidate;
+
+/// Path relinking: evaluates `samples` evenly spaced points strictly between the positions of
+/// `a` and `b`, along with `a` and `b` themselves, and returns the best of them.
+///
+/// The intermediate candidates are clones of `a` moved to the sampled positions. Ties are
+/// resolved in favor of the point closest to `a`.
+pub fn path_relink<T>(a: &T, b: &T, samples: usize) -> T
+    where T: Candidate + Clone
+{
+    let (pos_a, pos_b) = (a.pos(), b.pos());
+
+    let mut best = a.clone();
+    let mut best_eval = best.eval();
+
+    for i in 1..samples + 2 {
+        let candidate = if i == samples + 1 {
+            b.clone()
+        } else {
+            let t = i as f64 / (samples + 1) as f64;
+            let mut candidate = a.clone();
+            *candidate.pos_mut() = pos_a.clone() + (pos_b.clone() - pos_a.clone()) * t;
+            candidate
+        };
+
+        let e = candidate.eval();
+        if e > best_eval {
+            best = candidate;
+            best_eval = e;
+        }
+    }
+
+    best
+}
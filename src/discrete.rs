@@ -0,0 +1,123 @@
+//! Positions on the integer lattice, for problems with integer decision variables.
+//!
+//! # Example
+//! ```
+//! extern crate meta_heuristics;
+//! extern crate rand;
+//!
+//! use meta_heuristics::{pso, Candidate, RandomCandidate};
+//! use meta_heuristics::discrete::IntPos;
+//! use rand::Rng;
+//!
+//! #[derive(Clone)]
+//! struct Particle {
+//!     pos: IntPos,
+//!     vel: IntPos,
+//!     best: (IntPos, i64),
+//! }
+//!
+//! // An integer quadratic peaking at (3, -2).
+//! fn eval_func(x: &IntPos) -> i64 {
+//!     -(x.0[0] - 3).pow(2) - (x.0[1] + 2).pow(2)
+//! }
+//!
+//! impl Candidate for Particle {
+//!     type Pos = IntPos;
+//!     type Eval = i64;
+//!
+//!     fn eval(&self) -> i64 {
+//!         eval_func(&self.pos)
+//!     }
+//!     fn pos(&self) -> IntPos {
+//!         self.pos.clone()
+//!     }
+//!     fn pos_mut(&mut self) -> &mut IntPos {
+//!         &mut self.pos
+//!     }
+//!     fn dim(&self) -> usize {
+//!         2
+//!     }
+//! }
+//!
+//! impl RandomCandidate for Particle {
+//!     fn new_random() -> Self {
+//!         let mut rng = rand::thread_rng();
+//!         let pos = IntPos((0..2).map(|_| rng.gen_range(-10, 11)).collect());
+//!         let vel = IntPos((0..2).map(|_| rng.gen_range(-2, 3)).collect());
+//!         let e = eval_func(&pos);
+//!         Self { pos: pos.clone(), vel, best: (pos, e) }
+//!     }
+//! }
+//!
+//! impl pso::Particle for Particle {
+//!     fn vel(&self) -> IntPos {
+//!         self.vel.clone()
+//!     }
+//!     fn best(&self) -> (IntPos, i64) {
+//!         self.best.clone()
+//!     }
+//!     fn vel_mut(&mut self) -> &mut IntPos {
+//!         &mut self.vel
+//!     }
+//!     fn best_mut(&mut self) -> &mut (IntPos, i64) {
+//!         &mut self.best
+//!     }
+//! }
+//!
+//! fn main() {
+//!     let mut pso: pso::PSO<Particle> = pso::PSO::new(24, 0.7, 1.5, 1.5);
+//!     for _ in 0..100 {
+//!         pso.update();
+//!     }
+//!
+//!     let (best, e) = pso.best();
+//!     assert_eq!(best.pos, IntPos(vec![3, -2]));
+//!     assert_eq!(e, 0);
+//! }
+//! ```
+
+use std::ops;
+
+/// A position with integer components, whose arithmetic stays on the integer lattice.
+///
+/// Addition and subtraction are exact and componentwise. Scaling by an `f64` rounds each
+/// product to the nearest integer, with halves rounded away from zero as `f64::round` does, so
+/// every intermediate result of a continuous update such as PSO's is snapped to the lattice.
+/// Small scaled terms may therefore round to zero.
+///
+/// Both operands of an addition or a subtraction must have the same number of components.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IntPos(pub Vec<i64>);
+
+impl ops::Add for IntPos {
+    type Output = IntPos;
+
+    fn add(self, rhs: IntPos) -> IntPos {
+        debug_assert_eq!(self.0.len(), rhs.0.len());
+        IntPos(self.0.iter().zip(&rhs.0).map(|(a, b)| a + b).collect())
+    }
+}
+
+impl ops::Sub for IntPos {
+    type Output = IntPos;
+
+    fn sub(self, rhs: IntPos) -> IntPos {
+        debug_assert_eq!(self.0.len(), rhs.0.len());
+        IntPos(self.0.iter().zip(&rhs.0).map(|(a, b)| a - b).collect())
+    }
+}
+
+impl ops::Mul<f64> for IntPos {
+    type Output = IntPos;
+
+    /// # Example
+    /// ```
+    /// use meta_heuristics::discrete::IntPos;
+    ///
+    /// assert_eq!(IntPos(vec![3, -3, 1]) * 0.5, IntPos(vec![2, -2, 1]));
+    /// assert_eq!(IntPos(vec![4, 1]) * 0.3, IntPos(vec![1, 0]));
+    /// ```
+    fn mul(self, rhs: f64) -> IntPos {
+        IntPos(self.0.iter().map(|&a| (a as f64 * rhs).round() as i64).collect())
+    }
+}
@@ -0,0 +1,149 @@
+//! Drivers running an optimizer to completion.
+//!
+//! # Example
+//! ```
+//! extern crate meta_heuristics;
+//! extern crate rand;
+//!
+//! use meta_heuristics::{bench, driver, pso, Candidate, RandomCandidate, SeededCandidate};
+//!
+//! #[derive(Clone, Copy)]
+//! struct Particle {
+//!     pos: f64,
+//!     vel: f64,
+//!     best: (f64, f64),
+//! }
+//!
+//! impl Candidate for Particle {
+//!     type Pos = f64;
+//!     type Eval = f64;
+//!
+//!     fn eval(&self) -> f64 {
+//!         bench::rastrigin(&[self.pos])
+//!     }
+//!     fn pos(&self) -> f64 {
+//!         self.pos
+//!     }
+//!     fn pos_mut(&mut self) -> &mut f64 {
+//!         &mut self.pos
+//!     }
+//! }
+//!
+//! impl RandomCandidate for Particle {
+//!     fn new_random() -> Self {
+//!         Self::new_random_seeded(rand::random())
+//!     }
+//! }
+//!
+//! impl SeededCandidate for Particle {
+//!     fn new_random_seeded(seed: u64) -> Self {
+//!         use rand::{Closed01, Rng};
+//!
+//!         let Closed01(x) = meta_heuristics::seeded_rng(seed).gen::<Closed01<f64>>();
+//!         let x = 10.24 * x - 5.12;
+//!         Self { pos: x, vel: 0.0, best: (x, bench::rastrigin(&[x])) }
+//!     }
+//! }
+//!
+//! impl pso::Particle for Particle {
+//!     fn vel(&self) -> f64 {
+//!         self.vel
+//!     }
+//!     fn best(&self) -> (f64, f64) {
+//!         self.best
+//!     }
+//!     fn vel_mut(&mut self) -> &mut f64 {
+//!         &mut self.vel
+//!     }
+//!     fn best_mut(&mut self) -> &mut (f64, f64) {
+//!         &mut self.best
+//!     }
+//! }
+//!
+//! fn main() {
+//!     // A small, quickly contracting swarm settles in whichever optimum of Rastrigin it
+//!     // happens to start near. Seeding makes the runs, restarts included, reproducible.
+//!     let swarm = |seed| pso::PSO::<Particle, _>::new_seeded(3, 0.4, 0.6, 0.6, seed);
+//!
+//!     let single: Vec<f64> = (0..20)
+//!         .map(|seed| driver::with_restarts(&mut swarm(seed), 400, 25, 0).1)
+//!         .collect();
+//!     let restarted: Vec<f64> = (0..20)
+//!         .map(|seed| driver::with_restarts(&mut swarm(seed), 400, 25, 15).1)
+//!         .collect();
+//!
+//!     let mean = |evals: &[f64]| evals.iter().sum::<f64>() / evals.len() as f64;
+//!     assert!(mean(&restarted) > mean(&single));
+//!     assert!(mean(&restarted) > -0.5);
+//! }
+//! ```
+
+use std::cmp;
+
+use {Candidate, Optimizer};
+use archive::Archive;
+
+/// Steps `optimizer` up to `max_iters` times in total, resetting it by `Optimizer::reset`
+/// whenever the best eval of the current run has not improved for `patience` consecutive
+/// steps, at most `max_restarts` times. Returns the best solution seen over all runs and its
+/// eval. Evals are compared by `Optimizer::is_better`.
+///
+/// After the last restart, the optimizer runs until the step budget is spent.
+///
+/// What a reset does is up to the optimizer. The population-based ones re-randomize their
+/// populations, but `SimulatedAnnealing` reheats from its best solution, so its restarts
+/// continue from there rather than starting independent runs.
+pub fn with_restarts<O>(optimizer: &mut O,
+                        max_iters: usize,
+                        patience: usize,
+                        max_restarts: usize)
+                        -> (O::Solution, O::Eval)
+    where O: Optimizer,
+          O::Eval: Clone + PartialOrd,
+          O::Solution: Candidate<Eval = O::Eval> + Clone
+{
+    let mut archive = Archive::new();
+    let (best, mut run_best) = optimizer.best();
+    archive.offer(&best, &run_best);
+
+    let (mut stagnation, mut restarts) = (0, 0);
+    for _ in 0..max_iters {
+        optimizer.step();
+
+        let (best, e) = optimizer.best();
+        archive.offer_by(&best, &e, |a, b| compare(optimizer, a, b));
+        if optimizer.is_better(&e, &run_best) {
+            run_best = e;
+            stagnation = 0;
+        } else {
+            stagnation += 1;
+        }
+
+        if stagnation >= patience && restarts < max_restarts {
+            optimizer.reset();
+            restarts += 1;
+
+            let (best, e) = optimizer.best();
+            archive.offer_by(&best, &e, |a, b| compare(optimizer, a, b));
+            run_best = e;
+            stagnation = 0;
+        }
+    }
+
+    let (best, e) = archive.best().expect("the initial best is archived");
+    (best.clone(), e.clone())
+}
+
+/// Orders two evals by `Optimizer::is_better`, for `Archive::offer_by`.
+fn compare<O>(optimizer: &O, a: &O::Eval, b: &O::Eval) -> cmp::Ordering
+    where O: Optimizer,
+          O::Eval: PartialOrd
+{
+    if optimizer.is_better(a, b) {
+        cmp::Ordering::Greater
+    } else if optimizer.is_better(b, a) {
+        cmp::Ordering::Less
+    } else {
+        cmp::Ordering::Equal
+    }
+}
@@ -0,0 +1,87 @@
+//! Errors reported by the optimizers.
+//!
+//! # Example
+//! ```
+//! use meta_heuristics::error::MetaHeuristicsError;
+//!
+//! let err = MetaHeuristicsError::InvalidParameter {
+//!     name: "absorption",
+//!     value: -1.0,
+//! };
+//! assert_eq!(err.to_string(), "invalid value -1 for parameter `absorption`");
+//!
+//! let err = MetaHeuristicsError::EmptyPopulation;
+//! assert_eq!(err.to_string(), "population must contain at least one individual");
+//! ```
+
+use std::{error, fmt};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MetaHeuristicsError {
+    /// An optimizer was asked to work on a population with no individuals.
+    EmptyPopulation,
+    /// A coefficient was out of its valid range, or not a number.
+    InvalidParameter { name: &'static str, value: f64 },
+    /// An objective evaluation produced NaN or an infinity.
+    NonFiniteEval,
+    /// An objective evaluation was zero or negative where a feature such as fitness sharing
+    /// derates evals by division.
+    NonPositiveEval,
+    /// A setting was requested which cannot be combined with another one already in effect.
+    IncompatibleSettings { setting: &'static str, with: &'static str },
+    /// A setting derived from the search bounds was requested before the bounds were set.
+    BoundsNotSet,
+    /// A source of candidates ran out before supplying the requested population.
+    InsufficientCandidates { required: usize, supplied: usize },
+    /// A neighbor topology listed the neighbors of a different number of particles than the
+    /// swarm has.
+    TopologyMismatch { particles: usize, lists: usize },
+    /// A neighbor topology gave a particle no neighbors, or a neighbor index out of range.
+    InvalidNeighbors { particle: usize },
+    /// Positions were to be plotted which are neither one- nor two-dimensional, or not all of
+    /// the same dimension.
+    UnsupportedDimension { dim: usize },
+}
+
+impl fmt::Display for MetaHeuristicsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            MetaHeuristicsError::EmptyPopulation => {
+                write!(f, "population must contain at least one individual")
+            }
+            MetaHeuristicsError::InvalidParameter { name, value } => {
+                write!(f, "invalid value {} for parameter `{}`", value, name)
+            }
+            MetaHeuristicsError::NonFiniteEval => {
+                write!(f, "evaluation produced a non-finite value")
+            }
+            MetaHeuristicsError::NonPositiveEval => {
+                write!(f, "evaluation produced a value which is not positive")
+            }
+            MetaHeuristicsError::IncompatibleSettings { setting, with } => {
+                write!(f, "{} cannot be combined with {}", setting, with)
+            }
+            MetaHeuristicsError::BoundsNotSet => write!(f, "search bounds have not been set"),
+            MetaHeuristicsError::InsufficientCandidates { required, supplied } => {
+                write!(f,
+                       "{} candidates were required, but only {} were supplied",
+                       required,
+                       supplied)
+            }
+            MetaHeuristicsError::TopologyMismatch { particles, lists } => {
+                write!(f,
+                       "topology lists the neighbors of {} particles, but the swarm has {}",
+                       lists,
+                       particles)
+            }
+            MetaHeuristicsError::InvalidNeighbors { particle } => {
+                write!(f, "the neighbors of particle {} are empty or out of range", particle)
+            }
+            MetaHeuristicsError::UnsupportedDimension { dim } => {
+                write!(f, "cannot plot positions of dimension {}", dim)
+            }
+        }
+    }
+}
+
+impl error::Error for MetaHeuristicsError {}
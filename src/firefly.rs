@@ -5,6 +5,7 @@
 //! extern crate meta_heuristics;
 //! extern crate rand;
 //!
+//! use meta_heuristics::{Candidate, RandomCandidate};
 //! use meta_heuristics::firefly::{self, Firefly};
 //!
 //! #[derive(Clone, Copy)]
@@ -16,10 +17,23 @@
 //!     1.0 - ((x - 3.0) * x + 2.0) * x * x
 //! }
 //!
-//! impl firefly::Firefly for Particle {
+//! impl Candidate for Particle {
 //!     type Pos = f64;
 //!     type Eval = f64;
 //!
+//!     fn eval(&self) -> Self::Eval {
+//!         eval_func(self.pos)
+//!     }
+//!
+//!     fn pos(&self) -> Self::Pos {
+//!         self.pos
+//!     }
+//!     fn pos_mut(&mut self) -> &mut Self::Pos {
+//!         &mut self.pos
+//!     }
+//! }
+//!
+//! impl RandomCandidate for Particle {
 //!     fn new_random() -> Self {
 //!         use rand::{random, Closed01};
 //!
@@ -27,21 +41,12 @@
 //!        let x = 4.0 * x - 1.5;
 //!        Self { pos: x }
 //!     }
+//! }
 //!
-//!     fn eval(&self) -> Self::Eval {
-//!         eval_func(self.pos)
-//!     }
-//!
+//! impl firefly::Firefly for Particle {
 //!     fn distance(&self, rhs: &Self) -> f64 {
 //!         (self.pos - rhs.pos).abs()
 //!     }
-//!
-//!     fn pos(&self) -> Self::Pos {
-//!         self.pos
-//!     }
-//!     fn pos_mut(&mut self) -> &mut Self::Pos {
-//!         &mut self.pos
-//!     }
 //! }
 //!
 //! fn main() {
@@ -58,66 +63,1101 @@
 //! }
 //! ```
 
-use std::{ops, mem};
+use std::{cmp, fmt, mem, ops, slice};
+use std::sync::Arc;
+use rand::{self, Closed01, Isaac64Rng, Rng, ThreadRng};
 
-pub trait Firefly {
-    type Pos: Copy + ops::Add<Output = Self::Pos> + ops::Sub<Output = Self::Pos> + ops::Mul<f64, Output = Self::Pos>;
-    type Eval: Copy + PartialOrd;
+use {Candidate, Optimizer, RandomCandidate, SeededCandidate};
+use error::MetaHeuristicsError;
+use problem::{MetricProblem, ProblemCandidate};
 
-    fn new_random() -> Self;
-    fn eval(&self) -> Self::Eval;
+pub trait Firefly: Candidate {
     fn distance(&self, rhs: &Self) -> f64;
+}
+
+/// A firefly which can draw random displacements, required for the randomization (alpha) term
+/// of the movement and for `FireflyAlg::set_min_step`.
+pub trait RandomStep: Firefly {
+    /// Returns a random displacement whose components are uniformly distributed in
+    /// `[-scale / 2, scale / 2]`.
+    fn random_step<R: Rng>(scale: f64, rng: &mut R) -> Self::Pos;
+}
+
+/// A firefly with a brightness of its own, required by `FireflyAlg::set_brightness`.
+pub trait Brightness: Firefly {
+    /// Returns the brightness deciding which fireflies attract which, when it should differ
+    /// from the eval, such as a scaled or rank-based transform of it. The eval still decides
+    /// the best firefly and the replacement policy.
+    fn brightness(&self) -> f64;
+}
 
-    fn pos(&self) -> Self::Pos;
-    fn pos_mut(&mut self) -> &mut Self::Pos;
+/// A firefly which can draw random displacements scaled per component, required by
+/// `FireflyAlg::set_scaled_alpha`.
+pub trait ScaledStep: RandomStep {
+    /// Returns a random displacement whose `i`-th component is uniformly distributed in
+    /// `[-range_i / 2, range_i / 2]`, used for the alpha term instead of
+    /// `RandomStep::random_step`.
+    fn random_step_in<R: Rng>(range: &Self::Pos, rng: &mut R) -> Self::Pos;
 }
 
-pub struct FireflyAlg<T: Firefly + Clone> {
+/// How a firefly's moved position replaces its previous one at the end of `update`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReplacementPolicy {
+    /// Always take the moved position, even if it is worse.
+    Always,
+    /// Keep the previous position if the moved one evaluates worse.
+    Greedy,
+}
+
+pub struct FireflyAlg<T: Firefly + Clone, R: Rng = ThreadRng> {
     fireflies: Vec<(T, T::Eval)>,
+    sampler: ::Sampler<T>,
     beta: f64,
     absorption: f64,
+    alpha: f64,
+    adaptive_absorption: Option<AdaptiveAbsorption<T::Eval>>,
+    replacement: ReplacementPolicy,
+    move_on_tie: bool,
+    brightness: Option<fn(&T) -> f64>,
+    bounds: Option<(T::Pos, T::Pos)>,
+    scaled_alpha: Option<RandomStepIn<T::Pos, R>>,
+    eval_count: usize,
+    moves_accepted: usize,
+    moves_rejected: usize,
+    plateau_alpha: Option<f64>,
+    plateau_updates: usize,
+    random_steps: Option<RandomSteps<T::Pos, R>>,
+    min_step: f64,
+    min_separation: f64,
+    decay_iters: Option<usize>,
+    time_varying: bool,
+    // Formats the best eval in the trace of `update`, set with `FireflyAlg::set_log_best_eval`.
+    #[cfg(feature = "log")]
+    log_eval: Option<fn(&T::Eval) -> String>,
+    iteration: usize,
+    rng: R,
+}
+
+/// The largest fraction of the regular attraction applied between equally bright fireflies.
+const TIE_SCALE: f64 = 0.1;
+
+struct AdaptiveAbsorption<E> {
+    low: f64,
+    high: f64,
+    initial_spread: f64,
+    to_f64: fn(&E) -> f64,
+}
+
+/// A random displacement within the given range, captured from `ScaledStep` by
+/// `FireflyAlg::set_scaled_alpha`.
+type RandomStepIn<P, R> = fn(&P, &mut R) -> P;
+
+/// The moves of `RandomStep`, captured by the setters of the alpha term and the minimum step.
+struct RandomSteps<P, R> {
+    /// `apply_alpha`, for the alpha term.
+    apply_alpha: fn(&mut P, f64, &mut R),
+    /// `RandomStep::random_step`, for the direction of the minimum step.
+    random_step: fn(f64, &mut R) -> P,
 }
 
-impl<T: Firefly + Clone> FireflyAlg<T> {
+fn random_steps<T: RandomStep, R: Rng>() -> RandomSteps<T::Pos, R> {
+    RandomSteps {
+        apply_alpha: apply_alpha::<T, R>,
+        random_step: T::random_step::<R>,
+    }
+}
+
+impl<T: RandomCandidate + Firefly + Clone> FireflyAlg<T, ThreadRng> {
     pub fn new(fireflies_num: usize, beta: f64, absorption: f64) -> Self {
-        let mut fireflies = Vec::with_capacity(fireflies_num);
-        for _ in 0..fireflies_num {
-            let ff = T::new_random();
-            let e = ff.eval();
-            fireflies.push((ff, e));
+        let fireflies = ::build_population(fireflies_num, |_| T::new_random());
+
+        Self::from_fireflies(fireflies, beta, absorption)
+    }
+
+    /// Creates the algorithm with opposition-based learning: draws `fireflies_num` fireflies
+    /// with `T::new_random()`, mirrors each one through the center of the box between `lower`
+    /// and `upper` to its opposite `lower + upper - x`, and keeps the brightest `fireflies_num` of
+    /// both. This costs twice the evaluations of `new`, and the initial best is never worse than
+    /// that of the random fireflies.
+    ///
+    /// `T::new_random()` should sample within the box, so that the opposites stay in it too. The
+    /// box does not bound the search; use `set_bounds` for that.
+    pub fn new_obl(fireflies_num: usize,
+                   lower: T::Pos,
+                   upper: T::Pos,
+                   beta: f64,
+                   absorption: f64)
+                   -> Self {
+        let mut pool = Vec::with_capacity(2 * fireflies_num);
+        for ff in ::build_population(fireflies_num, |_| T::new_random()) {
+            let mut opposite = ff.clone();
+            *opposite.pos_mut() = lower.clone() + upper.clone() - ff.pos();
+            for ff in [ff, opposite] {
+                let e = ff.eval();
+                pool.push((ff, e));
+            }
         }
+        pool.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(cmp::Ordering::Equal));
+        pool.truncate(fireflies_num);
+
+        let mut ff = Self::from_evaluated(pool,
+                                          ::Sampler::Random(T::new_random),
+                                          beta,
+                                          absorption,
+                                          rand::thread_rng());
+        ff.eval_count = 2 * fireflies_num;
+        ff
+    }
+
+    /// Creates the algorithm from an existing population, evaluating each firefly.
+    pub fn from_fireflies(fireflies: Vec<T>, beta: f64, absorption: f64) -> Self {
+        Self::from_fireflies_with_rng(fireflies, beta, absorption, rand::thread_rng())
+    }
+
+    /// Creates the algorithm from the population of another optimizer, as returned by
+    /// `Optimizer::population`, re-evaluating each solution, with the randomization coefficient
+    /// `alpha`. This hands the result of one algorithm over to another in hybrid pipelines.
+    ///
+    /// Returns an error if `alpha` is negative or NaN.
+    pub fn from_optimizer<O>(source: &O,
+                             beta: f64,
+                             absorption: f64,
+                             alpha: f64)
+                             -> Result<Self, MetaHeuristicsError>
+        where O: Optimizer<Solution = T>,
+              T: RandomStep
+    {
+        let mut ff = Self::from_fireflies(source.population(), beta, absorption);
+        ff.set_alpha(alpha)?;
+        Ok(ff)
+    }
+}
+
+impl<T: Firefly + Clone> FireflyAlg<T, ThreadRng> {
+    /// Creates the algorithm with fireflies drawn from `sampler` instead of `T::new_random()`.
+    ///
+    /// The sampler is kept and also used by `reset`, so `T` need not implement
+    /// `RandomCandidate` at all.
+    pub fn new_with_sampler<F>(fireflies_num: usize,
+                               mut sampler: F,
+                               beta: f64,
+                               absorption: f64)
+                               -> Self
+        where F: FnMut() -> T + Send + 'static
+    {
+        let fireflies = ::build_population(fireflies_num, |_| {
+            let ff = sampler();
+            let e = ff.eval();
+            (ff, e)
+        });
+
+        Self::from_evaluated(fireflies,
+                             ::Sampler::Closure(Box::new(sampler)),
+                             beta,
+                             absorption,
+                             rand::thread_rng())
+    }
+}
+
+impl<T: SeededCandidate + Firefly + Clone> FireflyAlg<T, Isaac64Rng> {
+    /// Creates the algorithm fully determined by `seed`, for reproducible examples and tests
+    /// without managing a random number generator.
+    ///
+    /// The generator of `seeded_rng(seed)` draws the seed of each firefly for
+    /// `SeededCandidate::new_random_seeded`, then the seed of the sampler, and then the random
+    /// steps. The sampler seeds the fireflies created later, by `reset`, from a generator of its
+    /// own.
+    pub fn new_seeded(fireflies_num: usize, beta: f64, absorption: f64, seed: u64) -> Self
+        where T: 'static
+    {
+        let mut rng = ::seeded_rng(seed);
+        let fireflies = ::build_population(fireflies_num, |_| {
+            let ff = T::new_random_seeded(rng.next_u64());
+            let e = ff.eval();
+            (ff, e)
+        });
+        let sampler = ::Sampler::seeded(rng.next_u64());
+
+        Self::from_evaluated(fireflies, sampler, beta, absorption, rng)
+    }
+}
+
+impl<P> FireflyAlg<ProblemCandidate<P>, ThreadRng>
+    where P: MetricProblem + Send + Sync + 'static
+{
+    /// Creates the algorithm optimizing `problem`, with the fireflies at solutions drawn from
+    /// `generator`. The fireflies' distances are those of `MetricProblem::distance`.
+    ///
+    /// # Example
+    /// ```
+    /// extern crate meta_heuristics;
+    /// extern crate rand;
+    ///
+    /// use meta_heuristics::Optimizer;
+    /// use meta_heuristics::firefly::FireflyAlg;
+    /// use meta_heuristics::problem::{MetricProblem, Problem};
+    /// use rand::Rng;
+    ///
+    /// // A parabola peaking at a given point.
+    /// struct Peak(f64);
+    ///
+    /// impl Problem for Peak {
+    ///     type Solution = f64;
+    ///     type Eval = f64;
+    ///
+    ///     fn evaluate(&self, x: &f64) -> f64 {
+    ///         -(x - self.0) * (x - self.0)
+    ///     }
+    /// }
+    ///
+    /// impl MetricProblem for Peak {
+    ///     fn distance(&self, a: &f64, b: &f64) -> f64 {
+    ///         (a - b).abs()
+    ///     }
+    /// }
+    ///
+    /// fn main() {
+    ///     for &peak in &[-1.0, 2.0] {
+    ///         // Without an alpha step, seeding the generator makes the run reproducible.
+    ///         let mut rng = meta_heuristics::seeded_rng(42);
+    ///         let generator = move || 6.0 * rng.gen::<f64>() - 3.0;
+    ///         let mut ff = FireflyAlg::new_with_problem(16, Peak(peak), generator, 1.0, 0.5);
+    ///         for _ in 0..50 {
+    ///             ff.update();
+    ///         }
+    ///         assert!((ff.best().0.solution() - peak).abs() < 0.1);
+    ///     }
+    /// }
+    /// ```
+    pub fn new_with_problem<F>(fireflies_num: usize,
+                               problem: P,
+                               mut generator: F,
+                               beta: f64,
+                               absorption: f64)
+                               -> Self
+        where F: FnMut() -> P::Solution + Send + 'static
+    {
+        let problem = Arc::new(problem);
+        let sampler = move || ProblemCandidate::new(generator(), problem.clone());
+        Self::new_with_sampler(fireflies_num, sampler, beta, absorption)
+    }
+}
+
+impl<T, R> FireflyAlg<T, R>
+    where T: Firefly + Clone,
+          R: Rng
+{
+    /// Creates the algorithm like `new`, but drawing the random steps from `rng`.
+    pub fn new_with_rng(fireflies_num: usize, beta: f64, absorption: f64, rng: R) -> Self
+        where T: RandomCandidate
+    {
+        let fireflies = ::build_population(fireflies_num, |_| T::new_random());
+
+        Self::from_fireflies_with_rng(fireflies, beta, absorption, rng)
+    }
 
+    /// Creates the algorithm like `from_fireflies`, but drawing the random steps from `rng`.
+    pub fn from_fireflies_with_rng(fireflies: Vec<T>, beta: f64, absorption: f64, rng: R) -> Self
+        where T: RandomCandidate
+    {
+        let fireflies = fireflies
+            .into_iter()
+            .map(|ff| {
+                     let e = ff.eval();
+                     (ff, e)
+                 })
+            .collect();
+
+        Self::from_evaluated(fireflies, ::Sampler::Random(T::new_random), beta, absorption, rng)
+    }
+
+    fn from_evaluated(fireflies: Vec<(T, T::Eval)>,
+                      sampler: ::Sampler<T>,
+                      beta: f64,
+                      absorption: f64,
+                      rng: R)
+                      -> Self {
         Self {
+            eval_count: fireflies.len(),
             fireflies,
+            sampler,
             beta,
             absorption,
+            alpha: 0.0,
+            adaptive_absorption: None,
+            replacement: ReplacementPolicy::Always,
+            move_on_tie: false,
+            brightness: None,
+            bounds: None,
+            scaled_alpha: None,
+            moves_accepted: 0,
+            moves_rejected: 0,
+            plateau_alpha: None,
+            plateau_updates: 0,
+            random_steps: None,
+            min_step: 0.0,
+            min_separation: 0.0,
+            decay_iters: None,
+            time_varying: false,
+            #[cfg(feature = "log")]
+            log_eval: None,
+            iteration: 0,
+            rng,
         }
     }
 
+    /// Moves every firefly towards the brighter ones, then applies the randomization term.
+    ///
+    /// The attractions on a firefly are summed into a single accumulator in ascending index
+    /// order of the attracting fireflies, and the sum is added to the position last. Floating
+    /// point operations are not reordered by the compiler, so given the same population and
+    /// random number generator seed, the result is bit-for-bit identical across build profiles.
+    ///
+    /// With the `log` feature, each update logs its iteration, evaluation count and number of
+    /// rejected moves at the trace level, and the best eval too once `set_log_best_eval` is
+    /// enabled, like `PSO::update`. `reset` logs at the debug level.
     pub fn update(&mut self) {
+        let iteration = self.iteration + 1;
+        if self.time_varying {
+            // The cached evals were evaluated at an earlier iteration.
+            for ff in &mut self.fireflies {
+                ff.1 = ff.0.eval_at(iteration);
+            }
+            self.eval_count += self.fireflies.len();
+        }
+
+        self.absorption = self.next_absorption();
+        let brightness = self.brightness_values();
+
+        let equal = |a: Option<cmp::Ordering>| a == Some(cmp::Ordering::Equal);
+        let all_equal = match (brightness.as_ref(), self.fireflies.first()) {
+            (Some(b), Some(_)) => b.iter().all(|x| equal(x.partial_cmp(&b[0]))),
+            (None, Some(first)) => {
+                self.fireflies.iter().all(|ff| equal(ff.1.partial_cmp(&first.1)))
+            }
+            (_, None) => false,
+        };
+        let alpha = match self.plateau_alpha {
+            Some(plateau_alpha) if all_equal => {
+                self.plateau_updates += 1;
+                plateau_alpha
+            }
+            _ => self.alpha,
+        } * self.decay_scale();
+
         let mut new_fireflies = self.fireflies.clone();
-        let fireflies_num = self.fireflies.len();
-
-        for i in 0..fireflies_num {
-            for j in 0..fireflies_num {
-                let ff_i = &self.fireflies[i];
-                let ff_j = &self.fireflies[j];
-                if ff_j.1 > ff_i.1 {
-                    let dist = ff_i.0.distance(&ff_j.0);
-                    let pos_diff = (ff_j.0.pos() - ff_i.0.pos()) * self.beta *
-                                   (-dist * dist * self.absorption).exp();
-                    let new_pos = ff_i.0.pos() + pos_diff;
-                    *new_fireflies[i].0.pos_mut() = new_pos;
-                    let new_e = new_fireflies[i].0.eval();
-                    new_fireflies[i].1 = new_e;
+        let mut moves = 0;
+
+        for (i, (ff_i, new_ff)) in self.fireflies.iter().zip(&mut new_fireflies).enumerate() {
+            let rng = &mut self.rng;
+            let displacement = attraction(&self.fireflies,
+                                          i,
+                                          brightness.as_ref().map(|b| &b[..]),
+                                          self.beta,
+                                          self.absorption,
+                                          self.move_on_tie,
+                                          self.min_separation,
+                                          || {
+                                              let Closed01(r) = rng.gen::<Closed01<f64>>();
+                                              Some(TIE_SCALE * r)
+                                          });
+
+            let mut moved = false;
+            if let Some(d) = displacement {
+                *new_ff.0.pos_mut() = ff_i.0.pos() + d;
+                moved = true;
+            }
+
+            let steps = self.random_steps.as_ref();
+            if let Some(&RandomSteps { apply_alpha, .. }) = steps.filter(|_| alpha > 0.0) {
+                let mut pos = new_ff.0.pos();
+                match (&self.bounds, self.scaled_alpha) {
+                    (&Some((ref lower, ref upper)), Some(random_step_in)) => {
+                        let range = (upper.clone() - lower.clone()) * alpha;
+                        pos = pos + random_step_in(&range, &mut self.rng);
+                    }
+                    _ => apply_alpha(&mut pos, alpha, &mut self.rng),
+                }
+                *new_ff.0.pos_mut() = pos;
+                moved = true;
+            }
+
+            let short = self.min_step > 0.0 && new_ff.0.distance(&ff_i.0) < self.min_step;
+            if let Some(&RandomSteps { random_step, .. }) = steps.filter(|_| short) {
+                let direction = random_step(1.0, &mut self.rng);
+                let mut probe = ff_i.0.clone();
+                *probe.pos_mut() = ff_i.0.pos() + direction.clone();
+                let norm = probe.distance(&ff_i.0);
+                if norm > 0.0 {
+                    *new_ff.0.pos_mut() = ff_i.0.pos() + direction * (self.min_step / norm);
+                    moved = true;
+                }
+            }
+
+            if moved {
+                new_ff.1 = if self.time_varying {
+                    new_ff.0.eval_at(iteration)
+                } else {
+                    new_ff.0.eval()
+                };
+                self.eval_count += 1;
+                moves += 1;
+            }
+        }
+
+        let mut rejected = 0;
+        if self.replacement == ReplacementPolicy::Greedy {
+            for (new_ff, old_ff) in new_fireflies.iter_mut().zip(&self.fireflies) {
+                if old_ff.1 > new_ff.1 {
+                    *new_ff = old_ff.clone();
+                    rejected += 1;
                 }
             }
         }
+        self.moves_accepted = moves - rejected;
+        self.moves_rejected = rejected;
 
         mem::swap(&mut self.fireflies, &mut new_fireflies);
+        self.iteration += 1;
+        #[cfg(feature = "log")]
+        {
+            let best = match (self.log_eval, self.best_index()) {
+                (Some(format), Some(i)) => Some(format(&self.fireflies[i].1)),
+                _ => None,
+            };
+            match best {
+                Some(best) => {
+                    trace!("iteration {}: best eval {}, {} evaluations, {} moves rejected",
+                           self.iteration,
+                           best,
+                           self.eval_count,
+                           self.moves_rejected)
+                }
+                None => {
+                    trace!("iteration {}: {} evaluations, {} moves rejected",
+                           self.iteration,
+                           self.eval_count,
+                           self.moves_rejected)
+                }
+            }
+        }
+    }
+
+    /// Returns the displacement the attraction of the brighter fireflies would apply to each
+    /// firefly in the next `update`, without moving any.
+    ///
+    /// This is the deterministic part of the move: the alpha step, the minimum step of
+    /// `set_min_step` and the random moves on ties of `set_move_on_tie` are left out, and a
+    /// firefly which no other attracts gets a zero displacement. With them disabled and
+    /// `ReplacementPolicy::Always`, each firefly moves by exactly its force.
+    pub fn forces(&self) -> Vec<T::Pos> {
+        let absorption = self.next_absorption();
+        let brightness = self.brightness_values();
+
+        (0..self.fireflies.len())
+            .map(|i| {
+                     attraction(&self.fireflies,
+                                i,
+                                brightness.as_ref().map(|b| &b[..]),
+                                self.beta,
+                                absorption,
+                                self.move_on_tie,
+                                self.min_separation,
+                                || None)
+                             .unwrap_or_else(|| self.fireflies[i].0.pos() * 0.0)
+                 })
+            .collect()
+    }
+
+    /// Returns the absorption the next `update` uses, adapted to the spread of the evals if
+    /// adaptive absorption is enabled.
+    fn next_absorption(&self) -> f64 {
+        match self.adaptive_absorption {
+            Some(ref adaptive) => {
+                let spread = eval_spread(&self.fireflies, adaptive.to_f64);
+                let ratio = if adaptive.initial_spread > 0.0 {
+                    (spread / adaptive.initial_spread).min(1.0)
+                } else {
+                    0.0
+                };
+                adaptive.high - (adaptive.high - adaptive.low) * ratio
+            }
+            None => self.absorption,
+        }
+    }
+
+    fn brightness_values(&self) -> Option<Vec<f64>> {
+        self.brightness
+            .map(|brightness| self.fireflies.iter().map(|ff| brightness(&ff.0)).collect())
+    }
+
+    /// Re-randomizes the whole population with the sampler and clears the iteration and
+    /// evaluation counters, keeping the coefficients, settings and random number generator.
+    /// Adaptive absorption, if enabled, is measured against the spread of the new population.
+    pub fn reset(&mut self) {
+        for ff in &mut self.fireflies {
+            ff.0 = self.sampler.sample();
+            ff.1 = ff.0.eval();
+        }
+        self.eval_count = self.fireflies.len();
+
+        if let Some(ref mut adaptive) = self.adaptive_absorption {
+            adaptive.initial_spread = eval_spread(&self.fireflies, adaptive.to_f64);
+        }
+        self.moves_accepted = 0;
+        self.moves_rejected = 0;
+        self.plateau_updates = 0;
+        self.iteration = 0;
+        #[cfg(feature = "log")]
+        debug!("reset the fireflies");
+    }
+
+    /// Sets how moved fireflies replace their previous positions. Defaults to
+    /// `ReplacementPolicy::Always`.
+    pub fn set_replacement_policy(&mut self, replacement: ReplacementPolicy) {
+        self.replacement = replacement;
+    }
+
+    /// Makes equally bright fireflies move towards each other too, by a random fraction of up to
+    /// a tenth of the regular attraction. Without it, fireflies on a plateau of the objective
+    /// never interact and the population stalls. Disabled by default.
+    pub fn set_move_on_tie(&mut self, move_on_tie: bool) {
+        self.move_on_tie = move_on_tie;
+    }
+
+    /// Returns the attractiveness at zero distance.
+    pub fn beta(&self) -> f64 {
+        self.beta
+    }
+
+    /// Returns the light absorption coefficient. With adaptive absorption enabled, this is the
+    /// value used by the last `update`.
+    pub fn absorption(&self) -> f64 {
+        self.absorption
+    }
+
+    /// Returns the scale of the random step added to every firefly's move. Defaults to 0.
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    /// Sets the attractiveness at zero distance. Rejects negative and NaN values.
+    pub fn set_beta(&mut self, beta: f64) -> Result<(), MetaHeuristicsError> {
+        self.beta = non_negative("beta", beta)?;
+        Ok(())
+    }
+
+    /// Sets the light absorption coefficient. Rejects negative and NaN values.
+    pub fn set_absorption(&mut self, absorption: f64) -> Result<(), MetaHeuristicsError> {
+        self.absorption = non_negative("absorption", absorption)?;
+        Ok(())
+    }
+
+    /// Returns the number of updates since construction or `reset` which started on a plateau
+    /// and took the step set with `set_plateau_alpha`.
+    pub fn plateau_updates(&self) -> usize {
+        self.plateau_updates
+    }
+
+    /// Scales the random step of each update, the regular and the plateau one alike, by
+    /// `1 - progress()`, so that it shrinks linearly from its full scale at the first update to
+    /// 0 once `max_iters` updates have been made, moving the search from exploration to
+    /// exploitation over a known budget. Not set by default.
+    pub fn set_linear_decay(&mut self, max_iters: usize) {
+        self.decay_iters = Some(max_iters);
+    }
+
+    pub fn unset_linear_decay(&mut self) {
+        self.decay_iters = None;
+    }
+
+    /// Returns the fraction of the budget of `set_linear_decay` used so far, the number of
+    /// updates made divided by `max_iters` and capped at 1, or `None` if no budget is set.
+    pub fn progress(&self) -> Option<f64> {
+        self.decay_iters.map(|max_iters| (self.iteration as f64 / max_iters.max(1) as f64).min(1.0))
+    }
+
+    /// Returns the scale of the regular random step of the next update, alpha scaled by the
+    /// linear decay if set.
+    pub fn effective_alpha(&self) -> f64 {
+        self.alpha * self.decay_scale()
+    }
+
+    fn decay_scale(&self) -> f64 {
+        1.0 - self.progress().unwrap_or(0.0)
+    }
+
+    /// Sets the comfort zone radius: fireflies closer than `min_separation` to each other, by
+    /// `Firefly::distance`, repel each other, however bright, by the displacement the attraction
+    /// would have applied reversed. This keeps the population from crowding onto a single
+    /// optimum, so that it can spread over several. `min_separation` must be non-negative, and
+    /// 0, the default, leaves pure attraction.
+    ///
+    /// The repulsion ends at the radius, beyond which the brighter firefly attracts again, so
+    /// fireflies settle around an optimum about that far apart rather than converging.
+    /// Absorption weakens the repulsion like the attraction, and only once it makes the
+    /// attraction of fireflies on other optima weak can a crowded optimum push fireflies over to
+    /// them. Repulsion vanishes between fireflies at the same position, which only the alpha
+    /// step separates; an alpha step comparable to `min_separation` moves fireflies back within
+    /// the radius as often as out of it.
+    pub fn set_min_separation(&mut self, min_separation: f64) -> Result<(), MetaHeuristicsError> {
+        self.min_separation = non_negative("min_separation", min_separation)?;
+        Ok(())
+    }
+
+    /// Sets the box between `lower` and `upper` as the search bounds, the domain which scales
+    /// the alpha term when `set_scaled_alpha` is enabled. Positions are not clamped to it.
+    pub fn set_bounds(&mut self, lower: T::Pos, upper: T::Pos) {
+        self.bounds = Some((lower, upper));
+    }
+
+    /// Removes the search bounds, which also disables scaled alpha.
+    pub fn unset_bounds(&mut self) {
+        self.bounds = None;
+        self.scaled_alpha = None;
+    }
+
+    /// Returns the search bounds, if set.
+    pub fn bounds(&self) -> Option<(T::Pos, T::Pos)> {
+        self.bounds.clone()
     }
 
     pub fn fireflies(&self) -> &Vec<(T, T::Eval)> {
         &self.fireflies
     }
+
+    /// Returns the fireflies and their cached evals for external modification.
+    ///
+    /// The cached evals are not updated when a position is changed through this; call
+    /// `reevaluate` afterwards so that `update` compares the new positions' brightness.
+    pub fn fireflies_mut(&mut self) -> &mut [(T, T::Eval)] {
+        &mut self.fireflies
+    }
+
+    /// Sets whether the objective changes over time, as `Candidate::eval_at` describes.
+    /// Disabled by default.
+    ///
+    /// When enabled, each `update` first re-evaluates every firefly's cached eval with `eval_at`
+    /// the value `iteration()` takes after the update, so that the attractions and the greedy
+    /// replacement compare evals of the same iteration, then evaluates the moved fireflies at
+    /// that iteration too. This doubles the evaluations per update. The initial population is
+    /// evaluated with `eval`, which should agree with `eval_at(0)`.
+    pub fn set_time_varying(&mut self, time_varying: bool) {
+        self.time_varying = time_varying;
+    }
+
+    /// Recomputes every firefly's cached eval from its current position.
+    pub fn reevaluate(&mut self) {
+        for ff in &mut self.fireflies {
+            ff.1 = ff.0.eval();
+        }
+        self.eval_count += self.fireflies.len();
+    }
+
+    /// Returns the number of objective evaluations made so far.
+    pub fn eval_count(&self) -> usize {
+        self.eval_count
+    }
+
+    /// Runs `update` as long as the next call cannot push `eval_count` above `max_evals`, and
+    /// returns the number of calls made.
+    ///
+    /// Each update evaluates at most one new position per firefly, however many others attract
+    /// it, so this assumes the worst case of every firefly moving, plus the re-evaluations of
+    /// `set_time_varying`.
+    pub fn run_eval_budget(&mut self, max_evals: usize) -> usize {
+        let cost = Optimizer::step_evals(self);
+
+        let mut iters = 0;
+        while self.eval_count + cost <= max_evals {
+            self.update();
+            iters += 1;
+        }
+        iters
+    }
+
+    /// Returns how many of the fireflies which moved in the last `update` took the moved
+    /// position. Fireflies move when a brighter one attracts them or the randomization term is
+    /// enabled; the rest keep their position without an attempt.
+    pub fn moves_accepted(&self) -> usize {
+        self.moves_accepted
+    }
+
+    /// Returns how many of the fireflies which moved in the last `update` kept their previous
+    /// position instead, which only `ReplacementPolicy::Greedy` does. Many rejections suggest
+    /// that the steps of `beta` or `alpha` are too aggressive.
+    pub fn moves_rejected(&self) -> usize {
+        self.moves_rejected
+    }
+
+    /// Returns the number of fireflies.
+    pub fn len(&self) -> usize {
+        self.fireflies.len()
+    }
+
+    /// Returns whether the population has no fireflies.
+    pub fn is_empty(&self) -> bool {
+        self.fireflies.is_empty()
+    }
+
+    /// Returns the number of `update` calls made so far.
+    pub fn iteration(&self) -> usize {
+        self.iteration
+    }
+
+    /// Returns the firefly with the lowest eval, and that eval.
+    ///
+    /// # Panics
+    /// Panics if there are no fireflies.
+    pub fn worst(&self) -> (&T, T::Eval) {
+        let worst = self.worst_index().expect("no fireflies");
+        let (ref ff, ref e) = self.fireflies[worst];
+        (ff, e.clone())
+    }
+
+    /// Disables adaptive absorption, keeping the absorption it last used.
+    pub fn unset_adaptive_absorption(&mut self) {
+        self.adaptive_absorption = None;
+    }
+
+    fn worst_index(&self) -> Option<usize> {
+        if self.fireflies.is_empty() {
+            return None;
+        }
+
+        let mut worst = 0;
+        for (i, ff) in self.fireflies.iter().enumerate() {
+            if ff.1 < self.fireflies[worst].1 {
+                worst = i;
+            }
+        }
+        Some(worst)
+    }
+
+    fn best_index(&self) -> Option<usize> {
+        if self.fireflies.is_empty() {
+            return None;
+        }
+
+        let mut best = 0;
+        for (i, ff) in self.fireflies.iter().enumerate() {
+            if ff.1 > self.fireflies[best].1 {
+                best = i;
+            }
+        }
+        Some(best)
+    }
+}
+
+impl<T, R> FireflyAlg<T, R>
+    where T: RandomStep + Clone,
+          R: Rng
+{
+    /// Sets the scale of the random step, which is drawn with `RandomStep::random_step`. Rejects
+    /// negative and NaN values.
+    pub fn set_alpha(&mut self, alpha: f64) -> Result<(), MetaHeuristicsError> {
+        self.alpha = non_negative("alpha", alpha)?;
+        self.random_steps = Some(random_steps::<T, R>());
+        Ok(())
+    }
+
+    /// Sets the scale of the random step taken instead of the regular alpha one in updates
+    /// which start with every firefly equally bright, as on a plateau of the objective or after
+    /// convergence. No firefly attracts another then, so without a random step the population
+    /// is frozen. Equal brightness means equal evals, or equal `Brightness::brightness` if
+    /// `set_brightness` is enabled. Not set by default. Rejects negative and NaN values.
+    ///
+    /// `plateau_updates` counts the updates which fell back to it.
+    pub fn set_plateau_alpha(&mut self, alpha: f64) -> Result<(), MetaHeuristicsError> {
+        self.plateau_alpha = Some(non_negative("alpha", alpha)?);
+        self.random_steps = Some(random_steps::<T, R>());
+        Ok(())
+    }
+
+    /// Leaves updates on a plateau to the regular alpha step again.
+    pub fn unset_plateau_alpha(&mut self) {
+        self.plateau_alpha = None;
+    }
+
+    /// Sets the minimum length of a firefly's move: whenever the attractions and the alpha step
+    /// would move a firefly by a `Firefly::distance` shorter than `min_step`, it takes a step of
+    /// exactly `min_step` in a random direction from `RandomStep::random_step` instead. This keeps
+    /// the population exploring when the attractions vanish, as they do for the brightest
+    /// firefly and over long distances under strong absorption. `min_step` must be
+    /// non-negative, and 0, the default, disables it.
+    pub fn set_min_step(&mut self, min_step: f64) -> Result<(), MetaHeuristicsError> {
+        self.min_step = non_negative("min_step", min_step)?;
+        self.random_steps = Some(random_steps::<T, R>());
+        Ok(())
+    }
+}
+
+impl<T, R> FireflyAlg<T, R>
+    where T: ScaledStep + Clone,
+          R: Rng
+{
+    /// Sets whether the alpha term is scaled by the search bounds, drawing it with
+    /// `ScaledStep::random_step_in(alpha * (upper - lower))` so that alpha means the same relative
+    /// step on differently scaled problems. Disabled by default.
+    ///
+    /// # Errors
+    /// Returns `MetaHeuristicsError::BoundsNotSet` when enabling it before `set_bounds`.
+    pub fn set_scaled_alpha(&mut self, scaled: bool) -> Result<(), MetaHeuristicsError> {
+        if scaled && self.bounds.is_none() {
+            return Err(MetaHeuristicsError::BoundsNotSet);
+        }
+        self.scaled_alpha = if scaled {
+            Some(T::random_step_in::<R>)
+        } else {
+            None
+        };
+        Ok(())
+    }
+}
+
+impl<T, R> FireflyAlg<T, R>
+    where T: Brightness + Clone,
+          R: Rng
+{
+    /// Sets whether fireflies are attracted by the brighter ones as `Brightness::brightness` ranks
+    /// them, rather than by eval. The brightness is computed once per firefly in each `update`.
+    /// Disabled by default.
+    pub fn set_brightness(&mut self, brightness: bool) {
+        self.brightness = if brightness {
+            Some(T::brightness)
+        } else {
+            None
+        };
+    }
+}
+
+#[cfg(feature = "log")]
+impl<T, R> FireflyAlg<T, R>
+    where T: Firefly + Clone,
+          T::Eval: fmt::Debug,
+          R: Rng
+{
+    /// Sets whether the trace logged by each `update` includes the best eval, formatted with
+    /// `Debug`, like `PSO::set_log_best_eval`. Disabled by default. Requires the `log` feature.
+    pub fn set_log_best_eval(&mut self, log_best: bool) {
+        self.log_eval = if log_best {
+            Some(::debug_eval::<T::Eval>)
+        } else {
+            None
+        };
+    }
+}
+
+impl<T, R> Optimizer for FireflyAlg<T, R>
+    where T: Firefly + Clone,
+          R: Rng
+{
+    type Solution = T;
+    type Eval = T::Eval;
+
+    fn step(&mut self) {
+        self.update();
+    }
+
+    /// # Panics
+    /// Panics if there are no fireflies.
+    fn best(&self) -> (T, T::Eval) {
+        let best = self.best_index().expect("no fireflies");
+        self.fireflies[best].clone()
+    }
+
+    fn replace_worst(&mut self, solution: T) {
+        let e = solution.eval();
+        self.eval_count += 1;
+        match self.worst_index() {
+            Some(worst) => self.fireflies[worst] = (solution, e),
+            None => self.fireflies.push((solution, e)),
+        }
+    }
+
+    fn population(&self) -> Vec<T> {
+        self.fireflies.iter().map(|ff| ff.0.clone()).collect()
+    }
+
+    fn reset(&mut self) {
+        FireflyAlg::reset(self);
+    }
+
+    fn eval_count(&self) -> usize {
+        FireflyAlg::eval_count(self)
+    }
+
+    /// Counts one evaluation per firefly, made if it moves, and another with `set_time_varying`.
+    fn step_evals(&self) -> usize {
+        if self.time_varying {
+            2 * self.fireflies.len()
+        } else {
+            self.fireflies.len()
+        }
+    }
+}
+
+impl<T, R> FireflyAlg<T, R>
+    where T: Firefly + Clone,
+          T::Eval: Into<f64>,
+          R: Rng
+{
+    /// Adapts the absorption coefficient to the progress of the search.
+    ///
+    /// Before each `update`, the absorption is interpolated from `low` to `high` as the spread
+    /// (maximum minus minimum) of the population's evals shrinks relative to its spread when
+    /// this was called: a population as spread out as at the start uses `low` (global search),
+    /// and a population whose evals have all converged uses `high` (local search). This
+    /// overrides the fixed absorption passed to `new` until `unset_adaptive_absorption`.
+    ///
+    /// A higher absorption shortens the range of attraction and so slows the convergence of
+    /// fireflies which are far apart; choose `high` with that in mind.
+    pub fn set_adaptive_absorption(&mut self, low: f64, high: f64) {
+        let to_f64 = ::eval_to_f64::<T::Eval>;
+        self.adaptive_absorption = Some(AdaptiveAbsorption {
+                                            low,
+                                            high,
+                                            initial_spread: eval_spread(&self.fireflies, to_f64),
+                                            to_f64,
+                                        });
+    }
+}
+
+/// Returns the move of a firefly at `from` towards a brighter one at `to`, `dist` away:
+/// `(to - from) * beta * exp(-gamma * dist^2)`.
+///
+/// # Example
+/// ```
+/// use meta_heuristics::firefly::firefly_displacement;
+///
+/// assert_eq!(firefly_displacement(&0.0, &2.0, 0.5, 0.25, 2.0), (-1f64).exp());
+/// assert_eq!(firefly_displacement(&1.0, &3.0, 0.5, 0.0, 2.0), 1.0);
+/// assert_eq!(firefly_displacement(&1.0, &1.0, 0.5, 0.25, 0.0), 0.0);
+/// ```
+pub fn firefly_displacement<P>(from: &P, to: &P, beta: f64, gamma: f64, dist: f64) -> P
+    where P: Clone + ops::Sub<Output = P> + ops::Mul<f64, Output = P>
+{
+    (to.clone() - from.clone()) * beta * (-dist * dist * gamma).exp()
+}
+
+/// Adds the randomization term, `T::random_step(alpha)`, to `pos`. This is the alpha term of
+/// `FireflyAlg::update`, unless `set_scaled_alpha` scales it to the bounds.
+pub fn apply_alpha<T: RandomStep, R: Rng>(pos: &mut T::Pos, alpha: f64, rng: &mut R) {
+    let new_pos = pos.clone() + T::random_step(alpha, rng);
+    *pos = new_pos;
+}
+
+/// Iterates over the fireflies and their evals, like `fireflies().iter()`.
+impl<'a, T, R> IntoIterator for &'a FireflyAlg<T, R>
+    where T: Firefly + Clone,
+          R: Rng
+{
+    type Item = &'a (T, T::Eval);
+    type IntoIter = slice::Iter<'a, (T, T::Eval)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.fireflies.iter()
+    }
+}
+
+/// Sums the displacements of the `i`-th firefly towards every brighter one, or returns `None`
+/// if none attracts it.
+///
+/// Every brighter firefly contributes to a single displacement applied at once, so the move
+/// does not depend on which others have already moved. The accumulation order is part of the
+/// reproducibility guarantee documented on `FireflyAlg::update`. With `move_on_tie`, equally
+/// bright fireflies attract each other, scaled by `tie_scale`, and are skipped where it returns
+/// `None`. Fireflies closer than `min_separation` to the `i`-th repel it instead, however
+/// bright.
+#[allow(clippy::too_many_arguments)]
+fn attraction<T, F>(fireflies: &[(T, T::Eval)],
+                    i: usize,
+                    brightness: Option<&[f64]>,
+                    beta: f64,
+                    absorption: f64,
+                    move_on_tie: bool,
+                    min_separation: f64,
+                    mut tie_scale: F)
+                    -> Option<T::Pos>
+    where T: Firefly,
+          F: FnMut() -> Option<f64>
+{
+    let ff_i = &fireflies[i];
+    let mut displacement: Option<T::Pos> = None;
+    for (j, ff_j) in fireflies.iter().enumerate() {
+        let order = match brightness {
+            Some(b) => b[j].partial_cmp(&b[i]),
+            None => ff_j.1.partial_cmp(&ff_i.1),
+        };
+        let tie = move_on_tie && i != j && order == Some(cmp::Ordering::Equal);
+        let crowded = min_separation > 0.0 && i != j &&
+                      ff_i.0.distance(&ff_j.0) < min_separation;
+        if order == Some(cmp::Ordering::Greater) || tie || crowded {
+            let dist = ff_i.0.distance(&ff_j.0);
+            let mut pos_diff = firefly_displacement(&ff_i.0.pos(),
+                                                    &ff_j.0.pos(),
+                                                    beta,
+                                                    absorption,
+                                                    dist);
+            if crowded {
+                pos_diff = pos_diff * -1.0;
+            } else if tie {
+                match tie_scale() {
+                    Some(scale) => pos_diff = pos_diff * scale,
+                    None => continue,
+                }
+            }
+            displacement = Some(match displacement {
+                                    Some(d) => d + pos_diff,
+                                    None => pos_diff,
+                                });
+        }
+    }
+    displacement
+}
+
+fn non_negative(name: &'static str, value: f64) -> Result<f64, MetaHeuristicsError> {
+    if value >= 0.0 {
+        Ok(value)
+    } else {
+        Err(MetaHeuristicsError::InvalidParameter { name, value })
+    }
+}
+
+fn eval_spread<T, E>(fireflies: &[(T, E)], to_f64: fn(&E) -> f64) -> f64 {
+    let mut min = f64::INFINITY;
+    let mut max = f64::NEG_INFINITY;
+    for (_, e) in fireflies {
+        let e = to_f64(e);
+        min = min.min(e);
+        max = max.max(e);
+    }
+
+    if max >= min { max - min } else { 0.0 }
+}
+
+/// Summarizes the algorithm configuration and progress rather than dumping every firefly.
+impl<T, R> fmt::Debug for FireflyAlg<T, R>
+    where T: Firefly + Clone,
+          T::Eval: fmt::Debug,
+          R: Rng
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FireflyAlg")
+            .field("fireflies", &self.fireflies.len())
+            .field("beta", &self.beta)
+            .field("absorption", &self.absorption)
+            .field("alpha", &self.alpha)
+            .field("replacement", &self.replacement)
+            .field("move_on_tie", &self.move_on_tie)
+            .field("brightness", &self.brightness.is_some())
+            .field("moves_accepted", &self.moves_accepted)
+            .field("moves_rejected", &self.moves_rejected)
+            .field("plateau_updates", &self.plateau_updates)
+            .field("min_step", &self.min_step)
+            .field("min_separation", &self.min_separation)
+            .field("iteration", &self.iteration)
+            .field("best_eval", &self.best_index().map(|i| &self.fireflies[i].1))
+            .finish()
+    }
 }
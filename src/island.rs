@@ -0,0 +1,219 @@
+//! Island model: several independent populations with periodic migration.
+//!
+//! # Example
+//! ```
+//! extern crate meta_heuristics;
+//! extern crate rand;
+//!
+//! use meta_heuristics::{firefly, island, Candidate, Optimizer, RandomCandidate};
+//!
+//! #[derive(Clone, Copy)]
+//! struct Particle {
+//!     pos: f64,
+//! }
+//!
+//! impl Candidate for Particle {
+//!     type Pos = f64;
+//!     type Eval = f64;
+//!
+//!     fn eval(&self) -> f64 {
+//!         1.0 - ((self.pos - 3.0) * self.pos + 2.0) * self.pos * self.pos
+//!     }
+//!     fn pos(&self) -> f64 {
+//!         self.pos
+//!     }
+//!     fn pos_mut(&mut self) -> &mut f64 {
+//!         &mut self.pos
+//!     }
+//! }
+//!
+//! impl RandomCandidate for Particle {
+//!     fn new_random() -> Self {
+//!         use rand::{random, Closed01};
+//!
+//!         let Closed01(x) = random::<Closed01<f64>>();
+//!         Self { pos: 4.0 * x - 1.5 }
+//!     }
+//! }
+//!
+//! impl firefly::Firefly for Particle {
+//!     fn distance(&self, rhs: &Self) -> f64 {
+//!         (self.pos - rhs.pos).abs()
+//!     }
+//! }
+//!
+//! fn main() {
+//!     let islands = (0..3).map(|_| firefly::FireflyAlg::<Particle>::new(8, 0.5, 0.2)).collect();
+//!     let mut model = island::IslandModel::new(islands, 5);
+//!
+//!     for _ in 0..20 {
+//!         model.step();
+//!     }
+//!
+//!     // Migration sends the best of each island to the next one in the ring.
+//!     let bests: Vec<f64> = model.islands().iter().map(|i| i.best().0.pos).collect();
+//!     model.migrate();
+//!     for (i, &migrant) in bests.iter().enumerate() {
+//!         let next = &model.islands()[(i + 1) % 3];
+//!         assert!(next.fireflies().iter().any(|&(p, _)| p.pos == migrant));
+//!     }
+//!
+//!     let (_, best) = model.best();
+//!     assert!(model.islands().iter().all(|i| i.best().1 <= best));
+//! }
+//! ```
+
+use std::thread;
+
+use Optimizer;
+
+/// Runs a ring of islands, each sending its best solution to the next island every `interval`
+/// steps.
+pub struct IslandModel<O: Optimizer> {
+    islands: Vec<O>,
+    interval: usize,
+    iteration: usize,
+}
+
+impl<O> IslandModel<O>
+    where O: Optimizer,
+          O::Solution: Clone,
+          O::Eval: PartialOrd
+{
+    /// Creates a model over `islands` migrating every `interval` steps. An `interval` of 0
+    /// disables automatic migration.
+    pub fn new(islands: Vec<O>, interval: usize) -> Self {
+        Self {
+            islands,
+            interval,
+            iteration: 0,
+        }
+    }
+
+    /// Steps every island once, then migrates if the migration interval has elapsed.
+    pub fn step(&mut self) {
+        for island in &mut self.islands {
+            island.step();
+        }
+
+        self.iteration += 1;
+        if self.interval > 0 && self.iteration % self.interval == 0 {
+            self.migrate();
+        }
+    }
+
+    /// Sends a copy of each island's best solution to the next island in the ring, where it
+    /// replaces the worst individual. All migrants are collected before any is inserted.
+    pub fn migrate(&mut self) {
+        let n = self.islands.len();
+        if n < 2 {
+            return;
+        }
+
+        let migrants: Vec<O::Solution> = self.islands.iter().map(|i| i.best().0).collect();
+        for (i, migrant) in migrants.into_iter().enumerate() {
+            self.islands[(i + 1) % n].replace_worst(migrant);
+        }
+    }
+
+    /// Returns the best solution across all islands, compared by `Optimizer::is_better`.
+    ///
+    /// # Panics
+    /// Panics if there are no islands.
+    pub fn best(&self) -> (O::Solution, O::Eval) {
+        let mut best = self.islands[0].best();
+        for island in &self.islands[1..] {
+            let b = island.best();
+            if island.is_better(&b.1, &best.1) {
+                best = b;
+            }
+        }
+        best
+    }
+
+    pub fn islands(&self) -> &[O] {
+        &self.islands
+    }
+
+    pub fn islands_mut(&mut self) -> &mut [O] {
+        &mut self.islands
+    }
+
+    /// Returns the number of `step` calls made so far.
+    pub fn iteration(&self) -> usize {
+        self.iteration
+    }
+
+    /// Returns the number of evaluations made so far across all islands, as counted by
+    /// `Optimizer::eval_count`.
+    pub fn eval_count(&self) -> usize {
+        self.islands.iter().map(O::eval_count).sum()
+    }
+
+    /// Steps the model as long as the next step, including the migration it may trigger, cannot
+    /// take the combined eval count of the islands past `max_evals`, and returns the number of
+    /// steps run. Each island bounds the cost of its step by `Optimizer::step_evals`, and each
+    /// migrant costs one evaluation on arrival.
+    ///
+    /// This compares a model fairly against a single optimizer under the same budget, as the
+    /// evaluations of every island, including their initial populations, count against it.
+    pub fn run_eval_budget(&mut self, max_evals: usize) -> usize {
+        let mut iters = 0;
+        loop {
+            let mut cost: usize = self.islands.iter().map(O::step_evals).sum();
+            let migrates = self.interval > 0 && (self.iteration + 1) % self.interval == 0;
+            if migrates && self.islands.len() >= 2 {
+                cost += self.islands.len();
+            }
+            if self.eval_count() + cost > max_evals {
+                return iters;
+            }
+
+            self.step();
+            iters += 1;
+        }
+    }
+}
+
+impl<O> IslandModel<O>
+    where O: Optimizer + Send,
+          O::Solution: Clone,
+          O::Eval: PartialOrd
+{
+    /// Steps every island `iters` times like as many `step` calls, but running the islands on
+    /// one thread each between migrations.
+    ///
+    /// The islands only synchronize to migrate, which happens in the ring order as in `migrate`,
+    /// so the result does not depend on thread scheduling as long as each island owns its random
+    /// number generator, as `new_seeded` optimizers do. Islands drawing from the thread-local
+    /// generator are not `Send` anyway.
+    ///
+    /// # Panics
+    /// Panics if an island panics.
+    pub fn run_parallel(&mut self, iters: usize) {
+        let mut remaining = iters;
+        while remaining > 0 {
+            let steps = if self.interval > 0 {
+                (self.interval - self.iteration % self.interval).min(remaining)
+            } else {
+                remaining
+            };
+
+            thread::scope(|scope| {
+                for island in &mut self.islands {
+                    scope.spawn(move || {
+                        for _ in 0..steps {
+                            island.step();
+                        }
+                    });
+                }
+            });
+
+            self.iteration += steps;
+            remaining -= steps;
+            if self.interval > 0 && self.iteration % self.interval == 0 {
+                self.migrate();
+            }
+        }
+    }
+}
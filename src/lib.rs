@@ -1,4 +1,755 @@
 extern crate rand;
+#[cfg(feature = "log")]
+#[macro_use]
+extern crate log;
+#[cfg(feature = "plotters")]
+extern crate plotters;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+#[cfg(feature = "serde")]
+extern crate serde_json;
 
+use std::{cmp, ops, thread};
+use std::error::Error;
+
+pub mod error;
 pub mod pso;
 pub mod firefly;
+pub mod woa;
+pub mod sa;
+pub mod island;
+pub mod combine;
+pub mod adapter;
+pub mod bench;
+pub mod discrete;
+pub mod archive;
+pub mod driver;
+pub mod survivor_selection;
+pub mod problem;
+#[cfg(feature = "plotters")]
+mod plot;
+
+/// A solution candidate: a position in the search space that can be evaluated.
+///
+/// This is shared by the algorithm-specific traits such as `pso::Particle` and
+/// `firefly::Firefly`, so that one type can be optimized by several algorithms. Every algorithm
+/// maximizes `eval`.
+///
+/// Positions only need to be `Clone`, so they may own heap storage.
+///
+/// # Example
+/// ```
+/// extern crate meta_heuristics;
+/// extern crate rand;
+///
+/// use std::ops;
+/// use meta_heuristics::{firefly, Candidate, RandomCandidate};
+///
+/// #[derive(Clone, Debug, PartialEq)]
+/// struct VecPos(Vec<f64>);
+///
+/// impl ops::Add for VecPos {
+///     type Output = VecPos;
+///     fn add(self, rhs: VecPos) -> VecPos {
+///         VecPos(self.0.iter().zip(&rhs.0).map(|(a, b)| a + b).collect())
+///     }
+/// }
+///
+/// impl ops::Sub for VecPos {
+///     type Output = VecPos;
+///     fn sub(self, rhs: VecPos) -> VecPos {
+///         VecPos(self.0.iter().zip(&rhs.0).map(|(a, b)| a - b).collect())
+///     }
+/// }
+///
+/// impl ops::Mul<f64> for VecPos {
+///     type Output = VecPos;
+///     fn mul(self, rhs: f64) -> VecPos {
+///         VecPos(self.0.iter().map(|a| a * rhs).collect())
+///     }
+/// }
+///
+/// #[derive(Clone)]
+/// struct Point {
+///     pos: VecPos,
+/// }
+///
+/// impl Candidate for Point {
+///     type Pos = VecPos;
+///     type Eval = f64;
+///
+///     fn eval(&self) -> f64 {
+///         -self.pos.0.iter().map(|x| x * x).sum::<f64>()
+///     }
+///     fn pos(&self) -> VecPos {
+///         self.pos.clone()
+///     }
+///     fn pos_mut(&mut self) -> &mut VecPos {
+///         &mut self.pos
+///     }
+///     fn dim(&self) -> usize {
+///         self.pos.0.len()
+///     }
+/// }
+///
+/// impl RandomCandidate for Point {
+///     fn new_random() -> Self {
+///         use rand::{random, Closed01};
+///
+///         let pos = (0..3).map(|_| 4.0 * random::<Closed01<f64>>().0 - 2.0).collect();
+///         Point { pos: VecPos(pos) }
+///     }
+/// }
+///
+/// impl firefly::Firefly for Point {
+///     fn distance(&self, rhs: &Self) -> f64 {
+///         (self.pos.clone() - rhs.pos.clone()).0.iter().map(|d| d * d).sum::<f64>().sqrt()
+///     }
+/// }
+///
+/// fn main() {
+///     let mut ff: firefly::FireflyAlg<Point> = firefly::FireflyAlg::new(8, 0.5, 0.2);
+///     for _ in 0..10 {
+///         ff.update();
+///     }
+///     assert!(ff.fireflies().iter().all(|ff| ff.0.dim() == 3));
+/// }
+/// ```
+pub trait Candidate {
+    type Pos: Clone +
+              ops::Add<Output = Self::Pos> +
+              ops::Sub<Output = Self::Pos> +
+              ops::Mul<f64, Output = Self::Pos>;
+    type Eval: Clone + PartialOrd;
+
+    fn eval(&self) -> Self::Eval;
+
+    /// Evaluates the candidate as of `iteration`, for objectives which change over time on a
+    /// known schedule. The default ignores the iteration and calls `eval`.
+    ///
+    /// The optimizers call this instead of `eval` once their `set_time_varying` is enabled:
+    /// `PSO`, `FireflyAlg`, `WhaleOptimization` and `SimulatedAnnealing`. They pass the
+    /// iteration about to complete, and re-evaluate the evals they store, such as personal and
+    /// global bests, at that iteration before comparing, since evals of earlier iterations are
+    /// stale. Each of them documents the extra evaluations this costs.
+    fn eval_at(&self, iteration: usize) -> Self::Eval {
+        let _ = iteration;
+        self.eval()
+    }
+
+    /// Evaluates the candidate with an objective which can fail, such as one calling an external
+    /// simulation. The default wraps `eval` in `Ok`.
+    ///
+    /// Only `PSO::try_update` calls this.
+    fn try_eval(&self) -> Result<Self::Eval, Box<dyn Error>> {
+        Ok(self.eval())
+    }
+
+    fn pos(&self) -> Self::Pos;
+    fn pos_mut(&mut self) -> &mut Self::Pos;
+
+    /// Projects the position back onto the feasible set, for constraints which bounds cannot
+    /// express, such as components summing to 1. The default does nothing.
+    ///
+    /// Only `PSO` with `PSO::set_repair` enabled calls this.
+    fn repair(&mut self) {}
+
+    /// Returns whether the position satisfies the invariants of the candidate type, such as
+    /// being a permutation or a unit vector. The default assumes it does.
+    ///
+    /// Only `PSO` with `PSO::set_validate` enabled calls this.
+    fn is_valid(&self) -> bool {
+        true
+    }
+
+    /// Returns whether every component of the position is finite. The default assumes it is.
+    ///
+    /// Only `PSO::is_diverging` calls this.
+    fn is_finite(&self) -> bool {
+        true
+    }
+
+    /// Returns the number of dimensions of the position. The default is 1, for scalar
+    /// positions.
+    fn dim(&self) -> usize {
+        1
+    }
+}
+
+/// A candidate which can be created at random, used to initialize and restart the population.
+///
+/// The optimizers' `new_with_sampler` constructors take a sampler closure instead, so candidates
+/// created only by a sampler need not implement this.
+pub trait RandomCandidate: Candidate + Sized {
+    fn new_random() -> Self;
+}
+
+/// A candidate which can be created at random deterministically from a seed, required by the
+/// seeded constructors `PSO::new_stratified`, `PSO::new_seeded` and `FireflyAlg::new_seeded`,
+/// and by `PSO::reset_with_seed`.
+///
+/// `seeded_rng` creates a generator from the seed the same way on every platform.
+pub trait SeededCandidate: RandomCandidate {
+    fn new_random_seeded(seed: u64) -> Self;
+}
+
+/// A candidate whose position can be exported as numbers, required by `PSO::write_csv`.
+pub trait Components: Candidate {
+    /// Returns the components of the position as a flat list of `dim` numbers.
+    fn components(&self) -> Vec<f64>;
+}
+
+/// An iterative, population-based optimizer.
+///
+/// This abstracts over the algorithms of this crate so that drivers such as
+/// `island::IslandModel` work with any of them.
+pub trait Optimizer {
+    type Solution;
+    type Eval;
+
+    /// Advances the optimizer by one iteration.
+    fn step(&mut self);
+
+    /// Returns the best solution of the current population and its eval.
+    fn best(&self) -> (Self::Solution, Self::Eval);
+
+    /// Replaces the worst individual of the population with `solution`.
+    fn replace_worst(&mut self, solution: Self::Solution);
+
+    /// Returns a copy of the current population, for handing it over to another optimizer. The
+    /// default returns only the best solution.
+    fn population(&self) -> Vec<Self::Solution> {
+        vec![self.best().0]
+    }
+
+    /// Returns the number of evaluations made so far, including those of the initial
+    /// population and of `replace_worst`.
+    fn eval_count(&self) -> usize;
+
+    /// Returns the most evaluations the next `step` can make, so that a budget of evaluations
+    /// is never overrun.
+    fn step_evals(&self) -> usize;
+
+    /// Starts the search over, typically by re-randomizing the population.
+    fn reset(&mut self);
+
+    /// Returns whether eval `a` is better than `b` in the order the optimizer maximizes, by
+    /// which drivers such as `driver::with_restarts` and `island::IslandModel::best` compare
+    /// bests. The default compares by `PartialOrd`.
+    ///
+    /// `PSO` follows the comparator set with `PSO::set_comparator`. The other optimizers of
+    /// this crate, `SimulatedAnnealing` among them, always compare by `PartialOrd`.
+    fn is_better(&self, a: &Self::Eval, b: &Self::Eval) -> bool
+        where Self::Eval: PartialOrd
+    {
+        a > b
+    }
+
+    /// Injects the best solution of `source` into the population, replacing the worst
+    /// individual. This hands the result of one optimizer over to another in hybrid pipelines.
+    ///
+    /// # Example
+    /// ```
+    /// extern crate meta_heuristics;
+    /// extern crate rand;
+    ///
+    /// use meta_heuristics::{firefly, pso, Candidate, Optimizer, RandomCandidate};
+    ///
+    /// #[derive(Clone, Copy)]
+    /// struct Particle { pos: f64, vel: f64, best: (f64, f64) }
+    ///
+    /// fn eval_func(x: f64) -> f64 { 1.0 - ((x - 3.0) * x + 2.0) * x * x }
+    ///
+    /// impl Candidate for Particle {
+    ///     type Pos = f64;
+    ///     type Eval = f64;
+    ///
+    ///     fn eval(&self) -> f64 { eval_func(self.pos) }
+    ///     fn pos(&self) -> f64 { self.pos }
+    ///     fn pos_mut(&mut self) -> &mut f64 { &mut self.pos }
+    /// }
+    ///
+    /// impl RandomCandidate for Particle {
+    ///     fn new_random() -> Self {
+    ///         use rand::{random, Closed01};
+    ///
+    ///         let Closed01(x) = random::<Closed01<f64>>();
+    ///         let x = 4.0 * x - 1.0;
+    ///         Self { pos: x, vel: 0.0, best: (x, eval_func(x)) }
+    ///     }
+    /// }
+    ///
+    /// impl pso::Particle for Particle {
+    ///     fn vel(&self) -> f64 { self.vel }
+    ///     fn best(&self) -> (f64, f64) { self.best }
+    ///     fn vel_mut(&mut self) -> &mut f64 { &mut self.vel }
+    ///     fn best_mut(&mut self) -> &mut (f64, f64) { &mut self.best }
+    /// }
+    ///
+    /// impl firefly::Firefly for Particle {
+    ///     fn distance(&self, rhs: &Self) -> f64 { (self.pos - rhs.pos).abs() }
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut ff: firefly::FireflyAlg<Particle> = firefly::FireflyAlg::new(16, 0.5, 0.2);
+    ///     for _ in 0..10 {
+    ///         ff.update();
+    ///     }
+    ///
+    ///     let mut pso: pso::PSO<Particle> = pso::PSO::new(4, 0.7, 0.9, 0.9);
+    ///     pso.seed_from(&ff);
+    ///
+    ///     let explored = Optimizer::best(&ff).1;
+    ///     assert!(pso.best().1 >= explored);
+    ///     assert!(pso.particles().iter().any(|p| p.eval() == explored));
+    /// }
+    /// ```
+    fn seed_from<O>(&mut self, source: &O)
+        where O: Optimizer<Solution = Self::Solution>,
+              Self: Sized
+    {
+        self.replace_worst(source.best().0);
+    }
+}
+
+/// An object-safe view of an `Optimizer`, for choosing the algorithm at run time.
+///
+/// `Optimizer` names its solution and eval types, so optimizers of different algorithms or
+/// candidate types cannot share a trait object. This one reports the best eval as an `f64`
+/// instead, and is implemented for every `Optimizer` whose eval converts into one.
+///
+/// # Example
+/// ```
+/// extern crate meta_heuristics;
+/// extern crate rand;
+///
+/// use meta_heuristics::{firefly, pso, woa, Candidate, DynOptimizer, RandomCandidate};
+///
+/// #[derive(Clone, Copy)]
+/// struct Particle { pos: f64, vel: f64, best: (f64, f64) }
+///
+/// fn eval_func(x: f64) -> f64 { 1.0 - ((x - 3.0) * x + 2.0) * x * x }
+///
+/// impl Candidate for Particle {
+///     type Pos = f64;
+///     type Eval = f64;
+///
+///     fn eval(&self) -> f64 { eval_func(self.pos) }
+///     fn pos(&self) -> f64 { self.pos }
+///     fn pos_mut(&mut self) -> &mut f64 { &mut self.pos }
+/// }
+///
+/// impl RandomCandidate for Particle {
+///     fn new_random() -> Self {
+///         use rand::{random, Closed01};
+///
+///         let Closed01(x) = random::<Closed01<f64>>();
+///         let x = 4.0 * x - 1.0;
+///         Self { pos: x, vel: 0.0, best: (x, eval_func(x)) }
+///     }
+/// }
+///
+/// impl pso::Particle for Particle {
+///     fn vel(&self) -> f64 { self.vel }
+///     fn best(&self) -> (f64, f64) { self.best }
+///     fn vel_mut(&mut self) -> &mut f64 { &mut self.vel }
+///     fn best_mut(&mut self) -> &mut (f64, f64) { &mut self.best }
+/// }
+///
+/// impl firefly::Firefly for Particle {
+///     fn distance(&self, rhs: &Self) -> f64 { (self.pos - rhs.pos).abs() }
+/// }
+///
+/// fn main() {
+///     let mut ensemble: Vec<Box<dyn DynOptimizer>> = vec![
+///         Box::new(pso::PSO::<Particle>::new(8, 0.7, 1.4, 1.4)),
+///         Box::new(firefly::FireflyAlg::<Particle>::new(8, 0.5, 0.2)),
+///         Box::new(woa::WhaleOptimization::<Particle>::new(8, 20)),
+///     ];
+///
+///     for optimizer in &mut ensemble {
+///         for _ in 0..20 {
+///             optimizer.step();
+///         }
+///     }
+///     assert!(ensemble.iter().all(|o| o.best_eval() <= eval_func(1.6404) + 1e-6));
+///     let best = ensemble.iter().map(|o| o.best_eval()).fold(f64::NEG_INFINITY, f64::max);
+///     assert!(best > 1.0);
+/// }
+/// ```
+pub trait DynOptimizer {
+    /// Advances the optimizer by one iteration, like `Optimizer::step`.
+    fn step(&mut self);
+
+    /// Returns the eval of the optimizer's best solution, like `Optimizer::best`.
+    fn best_eval(&self) -> f64;
+}
+
+impl<O> DynOptimizer for O
+    where O: Optimizer,
+          O::Eval: Into<f64>
+{
+    fn step(&mut self) {
+        Optimizer::step(self);
+    }
+
+    fn best_eval(&self) -> f64 {
+        Optimizer::best(self).1.into()
+    }
+}
+
+/// Evaluates `candidate`, for generic code handling candidates outside of any optimizer.
+///
+/// # Example
+/// ```
+/// # use meta_heuristics::Candidate;
+/// # #[derive(Clone, Copy)]
+/// # struct Point { pos: f64 }
+/// fn eval_func(x: f64) -> f64 { 1.0 - ((x - 3.0) * x + 2.0) * x * x }
+///
+/// # impl Candidate for Point {
+/// #     type Pos = f64;
+/// #     type Eval = f64;
+/// #     fn eval(&self) -> f64 { eval_func(self.pos) }
+/// #     fn pos(&self) -> f64 { self.pos }
+/// #     fn pos_mut(&mut self) -> &mut f64 { &mut self.pos }
+/// # }
+/// assert_eq!(meta_heuristics::eval_candidate(&Point { pos: 0.0 }), 1.0);
+/// assert_eq!(meta_heuristics::eval_candidate(&Point { pos: 2.0 }), eval_func(2.0));
+/// ```
+pub fn eval_candidate<T: Candidate>(candidate: &T) -> T::Eval {
+    candidate.eval()
+}
+
+/// Evaluates `base` as if it were at `pos`, by evaluating a clone of it moved there, leaving
+/// `base` and any optimizer holding it untouched.
+///
+/// This probes the landscape around a solution, as line searches and local refinement do. It
+/// calls `eval` once, which no optimizer's `eval_count` includes.
+///
+/// # Example
+/// ```
+/// # use meta_heuristics::Candidate;
+/// # #[derive(Clone, Copy, Debug, PartialEq)]
+/// # struct Point { pos: f64 }
+/// fn eval_func(x: f64) -> f64 { 1.0 - ((x - 3.0) * x + 2.0) * x * x }
+///
+/// # impl Candidate for Point {
+/// #     type Pos = f64;
+/// #     type Eval = f64;
+/// #     fn eval(&self) -> f64 { eval_func(self.pos) }
+/// #     fn pos(&self) -> f64 { self.pos }
+/// #     fn pos_mut(&mut self) -> &mut f64 { &mut self.pos }
+/// # }
+/// // Probes around the global maximum, which lies near 1.64.
+/// let base = Point { pos: 1.64 };
+/// for &dx in &[-0.1, -0.01, 0.0, 0.01, 0.1] {
+///     assert_eq!(meta_heuristics::eval_pos(&base, 1.64 + dx), eval_func(1.64 + dx));
+/// }
+/// assert_eq!(base, Point { pos: 1.64 });
+/// ```
+pub fn eval_pos<T: Candidate + Clone>(base: &T, pos: T::Pos) -> T::Eval {
+    let mut probe = base.clone();
+    *probe.pos_mut() = pos;
+    probe.eval()
+}
+
+/// Returns the candidate with the highest eval among `candidates`, and its eval, evaluating
+/// each candidate once. This is `best_of_by` ordering the evals by `PartialOrd`.
+///
+/// The first of equally good candidates wins, and a candidate whose eval is incomparable to the
+/// best so far, such as NaN, never does.
+///
+/// # Panics
+/// Panics if `candidates` is empty.
+///
+/// # Example
+/// ```
+/// # use meta_heuristics::Candidate;
+/// # #[derive(Clone, Copy, Debug, PartialEq)]
+/// # struct Point { pos: f64 }
+/// # fn eval_func(x: f64) -> f64 { 1.0 - ((x - 3.0) * x + 2.0) * x * x }
+/// # impl Candidate for Point {
+/// #     type Pos = f64;
+/// #     type Eval = f64;
+/// #     fn eval(&self) -> f64 { eval_func(self.pos) }
+/// #     fn pos(&self) -> f64 { self.pos }
+/// #     fn pos_mut(&mut self) -> &mut f64 { &mut self.pos }
+/// # }
+/// // The global maximum lies near 1.64, and a local one at 0.
+/// let points: Vec<Point> = [-1.0, 0.0, 1.0, 1.6, 3.0].iter().map(|&pos| Point { pos }).collect();
+///
+/// let (best, e) = meta_heuristics::best_of(&points);
+/// assert_eq!(*best, Point { pos: 1.6 });
+/// assert_eq!(e, eval_func(1.6));
+/// ```
+pub fn best_of<T: Candidate>(candidates: &[T]) -> (&T, T::Eval) {
+    best_of_by(candidates, |a, b| a.partial_cmp(b).unwrap_or(cmp::Ordering::Equal))
+}
+
+/// Returns the best candidate among `candidates` like `best_of`, but ordering the evals by
+/// `comparator`, as set with `pso::PSO::set_comparator`, so that the greatest eval by the
+/// comparator wins. The first of equally good candidates wins.
+///
+/// # Panics
+/// Panics if `candidates` is empty.
+///
+/// # Example
+/// ```
+/// # use meta_heuristics::Candidate;
+/// # #[derive(Clone, Copy, Debug, PartialEq)]
+/// # struct Point { pos: f64 }
+/// # fn eval_func(x: f64) -> f64 { 1.0 - ((x - 3.0) * x + 2.0) * x * x }
+/// # impl Candidate for Point {
+/// #     type Pos = f64;
+/// #     type Eval = f64;
+/// #     fn eval(&self) -> f64 { eval_func(self.pos) }
+/// #     fn pos(&self) -> f64 { self.pos }
+/// #     fn pos_mut(&mut self) -> &mut f64 { &mut self.pos }
+/// # }
+/// let points: Vec<Point> = [-1.0, 0.0, 1.0, 1.6, 3.0].iter().map(|&pos| Point { pos }).collect();
+///
+/// // Minimizing, the best point is the one with the lowest eval, at 3.
+/// let (best, e) = meta_heuristics::best_of_by(&points, |a, b| b.partial_cmp(a).unwrap());
+/// assert_eq!(*best, Point { pos: 3.0 });
+/// assert_eq!(e, eval_func(3.0));
+/// ```
+pub fn best_of_by<T, F>(candidates: &[T], mut comparator: F) -> (&T, T::Eval)
+    where T: Candidate,
+          F: FnMut(&T::Eval, &T::Eval) -> cmp::Ordering
+{
+    assert!(!candidates.is_empty(), "no candidates");
+
+    let mut best = (&candidates[0], candidates[0].eval());
+    for c in &candidates[1..] {
+        let e = c.eval();
+        if comparator(&e, &best.1) == cmp::Ordering::Greater {
+            best = (c, e);
+        }
+    }
+    best
+}
+
+/// Evaluates `candidates` on `threads` scoped threads, each taking a contiguous chunk, and
+/// returns the evals in the same order as `candidates`.
+///
+/// This is meant for overriding `pso::Particle::eval_batch` with an expensive objective. `PSO`
+/// draws all of an update's random numbers before evaluating the moved swarm, so as long as
+/// `eval` is pure, a seeded run gives the same results as with sequential evaluation.
+///
+/// # Panics
+/// Panics if `threads` is 0.
+///
+/// # Example
+/// ```
+/// extern crate meta_heuristics;
+/// extern crate rand;
+///
+/// use std::sync::atomic::{AtomicBool, Ordering};
+/// use meta_heuristics::{pso, Candidate, RandomCandidate};
+/// use rand::{SeedableRng, StdRng};
+///
+/// static PARALLEL: AtomicBool = AtomicBool::new(false);
+///
+/// #[derive(Clone, Copy)]
+/// struct Particle { pos: f64, vel: f64, best: (f64, f64) }
+///
+/// fn eval_func(x: f64) -> f64 { 1.0 - ((x - 3.0) * x + 2.0) * x * x }
+///
+/// impl Candidate for Particle {
+///     type Pos = f64;
+///     type Eval = f64;
+///
+///     fn eval(&self) -> f64 { eval_func(self.pos) }
+///     fn pos(&self) -> f64 { self.pos }
+///     fn pos_mut(&mut self) -> &mut f64 { &mut self.pos }
+/// }
+///
+/// impl RandomCandidate for Particle {
+///     fn new_random() -> Self {
+///         use rand::{random, Closed01};
+///
+///         let Closed01(x) = random::<Closed01<f64>>();
+///         let x = 4.0 * x - 1.0;
+///         Self { pos: x, vel: 0.0, best: (x, eval_func(x)) }
+///     }
+/// }
+///
+/// impl pso::Particle for Particle {
+///     fn vel(&self) -> f64 { self.vel }
+///     fn best(&self) -> (f64, f64) { self.best }
+///     fn vel_mut(&mut self) -> &mut f64 { &mut self.vel }
+///     fn best_mut(&mut self) -> &mut (f64, f64) { &mut self.best }
+///     fn eval_batch(particles: &[Self]) -> Vec<f64> {
+///         if PARALLEL.load(Ordering::SeqCst) {
+///             meta_heuristics::eval_parallel(particles, 4)
+///         } else {
+///             particles.iter().map(Particle::eval).collect()
+///         }
+///     }
+/// }
+///
+/// fn main() {
+///     let initial = pso::PSO::<Particle>::new(16, 0.7, 1.4, 1.4).into_state();
+///     let mut run = |parallel: bool| {
+///         PARALLEL.store(parallel, Ordering::SeqCst);
+///         let rng = StdRng::from_seed(&[42][..]);
+///         let mut pso = pso::PSO::from_state(initial.clone(), rng);
+///         for _ in 0..30 {
+///             pso.update();
+///         }
+///         let best = pso.best();
+///         (best.0.pos.to_bits(), best.1.to_bits())
+///     };
+///
+///     assert_eq!(run(false), run(true));
+/// }
+/// ```
+pub fn eval_parallel<T>(candidates: &[T], threads: usize) -> Vec<T::Eval>
+    where T: Candidate + Sync,
+          T::Eval: Send
+{
+    assert!(threads > 0, "no threads");
+    if candidates.is_empty() {
+        return Vec::new();
+    }
+
+    let chunk = (candidates.len() + threads - 1) / threads;
+    thread::scope(|scope| {
+        let handles: Vec<_> = candidates
+            .chunks(chunk)
+            .map(|chunk| scope.spawn(move || chunk.iter().map(T::eval).collect::<Vec<_>>()))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().expect("an evaluation thread panicked"))
+            .collect()
+    })
+}
+
+/// Returns the index of the highest of `evals`, the first of equally high ones, comparing on
+/// `threads` scoped threads which each scan a contiguous chunk.
+///
+/// A sequential scan replaces its best whenever it meets a higher eval, so an eval
+/// incomparable to the others, such as NaN, never takes the lead but keeps it if it comes
+/// first. Each chunk is therefore scanned starting from the first eval, and the winners of the
+/// chunks are reduced in order, which gives the index of the sequential scan for any number of
+/// threads, as long as the comparable evals are totally ordered. `PSO` selects its best this
+/// way with `PSO::enable_parallel_eval`.
+///
+/// # Panics
+/// Panics if `evals` is empty or `threads` is 0.
+///
+/// # Example
+/// ```
+/// use std::f64::NAN;
+/// use meta_heuristics::best_index_parallel;
+///
+/// fn sequential(evals: &[f64]) -> usize {
+///     let mut best = 0;
+///     for (i, e) in evals.iter().enumerate() {
+///         if *e > evals[best] {
+///             best = i;
+///         }
+///     }
+///     best
+/// }
+///
+/// let cases = [vec![1.0, 3.0, 2.0, 3.0, 0.0, 3.0, 1.0, 2.0, 3.0],
+///              vec![1.0, NAN, 2.0, NAN, 2.0, 0.0, 1.0, 2.0],
+///              vec![NAN, 1.0, 5.0, 2.0, 5.0, NAN, 4.0],
+///              vec![0.5],
+///              vec![NAN, NAN, NAN]];
+/// for evals in &cases {
+///     for &threads in &[1, 2, 8] {
+///         assert_eq!(best_index_parallel(evals, threads), sequential(evals));
+///     }
+/// }
+/// assert_eq!(best_index_parallel(&cases[0], 2), 1);
+/// assert_eq!(best_index_parallel(&cases[2], 8), 0);
+/// ```
+pub fn best_index_parallel<E: PartialOrd + Sync>(evals: &[E], threads: usize) -> usize {
+    assert!(threads > 0, "no threads");
+    assert!(!evals.is_empty(), "no evals");
+
+    let chunk = (evals.len() + threads - 1) / threads;
+    let winners: Vec<usize> = thread::scope(|scope| {
+        let handles: Vec<_> = evals
+            .chunks(chunk)
+            .enumerate()
+            .map(|(k, evals_k)| {
+                scope.spawn(move || {
+                    let mut best = 0;
+                    for (j, e) in evals_k.iter().enumerate() {
+                        if *e > evals[best] {
+                            best = k * chunk + j;
+                        }
+                    }
+                    best
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().expect("a comparison thread panicked"))
+            .collect()
+    });
+
+    winners
+        .into_iter()
+        .fold(0, |best, i| if evals[i] > evals[best] { i } else { best })
+}
+
+/// Creates a population of `num` candidates, the `i`-th by `factory(i)`.
+fn build_population<T, F: FnMut(usize) -> T>(num: usize, factory: F) -> Vec<T> {
+    (0..num).map(factory).collect()
+}
+
+/// Creates the candidates an optimizer needs after construction, such as on `reset`: either
+/// `RandomCandidate::new_random`, or the closure given to a `new_with_sampler` constructor.
+enum Sampler<T> {
+    Random(fn() -> T),
+    Closure(Box<dyn FnMut() -> T + Send>),
+}
+
+impl<T> Sampler<T> {
+    fn sample(&mut self) -> T {
+        match *self {
+            Sampler::Random(new_random) => new_random(),
+            Sampler::Closure(ref mut sampler) => sampler(),
+        }
+    }
+}
+
+impl<T: SeededCandidate + 'static> Sampler<T> {
+    /// Samples `SeededCandidate::new_random_seeded` with the seeds drawn from a generator seeded
+    /// with `seed`, for the `new_seeded` constructors.
+    fn seeded(seed: u64) -> Self {
+        use rand::Rng;
+
+        let mut rng = seeded_rng(seed);
+        Sampler::Closure(Box::new(move || T::new_random_seeded(rng.next_u64())))
+    }
+}
+
+/// Creates the random number generator of the `new_seeded` constructors from `seed`, for
+/// implementing `SeededCandidate` too.
+///
+/// This is an `Isaac64Rng` rather than a `StdRng`, whose algorithm depends on the width of
+/// `usize`, so that the generator is the same on every platform.
+pub fn seeded_rng(seed: u64) -> rand::Isaac64Rng {
+    use rand::SeedableRng;
+
+    rand::Isaac64Rng::from_seed(&[seed][..])
+}
+
+fn eval_to_f64<E: Clone + Into<f64>>(e: &E) -> f64 {
+    e.clone().into()
+}
+
+/// Formats an eval with `Debug`, captured by the setters logging the best eval.
+#[cfg(feature = "log")]
+fn debug_eval<E: ::std::fmt::Debug>(e: &E) -> String {
+    format!("{:?}", e)
+}
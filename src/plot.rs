@@ -0,0 +1,112 @@
+//! Chart rendering for the `plotters` feature.
+//!
+//! Images are written as PNG, or as SVG for paths with the `svg` extension.
+
+use std::error::Error;
+use std::ops::Range;
+use std::path::Path;
+
+use plotters::coord::Shift;
+use plotters::prelude::*;
+
+const SIZE: (u32, u32) = (640, 480);
+
+/// Draws `points` connected by a line, in order.
+pub fn line_chart(path: &Path,
+                  points: &[(f64, f64)],
+                  x_desc: &str,
+                  y_desc: &str)
+                  -> Result<(), Box<dyn Error>> {
+    if is_svg(path) {
+        line(SVGBackend::new(path, SIZE).into_drawing_area(), points, x_desc, y_desc)
+    } else {
+        line(BitMapBackend::new(path, SIZE).into_drawing_area(), points, x_desc, y_desc)
+    }
+}
+
+/// Draws `points` as dots, and `highlight` as a larger one on top.
+pub fn scatter_chart(path: &Path,
+                     points: &[(f64, f64)],
+                     highlight: (f64, f64),
+                     x_desc: &str,
+                     y_desc: &str)
+                     -> Result<(), Box<dyn Error>> {
+    if is_svg(path) {
+        let root = SVGBackend::new(path, SIZE).into_drawing_area();
+        scatter(root, points, highlight, x_desc, y_desc)
+    } else {
+        let root = BitMapBackend::new(path, SIZE).into_drawing_area();
+        scatter(root, points, highlight, x_desc, y_desc)
+    }
+}
+
+fn is_svg(path: &Path) -> bool {
+    path.extension().map_or(false, |ext| ext.eq_ignore_ascii_case("svg"))
+}
+
+fn line<DB>(root: DrawingArea<DB, Shift>,
+            points: &[(f64, f64)],
+            x_desc: &str,
+            y_desc: &str)
+            -> Result<(), Box<dyn Error>>
+    where DB: DrawingBackend,
+          DB::ErrorType: 'static
+{
+    root.fill(&WHITE)?;
+    {
+        let x_range = range(points.iter().map(|p| p.0));
+        let y_range = range(points.iter().map(|p| p.1));
+        let mut chart = ChartBuilder::on(&root)
+            .margin(10)
+            .x_label_area_size(40)
+            .y_label_area_size(60)
+            .build_cartesian_2d(x_range, y_range)?;
+        chart.configure_mesh().x_desc(x_desc).y_desc(y_desc).draw()?;
+        chart.draw_series(LineSeries::new(points.iter().cloned(), &BLUE))?;
+    }
+    root.present()?;
+    Ok(())
+}
+
+fn scatter<DB>(root: DrawingArea<DB, Shift>,
+               points: &[(f64, f64)],
+               highlight: (f64, f64),
+               x_desc: &str,
+               y_desc: &str)
+               -> Result<(), Box<dyn Error>>
+    where DB: DrawingBackend,
+          DB::ErrorType: 'static
+{
+    root.fill(&WHITE)?;
+    {
+        let x_range = range(points.iter().chain(Some(&highlight)).map(|p| p.0));
+        let y_range = range(points.iter().chain(Some(&highlight)).map(|p| p.1));
+        let mut chart = ChartBuilder::on(&root)
+            .margin(10)
+            .x_label_area_size(40)
+            .y_label_area_size(60)
+            .build_cartesian_2d(x_range, y_range)?;
+        chart.configure_mesh().x_desc(x_desc).y_desc(y_desc).draw()?;
+        chart.draw_series(points.iter().map(|&p| Circle::new(p, 3, BLUE.filled())))?;
+        chart.draw_series(Some(Circle::new(highlight, 5, RED.filled())))?;
+    }
+    root.present()?;
+    Ok(())
+}
+
+/// The range of the finite `values` with a margin, or around them if they are all equal.
+fn range<I>(values: I) -> Range<f64>
+    where I: Iterator<Item = f64>
+{
+    let (lo, hi) = values
+        .filter(|v| v.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)));
+    if lo < hi {
+        let margin = (hi - lo) * 0.05;
+        lo - margin..hi + margin
+    } else if lo == hi {
+        lo - 1.0..hi + 1.0
+    } else {
+        0.0..1.0
+    }
+}
@@ -0,0 +1,202 @@
+//! Objectives kept apart from the representation of the solutions they evaluate.
+//!
+//! A candidate evaluates itself, so optimizing another objective over the same positions takes
+//! another candidate type. A `Problem` evaluates bare solutions instead, and `ProblemCandidate`
+//! makes candidates of them for the optimizers, which `PSO::new_with_problem` and
+//! `FireflyAlg::new_with_problem` build from a problem and a generator of initial solutions.
+//! Swapping the problem then reuses the representation as it is.
+//!
+//! # Example
+//! ```
+//! extern crate meta_heuristics;
+//! extern crate rand;
+//!
+//! use std::ops;
+//! use meta_heuristics::pso::PSO;
+//! use meta_heuristics::problem::Problem;
+//!
+//! #[derive(Clone, Debug, PartialEq)]
+//! struct VecPos(Vec<f64>);
+//!
+//! impl ops::Add for VecPos {
+//!     type Output = VecPos;
+//!     fn add(self, rhs: VecPos) -> VecPos {
+//!         VecPos(self.0.iter().zip(&rhs.0).map(|(a, b)| a + b).collect())
+//!     }
+//! }
+//!
+//! impl ops::Sub for VecPos {
+//!     type Output = VecPos;
+//!     fn sub(self, rhs: VecPos) -> VecPos {
+//!         VecPos(self.0.iter().zip(&rhs.0).map(|(a, b)| a - b).collect())
+//!     }
+//! }
+//!
+//! impl ops::Mul<f64> for VecPos {
+//!     type Output = VecPos;
+//!     fn mul(self, rhs: f64) -> VecPos {
+//!         VecPos(self.0.iter().map(|a| a * rhs).collect())
+//!     }
+//! }
+//!
+//! // The Sphere function centered at a given point, negated to be maximized.
+//! struct ShiftedSphere(Vec<f64>);
+//!
+//! impl Problem for ShiftedSphere {
+//!     type Solution = VecPos;
+//!     type Eval = f64;
+//!
+//!     fn evaluate(&self, x: &VecPos) -> f64 {
+//!         -x.0.iter().zip(&self.0).map(|(x, c)| (x - c) * (x - c)).sum::<f64>()
+//!     }
+//! }
+//!
+//! // The negated sum of absolute values, maximized at the origin.
+//! struct Manhattan;
+//!
+//! impl Problem for Manhattan {
+//!     type Solution = VecPos;
+//!     type Eval = f64;
+//!
+//!     fn evaluate(&self, x: &VecPos) -> f64 {
+//!         -x.0.iter().map(|x| x.abs()).sum::<f64>()
+//!     }
+//! }
+//!
+//! fn random_vec() -> VecPos {
+//!     use rand::{random, Closed01};
+//!
+//!     VecPos((0..2).map(|_| 4.0 * random::<Closed01<f64>>().0 - 2.0).collect())
+//! }
+//!
+//! fn main() {
+//!     let problem = ShiftedSphere(vec![1.0, -0.5]);
+//!     let mut pso = PSO::new_with_problem(16, problem, random_vec, 0.7, 1.4, 1.4);
+//!     for _ in 0..200 {
+//!         pso.update();
+//!     }
+//!     let (best, _) = pso.best();
+//!     assert!((best.solution().0[0] - 1.0).abs() < 1e-3);
+//!     assert!((best.solution().0[1] + 0.5).abs() < 1e-3);
+//!
+//!     let mut pso = PSO::new_with_problem(16, Manhattan, random_vec, 0.7, 1.4, 1.4);
+//!     for _ in 0..200 {
+//!         pso.update();
+//!     }
+//!     let (best, e) = pso.best();
+//!     assert_eq!(e, Manhattan.evaluate(best.solution()));
+//!     assert!(e > -1e-3);
+//! }
+//! ```
+
+use std::ops;
+use std::sync::Arc;
+
+use Candidate;
+use pso::Particle;
+use firefly::Firefly;
+
+/// An objective over solutions of type `Solution`, to be maximized like `Candidate::eval`.
+pub trait Problem {
+    type Solution: Clone +
+                   ops::Add<Output = Self::Solution> +
+                   ops::Sub<Output = Self::Solution> +
+                   ops::Mul<f64, Output = Self::Solution>;
+    type Eval: Clone + PartialOrd;
+
+    fn evaluate(&self, s: &Self::Solution) -> Self::Eval;
+}
+
+/// A problem with a distance between its solutions, required by `FireflyAlg::new_with_problem`.
+pub trait MetricProblem: Problem {
+    /// Returns the distance between two solutions.
+    fn distance(&self, a: &Self::Solution, b: &Self::Solution) -> f64;
+}
+
+/// A candidate at a solution of the problem `P`, which evaluates it.
+///
+/// The candidates of a population share their problem through an `Arc`. Besides the solution,
+/// a candidate keeps the velocity and personal best of `pso::Particle`. The candidate does not
+/// implement `RandomCandidate`, so construct the optimizers with `new_with_problem`. The
+/// optional methods of `Candidate` and `pso::Particle` are not delegated to the problem, and
+/// neither are the capability traits such as `pso::Metric`, so the features needing them do not
+/// compile for it. It is a `Firefly` if the problem is a `MetricProblem`.
+pub struct ProblemCandidate<P: Problem> {
+    pos: P::Solution,
+    vel: P::Solution,
+    best: (P::Solution, P::Eval),
+    problem: Arc<P>,
+}
+
+impl<P: Problem> ProblemCandidate<P> {
+    /// Creates a candidate at `solution`, at rest and with its personal best there.
+    pub fn new(solution: P::Solution, problem: Arc<P>) -> Self {
+        let eval = problem.evaluate(&solution);
+        Self {
+            vel: solution.clone() * 0.0,
+            best: (solution.clone(), eval),
+            pos: solution,
+            problem,
+        }
+    }
+
+    pub fn solution(&self) -> &P::Solution {
+        &self.pos
+    }
+
+    pub fn into_solution(self) -> P::Solution {
+        self.pos
+    }
+
+    pub fn problem(&self) -> &P {
+        &self.problem
+    }
+}
+
+impl<P: Problem> Clone for ProblemCandidate<P> {
+    fn clone(&self) -> Self {
+        Self {
+            pos: self.pos.clone(),
+            vel: self.vel.clone(),
+            best: self.best.clone(),
+            problem: self.problem.clone(),
+        }
+    }
+}
+
+impl<P: Problem> Candidate for ProblemCandidate<P> {
+    type Pos = P::Solution;
+    type Eval = P::Eval;
+
+    fn eval(&self) -> Self::Eval {
+        self.problem.evaluate(&self.pos)
+    }
+
+    fn pos(&self) -> Self::Pos {
+        self.pos.clone()
+    }
+    fn pos_mut(&mut self) -> &mut Self::Pos {
+        &mut self.pos
+    }
+}
+
+impl<P: Problem> Particle for ProblemCandidate<P> {
+    fn vel(&self) -> Self::Pos {
+        self.vel.clone()
+    }
+    fn best(&self) -> (Self::Pos, Self::Eval) {
+        self.best.clone()
+    }
+    fn vel_mut(&mut self) -> &mut Self::Pos {
+        &mut self.vel
+    }
+    fn best_mut(&mut self) -> &mut (Self::Pos, Self::Eval) {
+        &mut self.best
+    }
+}
+
+impl<P: MetricProblem> Firefly for ProblemCandidate<P> {
+    fn distance(&self, rhs: &Self) -> f64 {
+        self.problem.distance(&self.pos, &rhs.pos)
+    }
+}
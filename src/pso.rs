@@ -5,8 +5,8 @@
 //! extern crate meta_heuristics;
 //! extern crate rand;
 //!
-//! use meta_heuristics::pso;
-//! use std::cmp;
+//! use meta_heuristics::{pso, Candidate, RandomCandidate, SeededCandidate};
+//! use rand::{Closed01, Rng};
 //!
 //! #[derive(Clone, Copy)]
 //! struct Particle {
@@ -19,36 +19,32 @@
 //!     1.0 - ((x - 3.0) * x + 2.0) * x * x
 //! }
 //!
-//! impl PartialEq for Particle {
-//!     fn eq(&self, rhs: &Self) -> bool {
-//!         self.pos == rhs.pos
-//!     }
-//! }
-//!
-//! impl Eq for Particle {}
+//! impl Candidate for Particle {
+//!     type Pos = f64;
+//!     type Eval = f64;
 //!
-//! impl PartialOrd for Particle {
-//!     fn partial_cmp(&self, rhs: &Self) -> Option<cmp::Ordering> {
-//!         Some(self.cmp(rhs))
+//!     fn eval(&self) -> Self::Eval {
+//!         eval_func(self.pos)
 //!     }
-//! }
 //!
-//! impl Ord for Particle {
-//!     fn cmp(&self, rhs: &Self) -> cmp::Ordering {
-//!         let self_eval = eval_func(self.pos);
-//!         let rhs_eval = eval_func(rhs.pos);
-//!         self_eval.partial_cmp(&rhs_eval).unwrap()
+//!     fn pos(&self) -> Self::Pos {
+//!         self.pos
+//!     }
+//!     fn pos_mut(&mut self) -> &mut Self::Pos {
+//!         &mut self.pos
 //!     }
 //! }
 //!
-//! impl pso::Particle for Particle {
-//!     type Pos = f64;
-//!     type Eval = f64;
-//!
+//! impl RandomCandidate for Particle {
 //!     fn new_random() -> Self {
-//!         use rand::{random, Closed01};
+//!         Self::new_random_seeded(rand::random())
+//!     }
+//! }
 //!
-//!         let Closed01(x) = random::<Closed01<f64>>();
+//! impl SeededCandidate for Particle {
+//!     fn new_random_seeded(seed: u64) -> Self {
+//!         let mut rng = meta_heuristics::seeded_rng(seed);
+//!         let Closed01(x) = rng.gen::<Closed01<f64>>();
 //!         let x = 4.0 * x - 1.0;
 //!         Self {
 //!             pos: x,
@@ -56,23 +52,15 @@
 //!             best: (x, eval_func(x)),
 //!         }
 //!     }
+//! }
 //!
-//!     fn eval(&self) -> Self::Eval {
-//!         eval_func(self.pos)
-//!     }
-//!
-//!     fn pos(&self) -> Self::Pos {
-//!         self.pos
-//!     }
+//! impl pso::Particle for Particle {
 //!     fn vel(&self) -> Self::Pos {
 //!         self.vel
 //!     }
 //!     fn best(&self) -> (Self::Pos, Self::Eval) {
 //!         self.best
 //!     }
-//!     fn pos_mut(&mut self) -> &mut Self::Pos {
-//!         &mut self.pos
-//!     }
 //!     fn vel_mut(&mut self) -> &mut Self::Pos {
 //!         &mut self.vel
 //!     }
@@ -82,7 +70,8 @@
 //! }
 //!
 //! fn main() {
-//!     let mut pso: pso::PSO<Particle> = pso::PSO::new(8, 0.9, 0.9, 0.9);
+//!     // Seeded, the swarm and therefore the whole run are reproducible.
+//!     let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.9, 0.9, 0.9, 42);
 //!
 //!     for i in 0..10 {
 //!         pso.update();
@@ -94,87 +83,4258 @@
 //! }
 //! ```
 
-use std::ops;
+use std::{cmp, error, fmt, io, slice};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::ops::ControlFlow;
+#[cfg(feature = "plotters")]
+use std::path::Path;
+use std::sync::Arc;
+use rand::{self, Isaac64Rng, Rng, SeedableRng, ThreadRng};
+use rand::distributions::normal::StandardNormal;
 
-pub trait Particle {
-    type Pos: Copy + ops::Add<Output = Self::Pos> + ops::Sub<Output = Self::Pos> + ops::Mul<f64, Output = Self::Pos>;
-    type Eval: Copy + PartialOrd;
+use {Candidate, Components, Optimizer, RandomCandidate, SeededCandidate};
+use archive::Archive;
+use error::MetaHeuristicsError;
+use problem::{Problem, ProblemCandidate};
 
-    fn new_random() -> Self;
-    fn eval(&self) -> Self::Eval;
+pub trait Particle: Candidate {
+    /// Evaluates a whole swarm at once, returning the evals in the same order as `particles`.
+    ///
+    /// `PSO` calls this instead of `eval` so that objectives which can be vectorized or share
+    /// setup cost across candidates can override it. The default maps `eval` over each particle.
+    fn eval_batch(particles: &[Self]) -> Vec<Self::Eval>
+        where Self: Sized
+    {
+        particles.iter().map(Self::eval).collect()
+    }
 
-    fn pos(&self) -> Self::Pos;
     fn vel(&self) -> Self::Pos;
     fn best(&self) -> (Self::Pos, Self::Eval);
-    fn pos_mut(&mut self) -> &mut Self::Pos;
     fn vel_mut(&mut self) -> &mut Self::Pos;
     fn best_mut(&mut self) -> &mut (Self::Pos, Self::Eval);
 }
 
-pub struct PSO<T: Particle> {
+/// A particle whose velocity has a magnitude, as the velocity diagnostics such as
+/// `PSO::max_velocity_magnitude` need.
+pub trait VelocityNorm: Particle {
+    /// Returns the magnitude (norm) of the velocity.
+    fn vel_magnitude(&self) -> f64;
+}
+
+/// A particle whose positions lie in a metric space, as the position-space diagnostics such as
+/// `PSO::diversity` and the features measuring moves need.
+pub trait Metric: Particle {
+    /// Returns the distance between two positions.
+    fn pos_distance(a: &Self::Pos, b: &Self::Pos) -> f64;
+}
+
+/// A particle whose positions can be compared componentwise, as the search bounds, the velocity
+/// limit and `PSO::position_bounds` need.
+pub trait ComponentBounds: Particle {
+    /// Returns the componentwise minimum of two positions.
+    fn component_min(a: &Self::Pos, b: &Self::Pos) -> Self::Pos;
+    /// Returns the componentwise maximum of two positions.
+    fn component_max(a: &Self::Pos, b: &Self::Pos) -> Self::Pos;
+}
+
+/// A particle which can be drawn in a box, required by `PSO::new_in_region`, `PSO::new_bounded`
+/// and `PSO::set_reinitialization`.
+pub trait RegionSampling: Particle {
+    /// Creates a random particle positioned uniformly in the box between `lower` and `upper`,
+    /// drawing from `rng`.
+    fn random_in<R: Rng>(lower: &Self::Pos, upper: &Self::Pos, rng: &mut R) -> Self;
+}
+
+/// A particle which can draw random velocities, required by `PSO::randomize_velocities`,
+/// `PSO::restart_around_best`, `PSO::set_local_search` and `PSO::set_min_step`.
+pub trait RandomVelocity: Particle {
+    /// Returns a random velocity whose components are uniformly distributed in
+    /// `[-scale, scale]`.
+    fn random_vel<R: Rng>(scale: f64, rng: &mut R) -> Self::Pos;
+}
+
+/// A particle which can be drawn from a normal distribution, required by `PSO::set_bare_bones`.
+pub trait Gaussian: Particle {
+    /// Returns a random position whose components are normally distributed with the
+    /// corresponding components of `mean` and `std`.
+    ///
+    /// Components of `std` may be negative, and their absolute values are the standard
+    /// deviations; `mean + std * z` with standard normal `z` samples correctly as is.
+    fn gaussian_sample<R: Rng>(mean: &Self::Pos, std: &Self::Pos, rng: &mut R) -> Self::Pos;
+}
+
+/// A particle whose position has unit axes, required by `PSO::polish` and
+/// `PSO::set_elitist_learning`.
+pub trait Axes: Particle {
+    /// Returns the unit vector along dimension `i` of the position, so that a step can move one
+    /// component at a time.
+    fn axis(&self, i: usize) -> Self::Pos;
+}
+
+/// A particle whose position can gain dimensions, required by `PSO::grow_dimension`.
+pub trait GrowDimension: Particle {
+    /// Appends a dimension to the position, initialized to `value`, and one to the velocity, so
+    /// that `dim` grows by one.
+    ///
+    /// As every particle starts the new dimension at the same value, a zero velocity component
+    /// would leave the swarm stuck there; a small random one lets it explore the dimension. Draw
+    /// it from `rng`, the swarm's generator, to keep seeded runs reproducible.
+    fn push_dimension<R: Rng>(&mut self, value: f64, rng: &mut R);
+}
+
+/// A particle which can take Lévy flights, as enabled by `PSO::set_levy`.
+pub trait Levy: Particle {
+    /// Displaces the position by a heavy-tailed Lévy-flight step, drawing from `rng`.
+    ///
+    /// `levy_sample` draws the step lengths with the given `exponent`.
+    fn levy_step<R: Rng>(&mut self, exponent: f64, rng: &mut R);
+}
+
+/// Which particles' personal bests make up the social attractor of each particle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Topology {
+    /// Every particle is attracted to the swarm's current best (gbest).
+    Global,
+    /// Each particle is attracted to the best personal best among itself and the `k` particles
+    /// on each side of it, with indices wrapping around (lbest).
+    Ring(usize),
+    /// Each particle is attracted to the best personal best among the neighbors listed for it
+    /// by `PSO::set_topology_graph`, which generalizes the other topologies.
+    ///
+    /// Passing this to `PSO::set_topology` reuses the last graph set, and so does `PSO::reset`.
+    /// Using it while the graph does not list the neighbors of every particle, including after
+    /// adding or removing particles, panics.
+    Graph,
+}
+
+/// How positions leaving the search bounds set with `PSO::set_bounds` are brought back, unless
+/// `PSO::set_reinitialization` replaces the particles instead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BoundaryBehavior {
+    /// Each violating component is clamped to the bound it crossed.
+    Clamp,
+    /// Each violating component is mirrored at the bound it crossed, and then clamped in case
+    /// the mirror image overshoots the opposite bound.
+    ///
+    /// Positions only support scaling as a whole, so velocities are kept as they are; the
+    /// velocity update pulls the particle back towards its attractors.
+    Reflect,
+}
+
+/// The velocity limit of `PSO::bounded` recommended for most problems, as a fraction of the
+/// range of the search bounds.
+pub const DEFAULT_VELOCITY_FRACTION: f64 = 0.2;
+
+/// The swarm size up to which `PSO::enable_parallel_eval` still evaluates sequentially, unless
+/// changed with `PSO::set_parallel_threshold`.
+pub const DEFAULT_PARALLEL_THRESHOLD: usize = 64;
+
+/// A step size adapted by Rechenberg's 1/5th success rule, as evolution strategies do.
+///
+/// Every `window` recorded trials, the step size is divided by 0.817 if more than the target
+/// ratio of them succeeded, and multiplied by 0.817 if fewer did: large steps keep being taken
+/// while they pay off, and shrink once they overshoot.
+///
+/// # Example
+/// ```
+/// use meta_heuristics::pso::AdaptiveStep;
+///
+/// let mut step = AdaptiveStep::new(1.0, 0.2, 10);
+/// for _ in 0..9 {
+///     step.record(true);
+/// }
+/// // The window is not complete yet.
+/// assert_eq!(step.sigma(), 1.0);
+/// step.record(true);
+/// assert!(step.sigma() > 1.0);
+///
+/// let grown = step.sigma();
+/// for _ in 0..10 {
+///     step.record(false);
+/// }
+/// assert!(step.sigma() < grown);
+///
+/// // Exactly on target, the step size is kept.
+/// let kept = step.sigma();
+/// for i in 0..10 {
+///     step.record(i < 2);
+/// }
+/// assert_eq!(step.sigma(), kept);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AdaptiveStep {
+    sigma: f64,
+    target: f64,
+    window: usize,
+    trials: usize,
+    successes: usize,
+}
+
+/// The factor by which `AdaptiveStep` scales its step size, after Schwefel.
+const STEP_FACTOR: f64 = 0.817;
+
+/// The number of local search steps over which `PSO::set_adaptive_mutation` measures success.
+const MUTATION_WINDOW: usize = 10;
+
+impl AdaptiveStep {
+    /// Starts from the step size `sigma`, aiming at the success ratio `target`, measured over
+    /// windows of `window` trials.
+    pub fn new(sigma: f64, target: f64, window: usize) -> Self {
+        AdaptiveStep {
+            sigma,
+            target,
+            window,
+            trials: 0,
+            successes: 0,
+        }
+    }
+
+    pub fn sigma(&self) -> f64 {
+        self.sigma
+    }
+
+    /// Records whether a trial with the current step size succeeded.
+    pub fn record(&mut self, success: bool) {
+        self.trials += 1;
+        if success {
+            self.successes += 1;
+        }
+        if self.trials >= self.window {
+            let ratio = self.successes as f64 / self.trials as f64;
+            if ratio > self.target {
+                self.sigma /= STEP_FACTOR;
+            } else if ratio < self.target {
+                self.sigma *= STEP_FACTOR;
+            }
+            self.trials = 0;
+            self.successes = 0;
+        }
+    }
+}
+
+/// How `PSO::update` moves the particles.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Mode {
+    /// The canonical velocity update with inertia and the local and global coefficients.
+    Standard,
+    /// The velocity update without the social term, for ablation studies. Unlike setting
+    /// `c_global` to 0, the term's random draw is skipped, so the random number stream is not
+    /// consumed by a term that contributes nothing, unless `PSO::set_consistent_rng_draws` is
+    /// enabled.
+    CognitiveOnly,
+    /// The velocity update without the cognitive term, skipping its random draw likewise.
+    SocialOnly,
+    /// A minimal swarm baseline without momentum: each particle moves the fraction `rate` of the
+    /// way toward the global best, `pos + rate * (gbest - pos)`.
+    ///
+    /// Velocities and personal bests play no part, and neither do the coefficients, the
+    /// velocity limit and the topology. The swarm converges quickly, but as nothing but the
+    /// current best attracts the particles, it collapses onto whichever optimum that best lies
+    /// near, and gets trapped there easily.
+    Attraction { rate: f64 },
+}
+
+/// The evolutionary state of a swarm in Zhan et al.'s adaptive PSO (APSO), classified from the
+/// evolutionary factor returned by `PSO::evolutionary_factor`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EvolutionaryState {
+    /// The swarm is spread out and searching for promising regions, with a factor from 0.5 up
+    /// to 0.75.
+    Exploration,
+    /// The swarm is closing in on a region, with a factor from 0.25 up to 0.5.
+    Exploitation,
+    /// The swarm has gathered around its best, with a factor below 0.25.
+    Convergence,
+    /// The best has left the swarm behind for a new region, with a factor of 0.75 or more.
+    JumpingOut,
+}
+
+impl EvolutionaryState {
+    /// Classifies the evolutionary factor `f` into the state whose range contains it.
+    ///
+    /// APSO classifies by fuzzy membership over overlapping ranges; these are the crisp ranges
+    /// splitting [0, 1] into quarters, as its simplified variants use.
+    ///
+    /// # Example
+    /// ```
+    /// use meta_heuristics::pso::EvolutionaryState;
+    ///
+    /// assert_eq!(EvolutionaryState::from_factor(0.1), EvolutionaryState::Convergence);
+    /// assert_eq!(EvolutionaryState::from_factor(0.25), EvolutionaryState::Exploitation);
+    /// assert_eq!(EvolutionaryState::from_factor(0.6), EvolutionaryState::Exploration);
+    /// assert_eq!(EvolutionaryState::from_factor(1.0), EvolutionaryState::JumpingOut);
+    /// ```
+    pub fn from_factor(f: f64) -> Self {
+        if f < 0.25 {
+            EvolutionaryState::Convergence
+        } else if f < 0.5 {
+            EvolutionaryState::Exploitation
+        } else if f < 0.75 {
+            EvolutionaryState::Exploration
+        } else {
+            EvolutionaryState::JumpingOut
+        }
+    }
+}
+
+pub struct PSO<T: Particle, R: Rng = ThreadRng> {
     particles: Vec<T>,
+    evals: Vec<T::Eval>,
     inetia: f64,
     c_local: f64,
     c_global: f64,
     best: (T, T::Eval),
+    // Whether `best` was set with `set_global_best`, to be kept until a particle beats it.
+    injected_best: bool,
+    archive: Archive<T>,
+    topology: Topology,
+    topology_graph: Vec<Vec<usize>>,
+    mode: Mode,
+    bare_bones: Option<GaussianSample<T::Pos, R>>,
+    consistent_draws: bool,
+    neighborhood_best: Vec<usize>,
+    levy: Option<LevyFlights<T, R>>,
+    teleport: Option<f64>,
+    improvement: Option<Improvement<T::Eval>>,
+    sharing: Option<Sharing<T::Pos, T::Eval>>,
+    sampler: ::Sampler<T>,
+    bounds: Option<Bounds<T::Pos>>,
+    boundary: BoundaryBehavior,
+    reinitialization: Option<Reinitialization<T::Pos, T, R>>,
+    vel_limit: Option<Bounds<T::Pos>>,
+    min_step: Option<MinStep<T::Pos, R>>,
+    unification: Option<f64>,
+    c_centroid: f64,
+    decay_iters: Option<usize>,
+    inertia_fn: Option<InertiaFn<T, R>>,
+    apso: Option<Distance<T::Pos>>,
+    comparator: Option<Comparator<T::Eval>>,
+    eval_cache: Option<CachedEval<T, T::Eval>>,
+    cache_hits: usize,
+    parallel_eval: Option<ParallelEval<T, T::Eval>>,
+    parallel_best: Option<ParallelBest<T::Eval>>,
+    parallel_threshold: usize,
+    resampling: Option<(usize, ResampledEval<T, T::Eval>)>,
+    time_varying: bool,
+    repair: bool,
+    validate: bool,
+    elitism: bool,
+    // Formats the best eval in the trace of `update`, set with `PSO::set_log_best_eval`.
+    #[cfg(feature = "log")]
+    log_eval: Option<fn(&T::Eval) -> String>,
+    local_search: Option<LocalSearch<T::Pos, R>>,
+    elitist_learning: Option<ElitistLearning<T, T::Pos>>,
+    adaptive_target: Option<f64>,
+    mutation_step: Option<AdaptiveStep>,
+    record_history: bool,
+    history: Vec<T::Eval>,
+    record_best_positions: bool,
+    best_position_history: Vec<T::Pos>,
+    stagnation: usize,
+    // Updates since each particle last improved its personal best.
+    stale: Vec<usize>,
+    frozen: Vec<bool>,
+    improved: Vec<bool>,
+    best_seen: T::Eval,
+    // `best_seen` before the last update.
+    prev_best_seen: T::Eval,
+    // The best eval found so far as of each update, starting from the initial population.
+    best_trace: Vec<T::Eval>,
+    initial_best_eval: T::Eval,
+    eval_count: usize,
+    iteration: usize,
+    rng: R,
+}
+
+/// Minimum increase of an eval, converted to `f64`, that counts as an improvement.
+struct Improvement<E> {
+    eps: f64,
+    to_f64: fn(&E) -> f64,
+}
+
+/// The Lévy flights enabled with `PSO::set_levy`, with the step of `Levy` they take.
+struct LevyFlights<T, R> {
+    prob: f64,
+    exponent: f64,
+    step: fn(&mut T, f64, &mut R),
+}
+
+/// Sharing radius of fitness sharing, with the distance of `Metric` and the conversion of
+/// evals to raw fitness.
+struct Sharing<P, E> {
+    sigma: f64,
+    distance: Distance<P>,
+    to_f64: fn(&E) -> f64,
+}
+
+/// The minimum step length set with `PSO::set_min_step`, with the distance of `Metric`
+/// measuring the moves and the random velocity of `RandomVelocity` directing the steps.
+struct MinStep<P, R> {
+    length: f64,
+    distance: Distance<P>,
+    random_vel: RandomVel<P, R>,
+}
+
+/// The elitist learning set with `PSO::set_elitist_learning`, with the axes of `Axes` along
+/// which the best is moved.
+struct ElitistLearning<T, P> {
+    sigma_start: f64,
+    sigma_end: f64,
+    axis: fn(&T, usize) -> P,
+}
+
+/// The replacement of stray particles set with `PSO::set_reinitialization`, with the distance
+/// of `Metric` detecting them and the sampling of `RegionSampling` replacing them.
+struct Reinitialization<P, T, R> {
+    distance: Distance<P>,
+    random_in: fn(&P, &P, &mut R) -> T,
+}
+
+/// The local search set with `PSO::set_local_search`, with the random velocity of
+/// `RandomVelocity` perturbing the best.
+struct LocalSearch<P, R> {
+    steps: usize,
+    step_size: f64,
+    random_vel: RandomVel<P, R>,
+}
+
+/// A box between `lower` and `upper`, with the componentwise minimum and maximum of
+/// `ComponentBounds` clamping into it.
+struct Bounds<P> {
+    lower: P,
+    upper: P,
+    min: fn(&P, &P) -> P,
+    max: fn(&P, &P) -> P,
+}
+
+impl<P> Bounds<P> {
+    /// Creates the box, clamping with the componentwise minimum and maximum of `T`.
+    fn new<T: ComponentBounds<Pos = P>>(lower: P, upper: P) -> Self {
+        Bounds {
+            lower,
+            upper,
+            min: T::component_min,
+            max: T::component_max,
+        }
+    }
+
+    /// Clamps `pos` into the box componentwise.
+    fn clamp(&self, pos: &P) -> P {
+        (self.min)(&(self.max)(pos, &self.lower), &self.upper)
+    }
+}
+
+/// The distance between two positions, captured from `Metric` by the setters which need it.
+type Distance<P> = fn(&P, &P) -> f64;
+
+/// A random velocity of the given scale, captured from `RandomVelocity` by the setters which
+/// need it.
+type RandomVel<P, R> = fn(f64, &mut R) -> P;
+
+/// A normally distributed position of the given mean and standard deviation, captured from
+/// `Gaussian` by `PSO::set_bare_bones`.
+type GaussianSample<P, R> = fn(&P, &P, &mut R) -> P;
+
+/// The coefficients and population size of a `PSO`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PsoConfig {
+    pub particles_num: usize,
+    pub inertia: f64,
+    pub c_local: f64,
+    pub c_global: f64,
+}
+
+impl PsoConfig {
+    /// Returns literature-backed defaults for a problem with `dimensions` decision variables.
+    ///
+    /// * The population size is `10 + floor(2 * sqrt(dimensions))`, as in the Standard PSO 2006.
+    /// * The coefficients are Clerc and Kennedy's constriction values, written as an inertia
+    ///   weight: `inertia = 0.7298` and `c_local = c_global = 1.49618`. This is the constriction
+    ///   factor `chi = 0.7298` applied to `phi_1 = phi_2 = 2.05`, which keeps the swarm from
+    ///   diverging without velocity clamping.
+    ///
+    /// # Example
+    /// ```
+    /// use meta_heuristics::pso::PsoConfig;
+    ///
+    /// assert_eq!(PsoConfig::recommended(1).particles_num, 12);
+    /// assert_eq!(PsoConfig::recommended(30).particles_num, 20);
+    ///
+    /// let config = PsoConfig::recommended(10);
+    /// assert_eq!(config.particles_num, 16);
+    /// assert_eq!(config.inertia, 0.7298);
+    /// assert_eq!(config.c_local, config.c_global);
+    /// ```
+    pub fn recommended(dimensions: usize) -> Self {
+        PsoConfig {
+            particles_num: 10 + (2.0 * (dimensions as f64).sqrt()) as usize,
+            inertia: 0.7298,
+            c_local: 1.49618,
+            c_global: 1.49618,
+        }
+    }
+}
+
+/// An ordering of evals overriding `PartialOrd`, set with `PSO::set_comparator`.
+type Comparator<E> = Box<dyn Fn(&E, &E) -> cmp::Ordering + Send>;
+
+/// A schedule computing the inertia of each update, set with `PSO::set_inertia_fn`.
+type InertiaFn<T, R> = Box<dyn FnMut(&RunContext<T, R>) -> f64 + Send>;
+
+/// Evaluates a swarm through the cache enabled with `PSO::enable_eval_cache`, returning the
+/// evals and the number of evaluations actually made.
+type CachedEval<T, E> = Box<dyn FnMut(&[T]) -> (Vec<E>, usize) + Send>;
+
+/// Evaluates a swarm on several threads, as enabled with `PSO::enable_parallel_eval`.
+type ParallelEval<T, E> = Box<dyn Fn(&[T]) -> Vec<E> + Send>;
+
+/// Selects the index of the best eval on several threads, as enabled with
+/// `PSO::enable_parallel_eval`.
+type ParallelBest<E> = Box<dyn Fn(&[E]) -> usize + Send>;
+
+/// Evaluates a particle as the average of its evals, as enabled with `PSO::set_resampling`.
+type ResampledEval<T, E> = Box<dyn Fn(&T) -> E + Send>;
+
+/// A view of a running `PSO`, passed to the schedule set with `PSO::set_inertia_fn` and to the
+/// `Termination` criteria of `PSO::run_until`.
+pub struct RunContext<'a, T: Particle + 'a, R: Rng + 'a = ThreadRng> {
+    pso: &'a PSO<T, R>,
+}
+
+impl<'a, T, R> RunContext<'a, T, R>
+    where T: Particle + Clone,
+          R: Rng
+{
+    /// Returns the number of `update` calls completed so far, not counting one in progress.
+    pub fn iteration(&self) -> usize {
+        self.pso.iteration
+    }
+
+    /// Returns the eval of the swarm's current best.
+    pub fn best_eval(&self) -> &T::Eval {
+        &self.pso.best.1
+    }
+
+    /// Returns whether `a` is better than `b` by the comparator set with `PSO::set_comparator`,
+    /// or by `PartialOrd` if none is set.
+    pub fn is_better(&self, a: &T::Eval, b: &T::Eval) -> bool {
+        self.pso.is_better(a, b)
+    }
+}
+
+impl<'a, T, R> RunContext<'a, T, R>
+    where T: Metric + Clone,
+          R: Rng
+{
+    /// Returns `PSO::diversity` of the swarm.
+    pub fn diversity(&self) -> f64 {
+        self.pso.diversity()
+    }
+}
+
+/// A stopping criterion of `PSO::run_until`, checked before every update.
+///
+/// The built-in criteria combine with `Any` and `All` into compound conditions.
+///
+/// # Example
+/// ```
+/// use meta_heuristics::pso::{Any, DiversityBelow, MaxIters, Metric, TargetEval, Termination};
+///
+/// // Stops at the target, once the swarm has collapsed, or after 1000 updates in total.
+/// fn stop<T: Metric<Eval = f64> + Clone + 'static>(target: f64) -> Any<T> {
+///     let criteria: Vec<Box<dyn Termination<T>>> =
+///         vec![Box::new(TargetEval(target)),
+///              Box::new(DiversityBelow(1e-3)),
+///              Box::new(MaxIters(1000))];
+///     Any(criteria)
+/// }
+/// # fn main() {}
+/// ```
+pub trait Termination<T: Particle, R: Rng = ThreadRng> {
+    /// Returns whether the run should stop before the next update.
+    fn should_stop(&mut self, ctx: &RunContext<T, R>) -> bool;
+}
+
+/// Stops once the swarm's best eval reaches the target, i.e. once the target is no better than
+/// it by the comparator set with `PSO::set_comparator`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TargetEval<E>(pub E);
+
+impl<T, R> Termination<T, R> for TargetEval<T::Eval>
+    where T: Particle + Clone,
+          R: Rng
+{
+    fn should_stop(&mut self, ctx: &RunContext<T, R>) -> bool {
+        !ctx.is_better(&self.0, ctx.best_eval())
+    }
+}
+
+/// Stops once `PSO::diversity` falls below the threshold.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DiversityBelow(pub f64);
+
+impl<T, R> Termination<T, R> for DiversityBelow
+    where T: Metric + Clone,
+          R: Rng
+{
+    fn should_stop(&mut self, ctx: &RunContext<T, R>) -> bool {
+        ctx.diversity() < self.0
+    }
+}
+
+/// Stops once the swarm has been updated the given number of times in total, counting the
+/// updates before the run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MaxIters(pub usize);
+
+impl<T, R> Termination<T, R> for MaxIters
+    where T: Particle + Clone,
+          R: Rng
+{
+    fn should_stop(&mut self, ctx: &RunContext<T, R>) -> bool {
+        ctx.iteration() >= self.0
+    }
+}
+
+/// Stops once any of the criteria does. Every criterion is checked every time.
+pub struct Any<T: Particle, R: Rng = ThreadRng>(pub Vec<Box<dyn Termination<T, R>>>);
+
+impl<T, R> Termination<T, R> for Any<T, R>
+    where T: Particle + Clone,
+          R: Rng
+{
+    fn should_stop(&mut self, ctx: &RunContext<T, R>) -> bool {
+        let mut stop = false;
+        for c in &mut self.0 {
+            stop |= c.should_stop(ctx);
+        }
+        stop
+    }
+}
+
+/// Stops once all of the criteria do at the same time. Every criterion is checked every time.
+pub struct All<T: Particle, R: Rng = ThreadRng>(pub Vec<Box<dyn Termination<T, R>>>);
+
+impl<T, R> Termination<T, R> for All<T, R>
+    where T: Particle + Clone,
+          R: Rng
+{
+    fn should_stop(&mut self, ctx: &RunContext<T, R>) -> bool {
+        let mut stop = true;
+        for c in &mut self.0 {
+            stop &= c.should_stop(ctx);
+        }
+        stop
+    }
+}
+
+/// A report of a run, returned by `PSO::summary`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunSummary<E> {
+    /// The eval of the swarm's best at the end of the run.
+    pub best_eval: E,
+    /// The number of `update` calls made.
+    pub iterations: usize,
+    /// The number of objective evaluations, as counted by `PSO::eval_count`.
+    pub eval_count: usize,
+    /// The iteration of the last improvement of the best eval, as tracked by the stagnation
+    /// counter.
+    pub best_iteration: usize,
+    /// `PSO::diversity` at the end of the run.
+    pub diversity: f64,
+}
+
+impl<E: fmt::Display> fmt::Display for RunSummary<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,
+               "best eval {} found at iteration {} of {}, {} evaluations, diversity {}",
+               self.best_eval,
+               self.best_iteration,
+               self.iterations,
+               self.eval_count,
+               self.diversity)
+    }
+}
+
+/// The distribution of the current evals over the swarm, returned by `PSO::eval_stats`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EvalStats {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    /// The population standard deviation.
+    pub std: f64,
+}
+
+/// The state of one particle as plain fields, returned by `PSO::snapshot`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParticleView<P, E> {
+    pub pos: P,
+    pub vel: P,
+    pub best_pos: P,
+    pub best_eval: E,
 }
 
-impl<T> PSO<T>
-    where T: Particle + Ord + Copy
+/// The portable state of a `PSO`, detached from the optimizer and its random number generator.
+///
+/// Obtained with `PSO::into_state` and turned back into a live optimizer with `PSO::from_state`,
+/// this lets a host program snapshot and resume runs in memory without any serialization.
+///
+/// The state holds the configuration that is plain data, from the topology to the frozen
+/// particles. Settings holding closures or functions of a capability trait are not part of it;
+/// `PSO::from_state` lists them.
+#[derive(Clone, Debug)]
+pub struct PsoState<T: Particle> {
+    pub particles: Vec<T>,
+    pub evals: Vec<T::Eval>,
+    pub inertia: f64,
+    pub c_local: f64,
+    pub c_global: f64,
+    pub best: (T, T::Eval),
+    pub topology: Topology,
+    /// The neighbor lists of `Topology::Graph`, empty if no graph was set.
+    pub topology_graph: Vec<Vec<usize>>,
+    pub mode: Mode,
+    pub boundary: BoundaryBehavior,
+    /// The probability set with `PSO::set_teleport`, if any.
+    pub teleport: Option<f64>,
+    /// The factor set with `PSO::set_unification`, if any.
+    pub unification: Option<f64>,
+    pub c_centroid: f64,
+    /// The horizon set with `PSO::set_linear_decay`, if any.
+    pub decay_iters: Option<usize>,
+    pub consistent_draws: bool,
+    pub time_varying: bool,
+    pub repair: bool,
+    pub validate: bool,
+    pub elitism: bool,
+    /// Whether each particle is frozen with `PSO::freeze`.
+    pub frozen: Vec<bool>,
+    pub parallel_threshold: usize,
+    pub stagnation: usize,
+    pub best_seen: T::Eval,
+    pub initial_best_eval: T::Eval,
+    pub eval_count: usize,
+    pub iteration: usize,
+}
+
+impl<T> PSO<T, ThreadRng>
+    where T: RandomCandidate + Particle + Clone
 {
+    /// Creates a swarm of `particles_num` particles from `T::new_random()`.
+    ///
+    /// Use `from_iter_capped` to get an error rather than a panic for an empty swarm.
+    ///
+    /// # Panics
+    /// Panics if `particles_num` is 0.
     pub fn new(particles_num: usize, inetia: f64, c_local: f64, c_global: f64) -> Self {
-        let mut particles = Vec::with_capacity(particles_num);
-        for _ in 0..particles_num {
-            particles.push(T::new_random());
+        let particles = ::build_population(particles_num, |_| T::new_random());
+
+        Self::with_particles(particles,
+                             ::Sampler::Random(T::new_random),
+                             inetia,
+                             c_local,
+                             c_global,
+                             rand::thread_rng())
+    }
+
+    /// Creates a swarm of the first `particles_num` particles of `iter`, which composes with
+    /// `std::iter::repeat_with` and deterministic generators.
+    ///
+    /// Like every constructor, this keeps the personal bests the particles come with, which
+    /// may be better than their current positions when warm-starting from a checkpoint. The
+    /// swarm's initial best is then the best of the current positions and the personal bests,
+    /// until the first `update` reselects it among the current positions.
+    ///
+    /// # Errors
+    /// Returns `MetaHeuristicsError::EmptyPopulation` if `particles_num` is 0, and
+    /// `MetaHeuristicsError::InsufficientCandidates` if `iter` ends before supplying
+    /// `particles_num` particles.
+    pub fn from_iter_capped<I>(iter: I,
+                               particles_num: usize,
+                               inetia: f64,
+                               c_local: f64,
+                               c_global: f64)
+                               -> Result<Self, MetaHeuristicsError>
+        where I: IntoIterator<Item = T>
+    {
+        if particles_num == 0 {
+            return Err(MetaHeuristicsError::EmptyPopulation);
+        }
+
+        let particles: Vec<T> = iter.into_iter().take(particles_num).collect();
+        if particles.len() < particles_num {
+            return Err(MetaHeuristicsError::InsufficientCandidates {
+                           required: particles_num,
+                           supplied: particles.len(),
+                       });
+        }
+
+        Ok(Self::with_particles(particles,
+                                ::Sampler::Random(T::new_random),
+                                inetia,
+                                c_local,
+                                c_global,
+                                rand::thread_rng()))
+    }
+
+    /// Creates a swarm initialized uniformly in the box between `lower` and `upper`, with
+    /// `RegionSampling::random_in`.
+    ///
+    /// The region is independent of the search bounds set with `set_bounds`: benchmark
+    /// protocols use asymmetric initialization, in a region excluding the optimum, to test
+    /// whether the swarm can leave it.
+    ///
+    /// # Panics
+    /// Panics if `particles_num` is 0.
+    pub fn new_in_region(particles_num: usize,
+                         lower: T::Pos,
+                         upper: T::Pos,
+                         inetia: f64,
+                         c_local: f64,
+                         c_global: f64)
+                         -> Self
+        where T: RegionSampling
+    {
+        let mut rng = rand::thread_rng();
+        let particles = ::build_population(particles_num, |_| {
+            T::random_in(&lower, &upper, &mut rng)
+        });
+
+        Self::with_particles(particles,
+                             ::Sampler::Random(T::new_random),
+                             inetia,
+                             c_local,
+                             c_global,
+                             rng)
+    }
+
+    /// Creates a swarm initialized in the box between `lower` and `upper` like `new_in_region`,
+    /// and confined to it with `set_bounds`.
+    ///
+    /// The bounds are positions, so each dimension has its own range, as problems mixing units
+    /// need.
+    pub fn new_bounded(particles_num: usize,
+                       lower: T::Pos,
+                       upper: T::Pos,
+                       inetia: f64,
+                       c_local: f64,
+                       c_global: f64)
+                       -> Self
+        where T: ComponentBounds + RegionSampling
+    {
+        let mut pso = Self::new_in_region(particles_num,
+                                          lower.clone(),
+                                          upper.clone(),
+                                          inetia,
+                                          c_local,
+                                          c_global);
+        pso.set_bounds(lower, upper);
+        pso
+    }
+
+    /// Creates a swarm from the population size and coefficients in `config`.
+    pub fn with_config(config: PsoConfig) -> Self {
+        Self::new(config.particles_num,
+                  config.inertia,
+                  config.c_local,
+                  config.c_global)
+    }
+
+    /// Creates a swarm whose `i`-th particle is `T::new_random_seeded(base_seed + i)`.
+    ///
+    /// Each particle's initial state therefore depends only on `base_seed` and its index, not on
+    /// the population size or on any shared random stream.
+    ///
+    /// # Panics
+    /// Panics if `particles_num` is 0.
+    ///
+    /// # Example
+    /// ```
+    /// extern crate meta_heuristics;
+    /// extern crate rand;
+    ///
+    /// use meta_heuristics::{pso, Candidate, RandomCandidate, SeededCandidate};
+    /// use rand::{Closed01, Rng};
+    ///
+    /// #[derive(Clone, Copy)]
+    /// struct Particle { pos: f64, vel: f64, best: (f64, f64) }
+    ///
+    /// fn eval_func(x: f64) -> f64 { 1.0 - ((x - 3.0) * x + 2.0) * x * x }
+    ///
+    /// impl Candidate for Particle {
+    ///     type Pos = f64;
+    ///     type Eval = f64;
+    ///
+    ///     fn eval(&self) -> f64 { eval_func(self.pos) }
+    ///     fn pos(&self) -> f64 { self.pos }
+    ///     fn pos_mut(&mut self) -> &mut f64 { &mut self.pos }
+    /// }
+    ///
+    /// impl RandomCandidate for Particle {
+    ///     fn new_random() -> Self {
+    ///         Self::new_random_seeded(rand::random())
+    ///     }
+    /// }
+    ///
+    /// impl SeededCandidate for Particle {
+    ///     fn new_random_seeded(seed: u64) -> Self {
+    ///         let mut rng = meta_heuristics::seeded_rng(seed);
+    ///         let Closed01(x) = rng.gen::<Closed01<f64>>();
+    ///         let x = 4.0 * x - 1.0;
+    ///         Self { pos: x, vel: 0.0, best: (x, eval_func(x)) }
+    ///     }
+    /// }
+    ///
+    /// impl pso::Particle for Particle {
+    ///     fn vel(&self) -> f64 { self.vel }
+    ///     fn best(&self) -> (f64, f64) { self.best }
+    ///     fn vel_mut(&mut self) -> &mut f64 { &mut self.vel }
+    ///     fn best_mut(&mut self) -> &mut (f64, f64) { &mut self.best }
+    /// }
+    ///
+    /// fn main() {
+    ///     let small: pso::PSO<Particle> = pso::PSO::new_stratified(4, 42, 0.9, 0.9, 0.9);
+    ///     let large: pso::PSO<Particle> = pso::PSO::new_stratified(16, 42, 0.9, 0.9, 0.9);
+    ///
+    ///     for (a, b) in small.particles().iter().zip(large.particles()) {
+    ///         assert_eq!(a.pos, b.pos);
+    ///     }
+    /// }
+    /// ```
+    pub fn new_stratified(particles_num: usize,
+                          base_seed: u64,
+                          inetia: f64,
+                          c_local: f64,
+                          c_global: f64)
+                          -> Self
+        where T: SeededCandidate
+    {
+        let particles = ::build_population(particles_num, |i| {
+            T::new_random_seeded(base_seed.wrapping_add(i as u64))
+        });
+
+        Self::with_particles(particles,
+                             ::Sampler::Random(T::new_random),
+                             inetia,
+                             c_local,
+                             c_global,
+                             rand::thread_rng())
+    }
+}
+
+impl<T> PSO<T, ThreadRng>
+    where T: Particle + Clone
+{
+    /// Creates a swarm whose particles are drawn from `sampler` instead of `T::new_random()`.
+    ///
+    /// The sampler is kept and also used for the particles created later, by `restart` and
+    /// `add_particles`, so `T` need not implement `RandomCandidate` at all. The other
+    /// constructors sample `RandomCandidate::new_random` instead.
+    ///
+    /// # Panics
+    /// Panics if `particles_num` is 0.
+    ///
+    /// # Example
+    /// ```
+    /// extern crate meta_heuristics;
+    ///
+    /// use meta_heuristics::{pso, Candidate};
+    ///
+    /// #[derive(Clone, Copy)]
+    /// struct Particle { pos: f64, vel: f64, best: (f64, f64) }
+    ///
+    /// fn eval_func(x: f64) -> f64 { 1.0 - ((x - 3.0) * x + 2.0) * x * x }
+    ///
+    /// impl Candidate for Particle {
+    ///     type Pos = f64;
+    ///     type Eval = f64;
+    ///
+    ///     fn eval(&self) -> f64 { eval_func(self.pos) }
+    ///     fn pos(&self) -> f64 { self.pos }
+    ///     fn pos_mut(&mut self) -> &mut f64 { &mut self.pos }
+    /// }
+    ///
+    /// impl pso::Particle for Particle {
+    ///     fn vel(&self) -> f64 { self.vel }
+    ///     fn best(&self) -> (f64, f64) { self.best }
+    ///     fn vel_mut(&mut self) -> &mut f64 { &mut self.vel }
+    ///     fn best_mut(&mut self) -> &mut (f64, f64) { &mut self.best }
+    /// }
+    ///
+    /// fn main() {
+    ///     // Evenly spaced starting points over [-1, 3].
+    ///     let mut i = 0;
+    ///     let sampler = move || {
+    ///         let x = -1.0 + 0.5 * (i % 9) as f64;
+    ///         i += 1;
+    ///         Particle { pos: x, vel: 0.0, best: (x, eval_func(x)) }
+    ///     };
+    ///
+    ///     let mut pso: pso::PSO<Particle> = pso::PSO::new_with_sampler(8, sampler, 0.9, 0.9, 0.9);
+    ///     assert_eq!(pso.particles()[2].pos, 0.0);
+    ///
+    ///     pso.restart(0);
+    ///     // The sampler continues where the initial population left off.
+    ///     assert!(pso.particles().iter().any(|p| p.pos == 3.0));
+    /// }
+    /// ```
+    pub fn new_with_sampler<F>(particles_num: usize,
+                               mut sampler: F,
+                               inetia: f64,
+                               c_local: f64,
+                               c_global: f64)
+                               -> Self
+        where F: FnMut() -> T + Send + 'static
+    {
+        let particles = ::build_population(particles_num, |_| sampler());
+
+        Self::with_particles(particles,
+                             ::Sampler::Closure(Box::new(sampler)),
+                             inetia,
+                             c_local,
+                             c_global,
+                             rand::thread_rng())
+    }
+}
+
+impl<T: SeededCandidate + Particle + Clone> PSO<T, Isaac64Rng> {
+    /// Creates a swarm which is fully determined by `seed`, for reproducible examples and tests
+    /// without managing a random number generator.
+    ///
+    /// The generator of `seeded_rng(seed)` draws the seed of each particle for
+    /// `SeededCandidate::new_random_seeded`, as `reset_with_seed` does, then the seed of the
+    /// sampler, and then the update coefficients. The sampler seeds the particles created later,
+    /// by `restart`, `reset`, `reinit_stale`, teleporting and `add_particles`, from a generator
+    /// of its own.
+    ///
+    /// # Panics
+    /// Panics if `particles_num` is 0.
+    pub fn new_seeded(particles_num: usize,
+                      inetia: f64,
+                      c_local: f64,
+                      c_global: f64,
+                      seed: u64)
+                      -> Self
+        where T: 'static
+    {
+        let mut rng = ::seeded_rng(seed);
+        let particles = ::build_population(particles_num, |_| {
+            T::new_random_seeded(rng.next_u64())
+        });
+        let sampler = ::Sampler::seeded(rng.next_u64());
+
+        Self::with_particles(particles,
+                             sampler,
+                             inetia,
+                             c_local,
+                             c_global,
+                             rng)
+    }
+}
+
+impl<T: Particle + Clone> PSO<T, Isaac64Rng> {
+    /// Creates a swarm like `new_with_sampler`, but drawing the update coefficients' randomness
+    /// from `seeded_rng(seed)`, so that a deterministic sampler makes the whole run
+    /// reproducible.
+    ///
+    /// # Panics
+    /// Panics if `particles_num` is 0.
+    pub fn new_with_sampler_seeded<F>(particles_num: usize,
+                                      mut sampler: F,
+                                      inetia: f64,
+                                      c_local: f64,
+                                      c_global: f64,
+                                      seed: u64)
+                                      -> Self
+        where F: FnMut() -> T + Send + 'static
+    {
+        let particles = ::build_population(particles_num, |_| sampler());
+
+        Self::with_particles(particles,
+                             ::Sampler::Closure(Box::new(sampler)),
+                             inetia,
+                             c_local,
+                             c_global,
+                             ::seeded_rng(seed))
+    }
+}
+
+impl<T, R> PSO<T, R>
+    where T: Particle + Clone,
+          R: Rng + Clone
+{
+    /// Returns a copy of the random number generator in its current state, which draws the same
+    /// numbers from then on as the swarm's own.
+    ///
+    /// Together with `into_state` at the same iteration, passing it to `from_state` replays the
+    /// rest of a run exactly, as long as the settings are restored too and the particles draw
+    /// no randomness of their own. The snapshot lives in memory, even with the `serde` feature:
+    /// the generators of `rand` 0.4 implement no `Serialize` and do not expose their internal
+    /// state. A copy of `ThreadRng` shares the generator of the thread rather than its state, so
+    /// use a seedable generator for replay.
+    pub fn rng_state(&self) -> R {
+        self.rng.clone()
+    }
+}
+
+impl<P> PSO<ProblemCandidate<P>, ThreadRng>
+    where P: Problem + Send + Sync + 'static
+{
+    /// Creates a swarm optimizing `problem`, with the initial particles at solutions drawn from
+    /// `generator`, which also serves as the sampler of re-initialized particles.
+    ///
+    /// See the `problem` module for an example.
+    pub fn new_with_problem<F>(particles_num: usize,
+                               problem: P,
+                               mut generator: F,
+                               inetia: f64,
+                               c_local: f64,
+                               c_global: f64)
+                               -> Self
+        where F: FnMut() -> P::Solution + Send + 'static
+    {
+        let problem = Arc::new(problem);
+        let sampler = move || ProblemCandidate::new(generator(), problem.clone());
+        Self::new_with_sampler(particles_num, sampler, inetia, c_local, c_global)
+    }
+}
+
+impl<T, R> PSO<T, R>
+    where T: Particle + Clone,
+          R: Rng
+{
+    /// Creates a swarm like `new`, but drawing the update coefficients' randomness from `rng`.
+    ///
+    /// # Panics
+    /// Panics if `particles_num` is 0.
+    pub fn new_with_rng(particles_num: usize,
+                        inetia: f64,
+                        c_local: f64,
+                        c_global: f64,
+                        rng: R)
+                        -> Self
+        where T: RandomCandidate
+    {
+        let particles = ::build_population(particles_num, |_| T::new_random());
+
+        Self::with_particles(particles,
+                             ::Sampler::Random(T::new_random),
+                             inetia,
+                             c_local,
+                             c_global,
+                             rng)
+    }
+
+    /// Resumes an optimizer from a state previously extracted with `into_state`.
+    ///
+    /// The random number generator is not part of the state and has to be supplied again. Nor
+    /// are the settings holding closures or functions of a capability trait, which are left at
+    /// their defaults and have to be set again:
+    ///
+    /// - the sampler, which becomes `RandomCandidate::new_random` unless resuming with
+    ///   `from_state_with_sampler`,
+    /// - the comparator, the inertia schedule and APSO,
+    /// - the eval cache, parallel evaluation and resampling,
+    /// - the improvement epsilon and fitness sharing,
+    /// - the search bounds, the velocity limit, reinitialization and the minimum step,
+    /// - local search, elitist learning and adaptive mutation,
+    /// - Lévy flights and bare bones.
+    ///
+    /// The archive and the recorded histories start empty, and the stale counters at 0.
+    pub fn from_state(state: PsoState<T>, rng: R) -> Self
+        where T: RandomCandidate
+    {
+        Self::resume(state, ::Sampler::Random(T::new_random), rng)
+    }
+
+    /// Resumes an optimizer from `state` like `from_state`, but with `sampler` as the sampler,
+    /// as `new_with_sampler` does, so `T` need not implement `RandomCandidate`.
+    pub fn from_state_with_sampler<F>(state: PsoState<T>, sampler: F, rng: R) -> Self
+        where F: FnMut() -> T + Send + 'static
+    {
+        Self::resume(state, ::Sampler::Closure(Box::new(sampler)), rng)
+    }
+
+    fn resume(state: PsoState<T>, sampler: ::Sampler<T>, rng: R) -> Self {
+        let stale = vec![0; state.particles.len()];
+        let mut pso = Self {
+            particles: state.particles,
+            evals: state.evals,
+            inetia: state.inertia,
+            c_local: state.c_local,
+            c_global: state.c_global,
+            archive: Archive::new(),
+            best: state.best,
+            injected_best: false,
+            topology: state.topology,
+            topology_graph: state.topology_graph,
+            mode: state.mode,
+            bare_bones: None,
+            consistent_draws: state.consistent_draws,
+            neighborhood_best: Vec::new(),
+            levy: None,
+            teleport: state.teleport,
+            improvement: None,
+            sharing: None,
+            sampler,
+            bounds: None,
+            boundary: state.boundary,
+            reinitialization: None,
+            vel_limit: None,
+            min_step: None,
+            unification: state.unification,
+            c_centroid: state.c_centroid,
+            decay_iters: state.decay_iters,
+            inertia_fn: None,
+            apso: None,
+            comparator: None,
+            eval_cache: None,
+            cache_hits: 0,
+            parallel_eval: None,
+            parallel_best: None,
+            parallel_threshold: state.parallel_threshold,
+            resampling: None,
+            time_varying: state.time_varying,
+            repair: state.repair,
+            validate: state.validate,
+            elitism: state.elitism,
+            #[cfg(feature = "log")]
+            log_eval: None,
+            local_search: None,
+            elitist_learning: None,
+            adaptive_target: None,
+            mutation_step: None,
+            record_history: false,
+            history: Vec::new(),
+            record_best_positions: false,
+            best_position_history: Vec::new(),
+            stagnation: state.stagnation,
+            improved: vec![false; stale.len()],
+            frozen: state.frozen,
+            stale,
+            prev_best_seen: state.best_seen.clone(),
+            best_trace: vec![state.best_seen.clone()],
+            initial_best_eval: state.initial_best_eval,
+            best_seen: state.best_seen,
+            eval_count: state.eval_count,
+            iteration: state.iteration,
+            rng,
+        };
+        pso.recalc_neighborhood_best();
+        pso.archive_best();
+        pso
+    }
+
+    /// Consumes the optimizer and returns its state, dropping the random number generator.
+    pub fn into_state(self) -> PsoState<T> {
+        PsoState {
+            particles: self.particles,
+            evals: self.evals,
+            inertia: self.inetia,
+            c_local: self.c_local,
+            c_global: self.c_global,
+            best: self.best,
+            topology: self.topology,
+            topology_graph: self.topology_graph,
+            mode: self.mode,
+            boundary: self.boundary,
+            teleport: self.teleport,
+            unification: self.unification,
+            c_centroid: self.c_centroid,
+            decay_iters: self.decay_iters,
+            consistent_draws: self.consistent_draws,
+            time_varying: self.time_varying,
+            repair: self.repair,
+            validate: self.validate,
+            elitism: self.elitism,
+            frozen: self.frozen,
+            parallel_threshold: self.parallel_threshold,
+            stagnation: self.stagnation,
+            best_seen: self.best_seen,
+            initial_best_eval: self.initial_best_eval,
+            eval_count: self.eval_count,
+            iteration: self.iteration,
         }
+    }
 
-        let best = Self::calc_best(&particles);
+    fn with_particles(particles: Vec<T>,
+                      sampler: ::Sampler<T>,
+                      inetia: f64,
+                      c_local: f64,
+                      c_global: f64,
+                      rng: R)
+                      -> Self {
+        assert!(!particles.is_empty(), "a swarm needs at least one particle");
+        let evals = T::eval_batch(&particles);
+        let mut best = Self::calc_best(&particles, &evals, &None);
+        // Warm-started particles may carry personal bests beyond their current positions.
+        for p in &particles {
+            let (pos, e) = p.best();
+            if is_better(&None, &e, &best.1) {
+                let mut pbest = p.clone();
+                *pbest.pos_mut() = pos;
+                best = (pbest, e);
+            }
+        }
+        let particles_num = particles.len();
 
-        Self {
+        let mut pso = Self {
             particles,
+            eval_count: evals.len(),
+            evals,
             inetia,
             c_local,
             c_global,
+            best_seen: best.1.clone(),
+            prev_best_seen: best.1.clone(),
+            best_trace: vec![best.1.clone()],
+            initial_best_eval: best.1.clone(),
+            archive: Archive::new(),
             best,
-        }
+            injected_best: false,
+            topology: Topology::Global,
+            topology_graph: Vec::new(),
+            mode: Mode::Standard,
+            bare_bones: None,
+            consistent_draws: false,
+            neighborhood_best: Vec::new(),
+            levy: None,
+            teleport: None,
+            improvement: None,
+            sharing: None,
+            sampler,
+            bounds: None,
+            boundary: BoundaryBehavior::Clamp,
+            reinitialization: None,
+            vel_limit: None,
+            min_step: None,
+            unification: None,
+            c_centroid: 0.0,
+            decay_iters: None,
+            inertia_fn: None,
+            apso: None,
+            comparator: None,
+            eval_cache: None,
+            cache_hits: 0,
+            parallel_eval: None,
+            parallel_best: None,
+            parallel_threshold: DEFAULT_PARALLEL_THRESHOLD,
+            resampling: None,
+            time_varying: false,
+            repair: false,
+            validate: false,
+            elitism: false,
+            #[cfg(feature = "log")]
+            log_eval: None,
+            local_search: None,
+            elitist_learning: None,
+            adaptive_target: None,
+            mutation_step: None,
+            record_history: false,
+            history: Vec::new(),
+            record_best_positions: false,
+            best_position_history: Vec::new(),
+            stagnation: 0,
+            stale: vec![0; particles_num],
+            improved: vec![false; particles_num],
+            frozen: vec![false; particles_num],
+            iteration: 0,
+            rng,
+        };
+        pso.archive_best();
+        pso
+    }
+
+    fn calc_best(particles: &[T],
+                 evals: &[T::Eval],
+                 comparator: &Option<Comparator<T::Eval>>)
+                 -> (T, T::Eval) {
+        let best = match *comparator {
+            None => Self::best_index_by(evals, |a, b| a > b),
+            Some(ref comparator) => {
+                Self::best_index_by(evals, |a, b| comparator(a, b) == cmp::Ordering::Greater)
+            }
+        };
+        (particles[best].clone(), evals[best].clone())
     }
 
-    fn calc_best(particles: &[T]) -> (T, T::Eval) {
-        let best = particles.iter().max().unwrap();
-        (*best, best.eval())
+    /// Returns the index of the first best eval, with `better` saying whether an eval is better
+    /// than another.
+    fn best_index_by<F>(evals: &[T::Eval], better: F) -> usize
+        where F: Fn(&T::Eval, &T::Eval) -> bool
+    {
+        let mut best = 0;
+        for (i, e) in evals.iter().enumerate() {
+            if better(e, &evals[best]) {
+                best = i;
+            }
+        }
+        best
     }
 
+    /// Moves every particle and updates the personal, neighborhood and global bests.
+    ///
+    /// # Random number draws
+    /// The draws from the optimizer's random number generator are part of the interface, so
+    /// that seeded runs stay comparable across versions. Each draw of a coefficient is one call
+    /// of `Rng::next_u64`.
+    ///
+    /// * Construction draws nothing from it, as the particles come from
+    ///   `RandomCandidate::new_random` or the sampler; seeded populations come from
+    ///   `new_stratified` instead.
+    /// * APSO, enabled with `set_apso`, adds 2 per update at its start, before everything
+    ///   below: the step of the cognitive coefficient, then that of the social one.
+    /// * `Mode::Standard` draws exactly 2 per particle and update, the cognitive coefficient and
+    ///   then the social one, in particle order.
+    /// * `Mode::CognitiveOnly` and `Mode::SocialOnly` draw 1 per particle, or 2 like
+    ///   `Mode::Standard` with `set_consistent_rng_draws` enabled.
+    /// * A centroid coefficient set with `set_c_centroid` adds 1 per particle to the modes
+    ///   above, drawn after the social one.
+    /// * `Mode::Attraction` draws nothing.
+    /// * Bare bones, enabled with `set_bare_bones`, draws whatever `Gaussian::gaussian_sample`
+    ///   draws instead of any of the modes above.
+    /// * A minimum step adds whatever `RandomVelocity::random_vel` draws for each particle whose
+    ///   move falls short of it, in particle order, before everything below.
+    /// * Reinitialization, enabled with `set_reinitialization`, adds whatever
+    ///   `RegionSampling::random_in` draws for each particle whose move leaves the bounds, right
+    ///   after that particle's minimum step, before everything below.
+    /// * Lévy flights add 1 per particle, drawn before the coefficients, plus whatever
+    ///   `Levy::levy_step` draws for the particles taking a flight.
+    /// * Teleporting adds 1 per update, drawn after the evaluation, before the local search.
+    /// * Frozen particles draw nothing for their moves, velocities and Lévy flights.
+    /// * Local search adds whatever `RandomVelocity::random_vel` draws, once per step, after all
+    ///   of the above.
+    /// * Elitist learning adds 1 per update for the dimension, then a standard normal sample,
+    ///   after the local search.
+    ///
+    /// Apart from teleporting, the local search and elitist learning, which come after the
+    /// evaluation, all draws happen before `Particle::eval_batch` evaluates the moved swarm.
+    /// Evaluating it in parallel, as with `eval_parallel`, therefore leaves seeded runs
+    /// unchanged.
+    ///
+    /// # Logging
+    /// With the `log` feature, each update logs its iteration, evaluation count and stagnation
+    /// count at the trace level, and the best eval too once `set_log_best_eval` is enabled.
+    /// Restarts, resets and the stops of `run_until` and `run_until_converged` are logged at the
+    /// debug level.
     pub fn update(&mut self) {
-        for p in &mut self.particles {
-            let new_pos = p.pos() + p.vel();
-            *p.pos_mut() = new_pos;
+        self.move_particles();
+
+        let evals = if self.time_varying {
+            let iteration = self.iteration + 1;
+            for p in &mut self.particles {
+                // The personal best was evaluated at an earlier iteration.
+                let mut probe = p.clone();
+                *probe.pos_mut() = p.best().0;
+                p.best_mut().1 = probe.eval_at(iteration);
+            }
+            self.eval_count += self.particles.len();
+
+            let evals: Vec<_> = self.particles.iter().map(|p| p.eval_at(iteration)).collect();
+            self.eval_count += evals.len();
+            evals
+        } else if let Some((samples, ref resample)) = self.resampling {
+            self.eval_count += samples * self.particles.len();
+            self.particles.iter().map(resample).collect()
+        } else if let Some(ref mut cached) = self.eval_cache {
+            let (evals, misses) = cached(&self.particles);
+            self.cache_hits += evals.len() - misses;
+            self.eval_count += misses;
+            evals
+        } else {
+            let evals = match self.parallel_eval {
+                Some(ref eval) if self.particles.len() > self.parallel_threshold => {
+                    eval(&self.particles)
+                }
+                _ => T::eval_batch(&self.particles),
+            };
+            self.eval_count += evals.len();
+            evals
+        };
+
+        self.apply_evals(evals);
+    }
+
+    /// Updates the swarm like `update`, but evaluates with `Candidate::try_eval` and returns the
+    /// first evaluation error.
+    ///
+    /// On error, the particles, their neighborhood bests and the coefficients reset by an
+    /// inertia schedule or APSO are restored to their state before the call. The move has still
+    /// drawn from the random number generator, and called the inertia schedule, so these have
+    /// advanced, and the evaluation count includes the evaluations made before the error.
+    /// `try_eval` does not take the iteration, so the objective is evaluated as static even with
+    /// `set_time_varying` enabled.
+    pub fn try_update(&mut self) -> Result<(), Box<dyn error::Error>> {
+        let particles = self.particles.clone();
+        let stale = self.stale.clone();
+        let neighborhood_best = self.neighborhood_best.clone();
+        let coefficients = (self.inetia, self.c_local, self.c_global);
+        self.move_particles();
+
+        let mut evals = Vec::with_capacity(self.particles.len());
+        for p in &self.particles {
+            match p.try_eval() {
+                Ok(e) => evals.push(e),
+                Err(err) => {
+                    self.eval_count += evals.len() + 1;
+                    self.particles = particles;
+                    self.stale = stale;
+                    self.neighborhood_best = neighborhood_best;
+                    (self.inetia, self.c_local, self.c_global) = coefficients;
+                    return Err(err);
+                }
+            }
+        }
+        self.eval_count += evals.len();
+
+        self.apply_evals(evals);
+        Ok(())
+    }
+
+    /// Moves the particles and updates their velocities, the first half of an update.
+    fn move_particles(&mut self) {
+        if let Some(mut inertia_fn) = self.inertia_fn.take() {
+            self.inetia = inertia_fn(&RunContext { pso: self });
+            self.inertia_fn = Some(inertia_fn);
+        }
+        if let Some(distance) = self.apso {
+            self.adapt_apso(distance);
+        }
+
+        let previous: Option<Vec<T::Pos>> = if self.validate {
+            Some(self.particles.iter().map(|p| p.pos()).collect())
+        } else {
+            None
+        };
+
+        let mut reinitialized = false;
+        for i in 0..self.particles.len() {
+            if self.frozen[i] {
+                continue;
+            }
+            let new_pos = match (self.bare_bones, self.mode) {
+                (Some(gaussian_sample), _) => {
+                    let local = self.particles[i].best().0;
+                    let social = self.social_attractor(i);
+                    let std = local.clone() - social.clone();
+                    gaussian_sample(&((local + social) * 0.5), &std, &mut self.rng)
+                }
+                (None, Mode::Standard) |
+                (None, Mode::CognitiveOnly) |
+                (None, Mode::SocialOnly) => self.particles[i].pos() + self.particles[i].vel(),
+                (None, Mode::Attraction { rate }) => {
+                    let pos = self.particles[i].pos();
+                    pos.clone() + (self.best.0.pos() - pos) * rate
+                }
+            };
+            let new_pos = match self.min_step {
+                Some(MinStep { length, distance, random_vel }) => {
+                    self.enforce_min_step(i, new_pos, length, distance, random_vel)
+                }
+                None => new_pos,
+            };
+            let stray = match self.reinitialization {
+                Some(Reinitialization { distance, random_in }) => {
+                    Some(random_in).filter(|_| self.out_of_bounds(&new_pos, distance))
+                }
+                None => None,
+            };
+            if let Some(random_in) = stray {
+                let bounds = self.bounds.as_ref().unwrap();
+                self.particles[i] = random_in(&bounds.lower, &bounds.upper, &mut self.rng);
+                self.stale[i] = 0;
+                reinitialized = true;
+                continue;
+            }
+            let new_pos = self.confine(new_pos);
+            *self.particles[i].pos_mut() = new_pos;
+        }
+        if reinitialized {
+            // The replaced personal bests may have led their neighborhoods.
+            self.recalc_neighborhood_best();
         }
 
-        for mut p in &mut self.particles {
-            let new_vel = p.vel() * self.inetia +
-                          (p.best().0 - p.pos()) * self.c_local * Self::rand_01() +
-                          (self.best.0.pos() - p.pos()) * self.c_global * Self::rand_01();
-            *p.vel_mut() = new_vel;
+        if let Some(ref levy) = self.levy {
+            for (p, &frozen) in self.particles.iter_mut().zip(&self.frozen) {
+                if !frozen && rand_01(&mut self.rng) < levy.prob {
+                    (levy.step)(p, levy.exponent, &mut self.rng);
+                }
+            }
         }
 
-        for mut p in &mut self.particles {
-            let e = p.eval();
-            if e > p.best().1 {
-                *p.best_mut() = (p.pos(), e);
+        if self.repair {
+            for (p, &frozen) in self.particles.iter_mut().zip(&self.frozen) {
+                if !frozen {
+                    p.repair();
+                }
             }
         }
 
-        self.best = Self::calc_best(&self.particles);
-    }
+        if let Some(previous) = previous {
+            for ((p, &frozen), pos) in self.particles.iter_mut().zip(&self.frozen).zip(previous) {
+                if !frozen && !p.is_valid() {
+                    *p.pos_mut() = pos;
+                }
+            }
+        }
 
-    pub fn best(&self) -> (T, T::Eval) {
-        self.best
-    }
+        if self.moves_by_velocity() {
+            let cognitive = self.mode != Mode::SocialOnly;
+            let social = self.mode != Mode::CognitiveOnly;
+            let centroid = if self.c_centroid > 0.0 {
+                Some(self.centroid_position())
+            } else {
+                None
+            };
 
-    fn rand_01() -> f64 {
-        use rand::{random, Closed01};
+            for i in 0..self.particles.len() {
+                if self.frozen[i] {
+                    continue;
+                }
+                let attractor = self.social_attractor(i);
 
-        let Closed01(val) = random::<Closed01<_>>();
-        val
+                let p = &mut self.particles[i];
+                let mut new_vel = p.vel() * self.inetia;
+                if cognitive {
+                    new_vel = new_vel +
+                              (p.best().0 - p.pos()) * self.c_local * rand_01(&mut self.rng);
+                } else if self.consistent_draws {
+                    rand_01(&mut self.rng);
+                }
+                if social {
+                    new_vel = new_vel +
+                              (attractor - p.pos()) * self.c_global * rand_01(&mut self.rng);
+                } else if self.consistent_draws {
+                    rand_01(&mut self.rng);
+                }
+                if let Some(ref centroid) = centroid {
+                    new_vel = new_vel +
+                              (centroid.clone() - p.pos()) * self.c_centroid *
+                              rand_01(&mut self.rng);
+                }
+                if let Some(ref limit) = self.vel_limit {
+                    new_vel = limit.clamp(&new_vel);
+                }
+                *p.vel_mut() = new_vel;
+            }
+        }
+    }
+
+    /// Records the evals of the moved particles, updating the personal, neighborhood and global
+    /// bests. The second half of an update.
+    fn apply_evals(&mut self, evals: Vec<T::Eval>) {
+        // The comparator is matched once per update rather than per comparison, so that the
+        // default order compares with `PartialOrd` directly.
+        let improved: Vec<usize> = match self.sharing {
+            None => {
+                match self.comparator {
+                    None => self.improved_by(&evals, |a, b| a > b),
+                    Some(ref comparator) => {
+                        self.improved_by(&evals, |a, b| comparator(a, b) == cmp::Ordering::Greater)
+                    }
+                }
+            }
+            Some(ref sharing) => {
+                assert!(evals.iter().all(|e| (sharing.to_f64)(e) > 0.0),
+                        "fitness sharing needs positive evals");
+                let particles = &self.particles;
+                (0..evals.len())
+                    .filter(|&i| {
+                        let (best_pos, best_eval) = particles[i].best();
+                        Self::shared_fitness(sharing, particles, &particles[i].pos(), &evals[i]) >
+                        Self::shared_fitness(sharing, particles, &best_pos, &best_eval)
+                    })
+                    .collect()
+            }
+        };
+        for s in &mut self.stale {
+            *s += 1;
+        }
+        for b in &mut self.improved {
+            *b = false;
+        }
+        for &i in &improved {
+            let p = &mut self.particles[i];
+            *p.best_mut() = (p.pos(), evals[i].clone());
+            self.stale[i] = 0;
+            self.improved[i] = true;
+        }
+
+        if self.sharing.is_some() || self.time_varying || self.topology == Topology::Graph {
+            // Shared or re-evaluated personal bests can lose raw eval, so the incremental update
+            // below is unsound. A graph has no inverse lists to find the neighborhoods of the
+            // improved particles, and recalculating costs no more than scanning the lists.
+            self.recalc_neighborhood_best();
+        } else if let Topology::Ring(k) = self.topology {
+            // Personal bests never degrade, so a neighborhood best can only be displaced by one
+            // of the particles that just improved.
+            let particles = &self.particles;
+            let neighborhood_best = &mut self.neighborhood_best;
+            match self.comparator {
+                None => {
+                    Self::offer_ring_bests(particles, neighborhood_best, &improved, k, |a, b| a > b)
+                }
+                Some(ref comparator) => {
+                    Self::offer_ring_bests(particles, neighborhood_best, &improved, k, |a, b| {
+                        comparator(a, b) == cmp::Ordering::Greater
+                    })
+                }
+            }
+        }
+
+        self.evals = evals;
+        if let Some(prob) = self.teleport {
+            if rand_01(&mut self.rng) < prob {
+                self.teleport_worst();
+            }
+        }
+        if let Some(LocalSearch { steps, step_size, random_vel }) = self.local_search {
+            self.refine_best(steps, step_size, random_vel);
+        }
+        let (p, e) = self.select_best();
+        // An injected best is kept until beaten, and any best until beaten by the improvement
+        // epsilon.
+        let replace = if is_better(&self.comparator, &e, &self.best.1) {
+            self.improves(&e, &self.best.1)
+        } else {
+            !self.injected_best
+        };
+        if replace {
+            self.best = (p, e);
+            self.injected_best = false;
+            self.archive_best();
+        }
+        if let Some(ElitistLearning { sigma_start, sigma_end, axis }) = self.elitist_learning {
+            self.learn_elite(sigma_start, sigma_end, axis);
+        }
+        self.iteration += 1;
+        if self.record_history {
+            self.history.push(self.best.1.clone());
+        }
+        if self.record_best_positions {
+            self.best_position_history.push(self.best.0.pos());
+        }
+
+        let best_so_far = match self.best_trace.last() {
+            Some(last) if !is_better(&self.comparator, &self.best.1, last) => last.clone(),
+            _ => self.best.1.clone(),
+        };
+        self.best_trace.push(best_so_far);
+
+        self.prev_best_seen = self.best_seen.clone();
+        if self.improves(&self.best.1, &self.best_seen) {
+            self.best_seen = self.best.1.clone();
+            self.stagnation = 0;
+        } else {
+            self.stagnation += 1;
+        }
+        #[cfg(feature = "log")]
+        match self.log_eval {
+            Some(format) => {
+                trace!("iteration {}: best eval {}, {} evaluations, stagnation {}",
+                       self.iteration,
+                       format(&self.best.1),
+                       self.eval_count,
+                       self.stagnation)
+            }
+            None => {
+                trace!("iteration {}: {} evaluations, stagnation {}",
+                       self.iteration,
+                       self.eval_count,
+                       self.stagnation)
+            }
+        }
+    }
+
+    /// Replaces the move of particle `i` to `new_pos` by a random step of length `min_step` if
+    /// it is shorter, as set with `set_min_step`. In the modes moving by velocity, the step also
+    /// becomes the velocity.
+    fn enforce_min_step(&mut self,
+                        i: usize,
+                        new_pos: T::Pos,
+                        min_step: f64,
+                        distance: Distance<T::Pos>,
+                        random_vel: RandomVel<T::Pos, R>)
+                        -> T::Pos {
+        let pos = self.particles[i].pos();
+        if distance(&new_pos, &pos) >= min_step {
+            return new_pos;
+        }
+
+        let direction = random_vel(1.0, &mut self.rng);
+        let norm = distance(&direction, &(direction.clone() * 0.0));
+        if norm == 0.0 {
+            return new_pos;
+        }
+        let step = direction * (min_step / norm);
+        if self.moves_by_velocity() {
+            *self.particles[i].vel_mut() = step.clone();
+        }
+        pos + step
+    }
+
+    /// Returns whether the particles move by their velocities, which neither bare bones nor
+    /// `Mode::Attraction` do.
+    fn moves_by_velocity(&self) -> bool {
+        match self.mode {
+            Mode::Standard | Mode::CognitiveOnly | Mode::SocialOnly => self.bare_bones.is_none(),
+            Mode::Attraction { .. } => false,
+        }
+    }
+
+    /// Brings `pos` back into the search bounds, if set, as the boundary behavior says.
+    fn confine(&self, mut pos: T::Pos) -> T::Pos {
+        if let Some(ref bounds) = self.bounds {
+            if self.boundary == BoundaryBehavior::Reflect {
+                let zero = pos.clone() * 0.0;
+                let over = (bounds.max)(&(pos.clone() - bounds.upper.clone()), &zero);
+                let under = (bounds.min)(&(pos.clone() - bounds.lower.clone()), &zero);
+                pos = pos - (over + under) * 2.0;
+            }
+            pos = bounds.clamp(&pos);
+        }
+        pos
+    }
+
+    /// Returns whether `pos` lies outside the search bounds, if set, by its `distance` to its
+    /// clamped image.
+    fn out_of_bounds(&self, pos: &T::Pos, distance: Distance<T::Pos>) -> bool {
+        match self.bounds {
+            Some(ref bounds) => distance(pos, &bounds.clamp(pos)) > 0.0,
+            None => false,
+        }
+    }
+
+    /// Sets the inertia from the evolutionary factor and steps the coefficients by the
+    /// evolutionary state, as documented on `set_apso`.
+    fn adapt_apso(&mut self, distance: Distance<T::Pos>) {
+        let f = self.evolutionary_factor_by(distance);
+        self.inetia = 1.0 / (1.0 + 1.5 * (-2.6 * f).exp());
+
+        let local_step = 0.05 + 0.05 * rand_01(&mut self.rng);
+        let global_step = 0.05 + 0.05 * rand_01(&mut self.rng);
+        let (local_step, global_step) = match EvolutionaryState::from_factor(f) {
+            EvolutionaryState::Exploration => (local_step, -global_step),
+            EvolutionaryState::Exploitation => (0.5 * local_step, -0.5 * global_step),
+            EvolutionaryState::Convergence => (0.5 * local_step, 0.5 * global_step),
+            EvolutionaryState::JumpingOut => (-local_step, global_step),
+        };
+        let c_local = (self.c_local + local_step).clamp(1.5, 2.5);
+        let c_global = (self.c_global + global_step).clamp(1.5, 2.5);
+        let scale = (4.0 / (c_local + c_global)).min(1.0);
+        self.c_local = c_local * scale;
+        self.c_global = c_global * scale;
+    }
+
+    /// Returns `evolutionary_factor`, measuring the distances with `distance`.
+    fn evolutionary_factor_by(&self, distance: Distance<T::Pos>) -> f64 {
+        let n = self.particles.len();
+        if n < 2 {
+            return 0.0;
+        }
+        let mean_distance = |pos: &T::Pos| {
+            let sum: f64 = self.particles
+                .iter()
+                .map(|p| distance(&p.pos(), pos))
+                .sum();
+            sum / (n - 1) as f64
+        };
+
+        let distances: Vec<f64> = self.particles.iter().map(|p| mean_distance(&p.pos())).collect();
+        let d_min = distances.iter().cloned().fold(f64::INFINITY, f64::min);
+        let d_max = distances.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        if d_max <= d_min {
+            return 0.0;
+        }
+        let d_g = mean_distance(&self.best.0.pos());
+        ((d_g - d_min) / (d_max - d_min)).clamp(0.0, 1.0)
+    }
+
+    /// Perturbs a copy of the swarm's best along one random dimension, moving the worst
+    /// particle there if it evaluates better.
+    fn learn_elite(&mut self, sigma_start: f64, sigma_end: f64, axis: fn(&T, usize) -> T::Pos) {
+        let sigma = sigma_start + (sigma_end - sigma_start) * self.progress().unwrap_or(0.0);
+        let mut candidate = self.best.0.clone();
+        let dim = self.rng.gen_range(0, candidate.dim());
+        let StandardNormal(z) = self.rng.gen::<StandardNormal>();
+        let pos = candidate.pos() + axis(&candidate, dim) * (sigma * z);
+        *candidate.pos_mut() = self.confine(pos);
+        if self.repair {
+            candidate.repair();
+        }
+        if self.validate && !candidate.is_valid() {
+            return;
+        }
+
+        if self.set_global_best(candidate, false) {
+            let worst = self.worst_index();
+            if self.frozen[worst] {
+                return;
+            }
+            let (mut elite, e) = self.best.clone();
+            *elite.best_mut() = (elite.pos(), e.clone());
+            self.particles[worst] = elite;
+            self.evals[worst] = e;
+            self.stale[worst] = 0;
+            self.recalc_neighborhood_best();
+        }
+    }
+
+    /// Hill-climbs from the particle with the best current eval for `steps` perturbations of up
+    /// to `step_size` per component, moving it to each one that evaluates better.
+    fn refine_best(&mut self, steps: usize, step_size: f64, random_vel: RandomVel<T::Pos, R>) {
+        let best = self.best_index();
+        if self.frozen[best] {
+            return;
+        }
+
+        if let (None, Some(target)) = (self.mutation_step, self.adaptive_target) {
+            self.mutation_step = Some(AdaptiveStep::new(step_size, target, MUTATION_WINDOW));
+        }
+
+        let mut improved = false;
+        for _ in 0..steps {
+            let sigma = self.mutation_step.as_ref().map_or(step_size, AdaptiveStep::sigma) *
+                        self.decay_scale();
+            let mut candidate = self.particles[best].clone();
+            let pos = candidate.pos() + random_vel(sigma, &mut self.rng);
+            *candidate.pos_mut() = self.confine(pos);
+            if self.repair {
+                candidate.repair();
+            }
+            if self.validate && !candidate.is_valid() {
+                if let Some(ref mut step) = self.mutation_step {
+                    step.record(false);
+                }
+                continue;
+            }
+
+            let e = if self.time_varying {
+                self.eval_count += 1;
+                candidate.eval_at(self.iteration + 1)
+            } else if let Some((samples, ref resample)) = self.resampling {
+                self.eval_count += samples;
+                resample(&candidate)
+            } else {
+                self.eval_count += 1;
+                candidate.eval()
+            };
+
+            let accepted = is_better(&self.comparator, &e, &self.evals[best]);
+            if let Some(ref mut step) = self.mutation_step {
+                step.record(accepted);
+            }
+            if accepted {
+                if self.improves(&e, &candidate.best().1) {
+                    *candidate.best_mut() = (candidate.pos(), e.clone());
+                }
+                self.particles[best] = candidate;
+                self.evals[best] = e;
+                improved = true;
+            }
+        }
+
+        if improved {
+            self.stale[best] = 0;
+            self.improved[best] = true;
+            self.recalc_neighborhood_best();
+        }
+    }
+
+    fn sample(&mut self) -> T {
+        self.sampler.sample()
+    }
+
+    /// Selects the best particle by eval, or by shared fitness if fitness sharing is enabled.
+    fn select_best(&self) -> (T, T::Eval) {
+        match self.sharing {
+            None => {
+                match self.parallel_best {
+                    // Comparators need not be `Sync`, so they are always applied sequentially.
+                    Some(ref best_index) if self.comparator.is_none() &&
+                                            self.evals.len() > self.parallel_threshold => {
+                        let best = best_index(&self.evals);
+                        (self.particles[best].clone(), self.evals[best].clone())
+                    }
+                    _ => Self::calc_best(&self.particles, &self.evals, &self.comparator),
+                }
+            }
+            Some(ref sharing) => {
+                let mut best = 0;
+                let mut best_fitness = f64::NEG_INFINITY;
+                for (i, (p, e)) in self.particles.iter().zip(&self.evals).enumerate() {
+                    let fitness = Self::shared_fitness(sharing, &self.particles, &p.pos(), e);
+                    if fitness > best_fitness {
+                        best = i;
+                        best_fitness = fitness;
+                    }
+                }
+                (self.particles[best].clone(), self.evals[best].clone())
+            }
+        }
+    }
+
+    /// Reselects the swarm's best like `select_best`, offering it to the archive.
+    fn reselect_best(&mut self) {
+        self.best = self.select_best();
+        self.injected_best = false;
+        self.archive_best();
+    }
+
+    /// Offers the swarm's best to the archive, which compares the raw evals.
+    fn archive_best(&mut self) {
+        let comparator = &self.comparator;
+        self.archive.offer_by(&self.best.0, &self.best.1, |a, b| compare(comparator, a, b));
+    }
+
+    /// The raw fitness `eval` at `pos` divided by its niche count among `particles`, i.e. the sum
+    /// of `1 - d / sigma` over the particles within distance `d < sigma` of `pos`.
+    fn shared_fitness(sharing: &Sharing<T::Pos, T::Eval>,
+                      particles: &[T],
+                      pos: &T::Pos,
+                      eval: &T::Eval)
+                      -> f64 {
+        let niche_count: f64 = particles
+            .iter()
+            .map(|p| (sharing.distance)(pos, &p.pos()) / sharing.sigma)
+            .filter(|&d| d < 1.0)
+            .map(|d| 1.0 - d)
+            .sum();
+        (sharing.to_f64)(eval) / niche_count.max(1.0)
+    }
+
+    /// Returns the indices of the particles whose `evals` improve on their personal bests, like
+    /// `improves` with `better` in place of the comparator.
+    fn improved_by<F>(&self, evals: &[T::Eval], better: F) -> Vec<usize>
+        where F: Fn(&T::Eval, &T::Eval) -> bool
+    {
+        (0..evals.len())
+            .filter(|&i| {
+                let old = self.particles[i].best().1;
+                better(&evals[i], &old) &&
+                match self.improvement {
+                    None => true,
+                    Some(ref imp) => gain(&self.comparator, imp.to_f64, &evals[i], &old) >= imp.eps,
+                }
+            })
+            .collect()
+    }
+
+    /// Offers the personal bests of the `improved` particles to the neighborhood bests of a ring
+    /// with `k` neighbors on each side, with `better` in place of the comparator.
+    fn offer_ring_bests<F>(particles: &[T],
+                           neighborhood_best: &mut [usize],
+                           improved: &[usize],
+                           k: usize,
+                           better: F)
+        where F: Fn(&T::Eval, &T::Eval) -> bool
+    {
+        for &j in improved {
+            for i in ring_neighbors(j, k, particles.len()) {
+                if better(&particles[j].best().1, &particles[neighborhood_best[i]].best().1) {
+                    neighborhood_best[i] = j;
+                }
+            }
+        }
+    }
+
+    fn improves(&self, new: &T::Eval, old: &T::Eval) -> bool {
+        is_better(&self.comparator, new, old) &&
+        match self.improvement {
+            None => true,
+            Some(ref imp) => gain(&self.comparator, imp.to_f64, new, old) >= imp.eps,
+        }
+    }
+
+    /// Runs up to `iters` updates, calling `callback` with the optimizer after each one, and
+    /// returns the number of updates run.
+    ///
+    /// The callback may inspect the swarm and reconfigure it, e.g. `restart` once `stagnation`
+    /// crosses a threshold, and stops the run early by returning `ControlFlow::Break`.
+    pub fn run<F>(&mut self, iters: usize, mut callback: F) -> usize
+        where F: FnMut(&mut Self) -> ControlFlow<()>
+    {
+        for i in 0..iters {
+            self.update();
+            if let ControlFlow::Break(()) = callback(self) {
+                return i + 1;
+            }
+        }
+        iters
+    }
+
+    /// Runs `update` until `termination` says to stop, checking it before every update, and
+    /// returns the number of updates made. `Termination` has an example.
+    ///
+    /// The run only ends when the criterion does, so include `MaxIters` unless it is certain to
+    /// stop.
+    pub fn run_until<C>(&mut self, termination: &mut C) -> usize
+        where C: Termination<T, R>
+    {
+        let mut iters = 0;
+        while !termination.should_stop(&RunContext { pso: self }) {
+            self.update();
+            iters += 1;
+        }
+        #[cfg(feature = "log")]
+        debug!("iteration {}: stopped by the termination criterion", self.iteration);
+        iters
+    }
+
+    /// Runs `update` until `pred` holds for the swarm's best and its eval, checking it before
+    /// every update and after the last, for conditions on the solution rather than a target
+    /// eval. Returns the number of updates made, or `None` if `pred` still failed after
+    /// `max_iters`.
+    pub fn run_until_pred<F>(&mut self, max_iters: usize, pred: F) -> Option<usize>
+        where F: Fn(&T, &T::Eval) -> bool
+    {
+        for i in 0..max_iters + 1 {
+            if pred(&self.best.0, &self.best.1) {
+                return Some(i);
+            }
+            if i < max_iters {
+                self.update();
+            }
+        }
+        None
+    }
+
+    /// Returns which particles improved their personal best in the most recent `update`, one
+    /// flag per particle in the order of `particles`. All flags are `false` before the first
+    /// update and after `reset`, as are those of particles replaced or added since.
+    pub fn improved_mask(&self) -> &[bool] {
+        &self.improved
+    }
+
+    /// Returns the number of consecutive non-improving `update`s.
+    ///
+    /// An update is non-improving when its `best()` eval does not strictly exceed the highest
+    /// best eval seen since the counter was last reset, by at least the improvement epsilon if
+    /// one is set. The counter resets to zero on an improving update and on `restart`.
+    pub fn stagnation(&self) -> usize {
+        self.stagnation
+    }
+
+    /// Re-randomizes the swarm, except for the `elite` particles with the best current evals.
+    ///
+    /// The surviving elites keep their positions, velocities and personal bests; the others are
+    /// replaced by fresh particles from the sampler and start from fresh personal bests. `elite`
+    /// larger than the population keeps every particle.
+    pub fn restart(&mut self, elite: usize) {
+        let mut order: Vec<usize> = (0..self.particles.len()).collect();
+        {
+            let (evals, comparator) = (&self.evals, &self.comparator);
+            order.sort_by(|&a, &b| compare(comparator, &evals[b], &evals[a]));
+        }
+
+        for &i in order.iter().skip(elite) {
+            self.particles[i] = self.sample();
+            self.stale[i] = 0;
+            self.improved[i] = false;
+        }
+
+        let new_evals = {
+            let fresh: Vec<T> = order
+                .iter()
+                .skip(elite)
+                .map(|&i| self.particles[i].clone())
+                .collect();
+            T::eval_batch(&fresh)
+        };
+        self.eval_count += new_evals.len();
+        for (&i, e) in order.iter().skip(elite).zip(new_evals) {
+            self.evals[i] = e;
+        }
+
+        self.reselect_best();
+        self.recalc_neighborhood_best();
+        self.stagnation = 0;
+        self.best_seen = self.best.1.clone();
+        self.prev_best_seen = self.best.1.clone();
+        #[cfg(feature = "log")]
+        debug!("iteration {}: restarted keeping {} elites", self.iteration, elite);
+    }
+
+    /// Re-randomizes the whole swarm and clears its progress, for running independent trials
+    /// with one configured optimizer.
+    ///
+    /// Every particle is replaced by a fresh one from the sampler, and the iteration, evaluation
+    /// and stagnation counters restart from the new population. The coefficients and every other
+    /// setting, as well as the random number generator, are kept.
+    ///
+    /// So is the `archive`, which therefore accumulates the best solution ever found across all
+    /// trials; `reset_and_clear_archive` starts it over instead.
+    pub fn reset(&mut self) {
+        let particles = ::build_population(self.particles.len(), |_| self.sample());
+        self.reset_to(particles);
+    }
+
+    /// Resets the swarm like `reset`, spelling out that the `archive` is kept, for running many
+    /// trials from one optimizer while accumulating the best solution ever found across them.
+    /// With `set_elitism`, `best` keeps reporting that solution until a later trial beats it.
+    pub fn reset_and_keep_archive(&mut self) {
+        self.reset();
+    }
+
+    /// Resets the swarm like `reset`, but also clears the `archive`, so that the new trial
+    /// reports its own best even with `set_elitism`.
+    pub fn reset_and_clear_archive(&mut self) {
+        self.archive.clear();
+        self.reset();
+    }
+
+    /// Reseeds the random number generator with `seed`, then resets the swarm like `reset`, so
+    /// that each seed gives a reproducible, independent trial from the same optimizer. Without
+    /// reseeding, `reset` continues the generator's stream.
+    ///
+    /// The new particles are created by `SeededCandidate::new_random_seeded` with seeds drawn
+    /// from the reseeded generator, one per particle, rather than by the sampler.
+    pub fn reset_with_seed<S>(&mut self, seed: S)
+        where T: SeededCandidate,
+              R: SeedableRng<S>
+    {
+        self.rng.reseed(seed);
+        let particles = ::build_population(self.particles.len(), |_| {
+            T::new_random_seeded(self.rng.next_u64())
+        });
+        self.reset_to(particles);
+    }
+
+    /// Replaces the population with `particles`, as a freshly constructed swarm would start.
+    fn reset_to(&mut self, particles: Vec<T>) {
+        self.evals = T::eval_batch(&particles);
+        self.particles = particles;
+        self.eval_count = self.evals.len();
+        self.stale = vec![0; self.particles.len()];
+        self.improved = vec![false; self.particles.len()];
+        self.frozen = vec![false; self.particles.len()];
+
+        self.reselect_best();
+        self.recalc_neighborhood_best();
+        self.best_seen = self.best.1.clone();
+        self.prev_best_seen = self.best.1.clone();
+        self.stagnation = 0;
+        self.iteration = 0;
+        self.history.clear();
+        self.best_position_history.clear();
+        self.best_trace = vec![self.best.1.clone()];
+        self.initial_best_eval = self.best.1.clone();
+        #[cfg(feature = "log")]
+        debug!("reset the swarm of {} particles", self.particles.len());
+    }
+
+    /// Enables teleporting: at the end of each `update`, with probability `prob`, the particle
+    /// with the worst current eval is replaced by a fresh one from the sampler, whose personal best
+    /// starts at its new position. Unlike `restart`, this diversifies the swarm all the time rather
+    /// than only once it stagnates. `prob` must be in `[0, 1]`. Disabled by default.
+    ///
+    /// The best particle is never the worst one unless all evals tie, and the archive kept for
+    /// `set_elitism` holds a copy of the best, so a teleport never loses the archived best.
+    pub fn set_teleport(&mut self, prob: f64) -> Result<(), MetaHeuristicsError> {
+        if !(0.0..=1.0).contains(&prob) {
+            return Err(MetaHeuristicsError::InvalidParameter {
+                           name: "prob",
+                           value: prob,
+                       });
+        }
+
+        self.teleport = Some(prob);
+        Ok(())
+    }
+
+    pub fn unset_teleport(&mut self) {
+        self.teleport = None;
+    }
+
+    /// Replaces the particle with the worst current eval by a fresh one, as enabled by
+    /// `set_teleport`.
+    fn teleport_worst(&mut self) {
+        let worst = self.worst_index();
+        if self.frozen[worst] {
+            return;
+        }
+        let mut fresh = self.sample();
+        let e = fresh.eval();
+        self.eval_count += 1;
+        *fresh.best_mut() = (fresh.pos(), e.clone());
+
+        self.particles[worst] = fresh;
+        self.evals[worst] = e;
+        self.stale[worst] = 0;
+        self.improved[worst] = false;
+        self.recalc_neighborhood_best();
+    }
+
+    /// Replaces the particle at `index`, evaluating it and updating the swarm's best.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn set_particle(&mut self, index: usize, particle: T) {
+        let e = particle.eval();
+        self.eval_count += 1;
+        self.particles[index] = particle;
+        self.evals[index] = e;
+        self.stale[index] = 0;
+        self.improved[index] = false;
+
+        self.reselect_best();
+        self.recalc_neighborhood_best();
+    }
+
+    /// Holds the particle at `index` in place: `update` no longer moves it or changes its
+    /// velocity, and neither do Lévy flights, repair, teleporting and the local search. It is
+    /// still evaluated, and takes part in the selection of the bests and in attracting the
+    /// others, so it can pin a known reference solution.
+    ///
+    /// Other operations replacing particles, such as `restart` and `set_particle`, ignore the
+    /// mask, and `reset` clears it.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn freeze(&mut self, index: usize) {
+        self.frozen[index] = true;
+    }
+
+    /// Lets the particle at `index`, held in place by `freeze`, move again.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn unfreeze(&mut self, index: usize) {
+        self.frozen[index] = false;
+    }
+
+    /// Returns whether the particle at `index` is held in place by `freeze`.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn is_frozen(&self, index: usize) -> bool {
+        self.frozen[index]
+    }
+
+    /// Appends `n` particles created with the sampler, evaluating them and updating the swarm's
+    /// best.
+    pub fn add_particles(&mut self, n: usize) {
+        let fresh = ::build_population(n, |_| self.sample());
+        let evals = T::eval_batch(&fresh);
+        self.eval_count += evals.len();
+        self.particles.extend(fresh);
+        self.evals.extend(evals);
+        self.stale.resize(self.particles.len(), 0);
+        self.improved.resize(self.particles.len(), false);
+        self.frozen.resize(self.particles.len(), false);
+
+        self.reselect_best();
+        self.recalc_neighborhood_best();
+    }
+
+    /// Removes the `n` particles with the lowest current evals and updates the swarm's best.
+    ///
+    /// At least one particle is always kept, so `n` is clamped to one less than the population
+    /// size.
+    pub fn remove_worst(&mut self, n: usize) {
+        let n = cmp::min(n, self.particles.len().saturating_sub(1));
+
+        let mut order: Vec<usize> = (0..self.particles.len()).collect();
+        {
+            let (evals, comparator) = (&self.evals, &self.comparator);
+            order.sort_by(|&a, &b| compare(comparator, &evals[a], &evals[b]));
+        }
+        let mut removed = order[..n].to_vec();
+        removed.sort();
+
+        for &i in removed.iter().rev() {
+            self.particles.swap_remove(i);
+            self.evals.swap_remove(i);
+            self.stale.swap_remove(i);
+            self.improved.swap_remove(i);
+            self.frozen.swap_remove(i);
+        }
+
+        self.reselect_best();
+        self.recalc_neighborhood_best();
+    }
+
+    /// Re-initializes every particle whose personal best has not improved for `patience`
+    /// updates, and returns how many were replaced.
+    ///
+    /// Unlike `restart`, which acts on the whole swarm once it stagnates, this only perturbs the
+    /// individual particles that got stuck. The replacements come from the sampler and start
+    /// from fresh personal bests.
+    pub fn reinit_stale(&mut self, patience: usize) -> usize {
+        let stale: Vec<usize> = (0..self.particles.len())
+            .filter(|&i| self.stale[i] >= patience)
+            .collect();
+        if stale.is_empty() {
+            return 0;
+        }
+
+        for &i in &stale {
+            self.particles[i] = self.sample();
+            self.stale[i] = 0;
+            self.improved[i] = false;
+        }
+
+        let new_evals = {
+            let fresh: Vec<T> = stale.iter().map(|&i| self.particles[i].clone()).collect();
+            T::eval_batch(&fresh)
+        };
+        self.eval_count += new_evals.len();
+        for (&i, e) in stale.iter().zip(new_evals) {
+            self.evals[i] = e;
+        }
+
+        self.reselect_best();
+        self.recalc_neighborhood_best();
+        stale.len()
+    }
+
+    /// Sets the global best to `particle` if its eval improves on the current best's, as
+    /// `set_improvement_epsilon` defines, or unconditionally if `force` is set. Returns whether
+    /// the best was replaced.
+    ///
+    /// The rest of the population is left untouched, so this suits memetic schemes that refine
+    /// the best externally and write it back. The injected best attracts the swarm and is kept
+    /// through the following updates until a particle beats it, after which the best is
+    /// selected from the population as usual; use `set_particle` as well to keep the solution
+    /// in the swarm.
+    pub fn set_global_best(&mut self, particle: T, force: bool) -> bool {
+        let e = particle.eval();
+        self.eval_count += 1;
+
+        if force || self.improves(&e, &self.best.1) {
+            self.best = (particle, e);
+            self.injected_best = true;
+            self.archive_best();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Sets the personal best of the particle at `index` to `pos` with the eval `eval`, which is
+    /// trusted rather than recomputed, as when resuming from a checkpoint whose particles had
+    /// found better positions than their current ones. If it beats the swarm's best, it becomes
+    /// the swarm's best too, until the next `update` reselects it.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn set_personal_best(&mut self, index: usize, pos: T::Pos, eval: T::Eval) {
+        *self.particles[index].best_mut() = (pos.clone(), eval.clone());
+
+        if is_better(&self.comparator, &eval, &self.best.1) {
+            let mut best = self.particles[index].clone();
+            *best.pos_mut() = pos;
+            self.best = (best, eval);
+            self.archive_best();
+        }
+        self.recalc_neighborhood_best();
+    }
+
+    /// Returns the index of the particle with the best current eval.
+    fn best_index(&self) -> usize {
+        let mut best = 0;
+        for i in 1..self.evals.len() {
+            if is_better(&self.comparator, &self.evals[i], &self.evals[best]) {
+                best = i;
+            }
+        }
+        best
+    }
+
+    fn worst_index(&self) -> usize {
+        let mut worst = 0;
+        for (i, e) in self.evals.iter().enumerate() {
+            if is_better(&self.comparator, &self.evals[worst], e) {
+                worst = i;
+            }
+        }
+        worst
+    }
+
+    /// Computes the inertia of every `update` with `inertia_fn`, which is called at the start of
+    /// the update with a view of the swarm. This overrides the fixed inertia; `config` reports
+    /// the inertia used by the last update.
+    pub fn set_inertia_fn<F>(&mut self, inertia_fn: F)
+        where F: FnMut(&RunContext<T, R>) -> f64 + Send + 'static
+    {
+        self.inertia_fn = Some(Box::new(inertia_fn));
+    }
+
+    /// Removes the inertia schedule, keeping the inertia it last produced.
+    pub fn unset_inertia_fn(&mut self) {
+        self.inertia_fn = None;
+    }
+
+    /// Sets how positions leaving the search bounds are brought back. Defaults to
+    /// `BoundaryBehavior::Clamp`.
+    pub fn set_boundary_behavior(&mut self, behavior: BoundaryBehavior) {
+        self.boundary = behavior;
+    }
+
+    pub fn boundary_behavior(&self) -> BoundaryBehavior {
+        self.boundary
+    }
+
+    /// Returns whether particles leaving the search bounds are replaced, as set with
+    /// `set_reinitialization`.
+    pub fn reinitialization(&self) -> bool {
+        self.reinitialization.is_some()
+    }
+
+    /// Sets the number of particles up to which the swarm is evaluated sequentially even with
+    /// `enable_parallel_eval`; `update` spawns the evaluation threads only for larger swarms.
+    /// Defaults to `DEFAULT_PARALLEL_THRESHOLD`.
+    pub fn set_parallel_threshold(&mut self, threshold: usize) {
+        self.parallel_threshold = threshold;
+    }
+
+    /// Returns the number of particles up to which the swarm is evaluated sequentially, as set
+    /// with `set_parallel_threshold`.
+    pub fn parallel_threshold(&self) -> usize {
+        self.parallel_threshold
+    }
+
+    pub fn unset_resampling(&mut self) {
+        self.resampling = None;
+    }
+
+    /// Returns the number of evals averaged per evaluation, 1 unless set with
+    /// `set_resampling`.
+    pub fn resampling(&self) -> usize {
+        self.resampling.as_ref().map_or(1, |r| r.0)
+    }
+
+    /// Sets the neighborhood topology of the social term. Defaults to `Topology::Global`.
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+        self.recalc_neighborhood_best();
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Blends the global best into the social attractor of the `Ring` and `Graph` topologies,
+    /// as in Parsopoulos and Vrahatis's unified PSO: each particle is attracted to
+    /// `u * gbest + (1 - u) * lbest`, where gbest is the swarm's best and lbest the best of the
+    /// particle's neighborhood. `u` of 1 is pure gbest, and `u` of 0 pure lbest, as if this was
+    /// not set. The `Global` topology is unaffected, as its lbest is gbest.
+    ///
+    /// Returns an error, leaving the setting unchanged, unless `u` is within `[0, 1]`.
+    pub fn set_unification(&mut self, u: f64) -> Result<(), MetaHeuristicsError> {
+        if !(0.0..=1.0).contains(&u) {
+            return Err(MetaHeuristicsError::InvalidParameter {
+                           name: "unification",
+                           value: u,
+                       });
+        }
+        self.unification = Some(u);
+        Ok(())
+    }
+
+    pub fn unset_unification(&mut self) {
+        self.unification = None;
+    }
+
+    /// Adds a term attracting each particle to the centroid of the swarm to the velocity
+    /// update, `c_centroid * r * (centroid - pos)` with a fresh random `r` in `[0, 1]` per
+    /// particle, as in the PSO variants using the mean position. This smooths the convergence on
+    /// unimodal objectives. Defaults to 0, which disables the term.
+    ///
+    /// The term adds to the cognitive and social ones, also in `Mode::CognitiveOnly` and
+    /// `Mode::SocialOnly`, and the velocity limit applies to the sum. As with those, a large
+    /// total of the coefficients for the inertia makes the swarm oscillate or diverge, so lower
+    /// `c_local` and `c_global` when raising this. Bare bones and `Mode::Attraction` ignore
+    /// it. The centroid is that of the moved positions, as `centroid_position` returns
+    /// it after the update.
+    ///
+    /// Returns an error, leaving the coefficient unchanged, if `c_centroid` is negative or not
+    /// finite.
+    pub fn set_c_centroid(&mut self, c_centroid: f64) -> Result<(), MetaHeuristicsError> {
+        if !(c_centroid >= 0.0 && c_centroid.is_finite()) {
+            return Err(MetaHeuristicsError::InvalidParameter {
+                           name: "c_centroid",
+                           value: c_centroid,
+                       });
+        }
+        self.c_centroid = c_centroid;
+        Ok(())
+    }
+
+    /// Returns the position attracting particle `index` in the social term under the topology
+    /// and the unification of `set_unification`.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of range.
+    pub fn social_attractor(&self, index: usize) -> T::Pos {
+        match self.topology {
+            Topology::Global => self.best.0.pos(),
+            Topology::Ring(_) | Topology::Graph => {
+                let lbest = self.particles[self.neighborhood_best[index]].best().0;
+                match self.unification {
+                    Some(u) => self.best.0.pos() * u + lbest * (1.0 - u),
+                    None => lbest,
+                }
+            }
+        }
+    }
+
+    /// Sets an arbitrary static neighbor topology, where `adjacency[i]` lists the neighbors of
+    /// particle `i`. Each particle is then attracted to the best personal best among its listed
+    /// neighbors, which include the particle itself only if listed.
+    ///
+    /// The topology becomes `Topology::Graph`. Returns an error, leaving the topology unchanged,
+    /// unless `adjacency` has one non-empty list per particle with every index in range.
+    pub fn set_topology_graph(&mut self,
+                              adjacency: Vec<Vec<usize>>)
+                              -> Result<(), MetaHeuristicsError> {
+        let n = self.particles.len();
+        if adjacency.len() != n {
+            return Err(MetaHeuristicsError::TopologyMismatch {
+                           particles: n,
+                           lists: adjacency.len(),
+                       });
+        }
+        if let Some(particle) = adjacency
+               .iter()
+               .position(|neighbors| neighbors.is_empty() || neighbors.iter().any(|&j| j >= n)) {
+            return Err(MetaHeuristicsError::InvalidNeighbors { particle });
+        }
+
+        self.topology_graph = adjacency;
+        self.set_topology(Topology::Graph);
+        Ok(())
+    }
+
+    /// Sets how `update` moves the particles. Defaults to `Mode::Standard`.
+    pub fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Returns whether bare bones is enabled, as set with `set_bare_bones`.
+    pub fn bare_bones(&self) -> bool {
+        self.bare_bones.is_some()
+    }
+
+    /// Sets whether `Mode::CognitiveOnly` and `Mode::SocialOnly` still draw the random number of
+    /// the term they leave out, discarding it. Disabled by default.
+    ///
+    /// With this enabled, every velocity update mode consumes the random number stream alike, so
+    /// runs from the same seed stay comparable across modes, and differences in their results
+    /// come from the modes rather than from diverging streams.
+    pub fn set_consistent_rng_draws(&mut self, consistent: bool) {
+        self.consistent_draws = consistent;
+    }
+
+    /// Orders evals by `comparator` instead of `PartialOrd`, and reselects the current and
+    /// neighborhood bests under the new order.
+    ///
+    /// Selecting the best and worst particles, updating personal bests and ranking particles
+    /// all follow the comparator, which lets a composite eval express a preference such as a
+    /// lexicographic or weighted order without changing its `PartialOrd` impl. Fitness sharing
+    /// derates evals as numbers, which cannot follow the comparator's order, so the comparator
+    /// disables it.
+    pub fn set_comparator<F>(&mut self, comparator: F)
+        where F: Fn(&T::Eval, &T::Eval) -> cmp::Ordering + Send + 'static
+    {
+        self.comparator = Some(Box::new(comparator));
+        self.sharing = None;
+        self.reselect_best();
+        self.recalc_neighborhood_best();
+    }
+
+    /// Restores the `PartialOrd` order of evals.
+    pub fn unset_comparator(&mut self) {
+        self.comparator = None;
+        self.reselect_best();
+        self.recalc_neighborhood_best();
+    }
+
+    fn recalc_neighborhood_best(&mut self) {
+        self.neighborhood_best.clear();
+        match self.topology {
+            Topology::Global => {}
+            Topology::Ring(k) => {
+                let n = self.particles.len();
+                for i in 0..n {
+                    let best = self.best_neighbor(i, ring_neighbors(i, k, n));
+                    self.neighborhood_best.push(best);
+                }
+            }
+            Topology::Graph => {
+                assert_eq!(self.topology_graph.len(),
+                           self.particles.len(),
+                           "topology graph does not match the swarm");
+                for i in 0..self.particles.len() {
+                    let neighbors = &self.topology_graph[i];
+                    let best = self.best_neighbor(neighbors[0], neighbors[1..].iter().cloned());
+                    self.neighborhood_best.push(best);
+                }
+            }
+        }
+    }
+
+    /// Returns the neighbor with the best personal best among `first` and `rest`.
+    fn best_neighbor<I: Iterator<Item = usize>>(&self, first: usize, rest: I) -> usize {
+        let mut best = first;
+        for j in rest {
+            if is_better(&self.comparator,
+                         &self.particles[j].best().1,
+                         &self.particles[best].best().1) {
+                best = j;
+            }
+        }
+        best
+    }
+
+    /// Returns the swarm's current best and its eval, or the all-time best with
+    /// `set_elitism`.
+    pub fn best(&self) -> (T, T::Eval) {
+        let (best, e) = self.best_ref();
+        (best.clone(), e.clone())
+    }
+
+    /// Returns references to the best that `best` returns, without cloning them.
+    pub fn best_ref(&self) -> (&T, &T::Eval) {
+        if self.elitism {
+            if let Some(best) = self.archive.best() {
+                return best;
+            }
+        }
+        (&self.best.0, &self.best.1)
+    }
+
+    /// Returns the best eval of the initial population, before any `update`, as a baseline for
+    /// how much the optimizer improved over random sampling. `reset` replaces it with the best
+    /// eval of the new population.
+    pub fn initial_best(&self) -> T::Eval {
+        self.initial_best_eval.clone()
+    }
+
+    /// Returns the archive of the best particle the swarm has had since construction, kept
+    /// across `reset`, `restart` and any other change of the population. Only
+    /// `reset_and_clear_archive` and `grow_dimension` start it over.
+    pub fn archive(&self) -> &Archive<T> {
+        &self.archive
+    }
+
+    /// Returns the particle with the lowest current eval, and that eval.
+    ///
+    /// Together with `best` this gives the range of the population's evals.
+    pub fn worst(&self) -> (&T, T::Eval) {
+        let worst = self.worst_index();
+        (&self.particles[worst], self.evals[worst].clone())
+    }
+
+    /// Returns the `k` particles with the highest current evals, best first, or the whole
+    /// population if it is smaller than `k`.
+    ///
+    /// Only the top `k` are sorted, after a linear-time selection of them. Particles with equal
+    /// evals are ordered by ascending index, so the result is reproducible whatever the
+    /// selection does.
+    pub fn top_k(&self, k: usize) -> Vec<(&T, T::Eval)> {
+        let k = cmp::min(k, self.particles.len());
+        if k == 0 {
+            return Vec::new();
+        }
+
+        let (evals, comparator) = (&self.evals, &self.comparator);
+        let by_eval_desc = |a: &usize, b: &usize| {
+            compare(comparator, &evals[*b], &evals[*a]).then(a.cmp(b))
+        };
+
+        let mut order: Vec<usize> = (0..self.particles.len()).collect();
+        order.select_nth_unstable_by(k - 1, by_eval_desc);
+        order.truncate(k);
+        order.sort_by(by_eval_desc);
+
+        order
+            .into_iter()
+            .map(|i| (&self.particles[i], evals[i].clone()))
+            .collect()
+    }
+
+    /// Returns the mean position of the particles, the centroid of the swarm.
+    pub fn centroid_position(&self) -> T::Pos {
+        let mut sum = self.particles[0].pos();
+        for p in &self.particles[1..] {
+            sum = sum + p.pos();
+        }
+        sum * (1.0 / self.particles.len() as f64)
+    }
+
+    /// Evaluates the centroid of the swarm, which on smooth objectives sometimes beats every
+    /// particle. The evaluation is not counted by `eval_count`.
+    pub fn centroid_eval(&self) -> T::Eval {
+        let mut probe = self.particles[0].clone();
+        *probe.pos_mut() = self.centroid_position();
+        probe.eval()
+    }
+
+    /// Runs `update` as long as the next call cannot push `eval_count` above `max_evals`, and
+    /// returns the number of calls made.
+    ///
+    /// A budget of evaluations rather than iterations compares algorithms which evaluate
+    /// different numbers of candidates per iteration fairly. Each update is assumed to cost
+    /// `Optimizer::step_evals`, which covers resampling, teleporting, the local search and
+    /// the other features making extra evaluations; the evaluations of the initial population
+    /// count too, so none may be run at all.
+    ///
+    /// # Example
+    /// ```
+    /// use meta_heuristics::pso::PSO;
+    /// use meta_heuristics::problem::Problem;
+    ///
+    /// // A parabola peaking at 1.
+    /// struct Parabola;
+    ///
+    /// impl Problem for Parabola {
+    ///     type Solution = f64;
+    ///     type Eval = f64;
+    ///
+    ///     fn evaluate(&self, x: &f64) -> f64 {
+    ///         -(x - 1.0) * (x - 1.0)
+    ///     }
+    /// }
+    ///
+    /// let mut pso = PSO::new_with_problem(8, Parabola, || 0.0, 0.7, 1.4, 1.4);
+    /// pso.set_resampling(3).unwrap();
+    /// pso.set_teleport(0.5).unwrap();
+    ///
+    /// // Each update costs 3 evaluations per particle, and 1 more if a particle teleports.
+    /// assert_eq!(pso.run_eval_budget(100), 3);
+    /// assert!(pso.eval_count() <= 100);
+    /// ```
+    pub fn run_eval_budget(&mut self, max_evals: usize) -> usize {
+        let mut iters = 0;
+        while self.eval_count + Optimizer::step_evals(self) <= max_evals {
+            self.update();
+            iters += 1;
+        }
+        iters
+    }
+
+    /// Returns the current population size and coefficients.
+    pub fn config(&self) -> PsoConfig {
+        PsoConfig {
+            particles_num: self.particles.len(),
+            inertia: self.inetia,
+            c_local: self.c_local,
+            c_global: self.c_global,
+        }
+    }
+
+    /// Replaces the inertia and both acceleration coefficients with those of `config` for the
+    /// following updates, as an external controller applying a new parameter vector each step
+    /// would. An inertia schedule set with `set_inertia_fn` still overrides the inertia.
+    ///
+    /// The coefficients are validated before any of them is applied, so on error the swarm is
+    /// left unchanged. The inertia must be finite and the acceleration coefficients finite and
+    /// non-negative. The population size is not changed, so `config.particles_num` must be the
+    /// current one, as it is when starting from `config`.
+    pub fn reconfigure(&mut self, config: PsoConfig) -> Result<(), MetaHeuristicsError> {
+        if config.particles_num != self.particles.len() {
+            return Err(MetaHeuristicsError::InvalidParameter {
+                           name: "particles_num",
+                           value: config.particles_num as f64,
+                       });
+        }
+        if !config.inertia.is_finite() {
+            return Err(MetaHeuristicsError::InvalidParameter {
+                           name: "inertia",
+                           value: config.inertia,
+                       });
+        }
+        for &(name, value) in &[("c_local", config.c_local), ("c_global", config.c_global)] {
+            if !(value >= 0.0 && value.is_finite()) {
+                return Err(MetaHeuristicsError::InvalidParameter { name, value });
+            }
+        }
+
+        self.inetia = config.inertia;
+        self.c_local = config.c_local;
+        self.c_global = config.c_global;
+        Ok(())
+    }
+
+    pub fn particles(&self) -> &[T] {
+        &self.particles
+    }
+
+    /// Returns the position, velocity and personal best of every particle as plain fields, in
+    /// the order of `particles`, for bulk analysis or export.
+    pub fn snapshot(&self) -> Vec<ParticleView<T::Pos, T::Eval>> {
+        self.particles
+            .iter()
+            .map(|p| {
+                     let (best_pos, best_eval) = p.best();
+                     ParticleView {
+                         pos: p.pos(),
+                         vel: p.vel(),
+                         best_pos,
+                         best_eval,
+                     }
+                 })
+            .collect()
+    }
+
+    /// Writes the current population to `writer` as CSV, for analysis in other tools.
+    ///
+    /// The header names the position components `x0`, `x1`, ..., as many as
+    /// `Components::components` returns for the first particle, followed by `eval`. Each
+    /// particle then takes one row, in index order, with its current position and eval.
+    pub fn write_csv<W>(&self, writer: &mut W) -> io::Result<()>
+        where W: io::Write,
+              T: Components,
+              T::Eval: fmt::Display
+    {
+        let dim = self.particles.first().map_or(0, |p| p.components().len());
+        let header: Vec<String> = (0..dim).map(|i| format!("x{}", i)).collect();
+        if header.is_empty() {
+            writeln!(writer, "eval")?;
+        } else {
+            writeln!(writer, "{},eval", header.join(","))?;
+        }
+
+        for (p, e) in self.particles.iter().zip(&self.evals) {
+            for x in p.components() {
+                write!(writer, "{},", x)?;
+            }
+            writeln!(writer, "{}", e)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the number of particles.
+    pub fn len(&self) -> usize {
+        self.particles.len()
+    }
+
+    /// Returns whether the swarm has no particles.
+    pub fn is_empty(&self) -> bool {
+        self.particles.is_empty()
+    }
+
+    /// Sets whether the objective changes over time, as `Candidate::eval_at` describes.
+    /// Disabled by default.
+    ///
+    /// When enabled, each `update` evaluates the moved particles with `eval_at` the value
+    /// `iteration()` takes after the update, instead of with `Particle::eval_batch`. Personal
+    /// bests found at earlier iterations are re-evaluated at the same iteration before being
+    /// compared, which doubles the evaluations per update, and neighborhood bests are
+    /// recomputed from scratch. The initial population is evaluated with `eval`, which should
+    /// agree with `eval_at(0)`.
+    pub fn set_time_varying(&mut self, time_varying: bool) {
+        self.time_varying = time_varying;
+    }
+
+    /// Sets whether each `update` calls `Candidate::repair` on every particle once it has moved,
+    /// after the search bounds and any Lévy flight are applied, so that the particles are
+    /// evaluated, and the velocities updated, at feasible positions. Disabled by default.
+    pub fn set_repair(&mut self, repair: bool) {
+        self.repair = repair;
+    }
+
+    /// Sets whether each `update` checks `Candidate::is_valid` on every particle once it has
+    /// moved and, with `set_repair`, been repaired, reverting the invalid ones to their
+    /// positions before the move. A reverted particle keeps its new velocity. The local search
+    /// discards invalid perturbations without evaluating them. Disabled by default.
+    ///
+    /// Unlike bounds, validity can express the invariants of structured search spaces, such as
+    /// permutations or unit vectors. It costs a copy of every position and a call to `is_valid`
+    /// per particle and update, on top of the evaluations.
+    pub fn set_validate(&mut self, validate: bool) {
+        self.validate = validate;
+    }
+
+    /// Sets whether `best` reports the all-time best kept by `archive` rather than the best of
+    /// the current swarm. Disabled by default.
+    ///
+    /// The swarm's best follows the current positions, so it degrades whenever features such
+    /// as Lévy flights, restarts or re-initialization move the population away from it; with
+    /// elitism, the reported best never does, even across `reset`. `reset_and_clear_archive`
+    /// starts the reported best over with the new swarm. The swarm is still guided by its
+    /// current best.
+    pub fn set_elitism(&mut self, elitism: bool) {
+        self.elitism = elitism;
+    }
+
+    /// Adapts the step size of the local search set with `set_local_search` by an
+    /// `AdaptiveStep`, aiming at the ratio `target_ratio` of accepted perturbations; 0.2 is
+    /// the classic choice. The step size passed to `set_local_search` is the initial one, and
+    /// success is measured over windows of 10 steps. Not set by default.
+    ///
+    /// Rejects a `target_ratio` outside `(0, 1)`.
+    pub fn set_adaptive_mutation(&mut self, target_ratio: f64) -> Result<(), MetaHeuristicsError> {
+        if !(target_ratio > 0.0 && target_ratio < 1.0) {
+            return Err(MetaHeuristicsError::InvalidParameter {
+                           name: "target_ratio",
+                           value: target_ratio,
+                       });
+        }
+
+        self.adaptive_target = Some(target_ratio);
+        self.mutation_step = None;
+        Ok(())
+    }
+
+    /// Keeps the step size of the local search fixed again, at the one passed to
+    /// `set_local_search`.
+    pub fn unset_adaptive_mutation(&mut self) {
+        self.adaptive_target = None;
+        self.mutation_step = None;
+    }
+
+    /// Returns the step size the local search currently perturbs by, including the linear
+    /// decay, or `None` if it is disabled.
+    pub fn mutation_step_size(&self) -> Option<f64> {
+        let scale = self.decay_scale();
+        self.local_search
+            .as_ref()
+            .map(|search| {
+                     let step_size = search.step_size;
+                     self.mutation_step.as_ref().map_or(step_size, AdaptiveStep::sigma) * scale
+                 })
+    }
+
+    /// Scales the step size of the local search by `1 - progress()`, so that it shrinks
+    /// linearly from its full size at the first update to 0 once `max_iters` updates have been
+    /// made, moving the search from exploration to exploitation over a known budget. This
+    /// applies on top of `set_adaptive_mutation`. Not set by default.
+    pub fn set_linear_decay(&mut self, max_iters: usize) {
+        self.decay_iters = Some(max_iters);
+    }
+
+    pub fn unset_linear_decay(&mut self) {
+        self.decay_iters = None;
+    }
+
+    /// Returns the fraction of the budget of `set_linear_decay` used so far, the number of
+    /// updates made divided by `max_iters` and capped at 1, or `None` if no budget is set.
+    pub fn progress(&self) -> Option<f64> {
+        self.decay_iters.map(|max_iters| (self.iteration as f64 / max_iters.max(1) as f64).min(1.0))
+    }
+
+    fn decay_scale(&self) -> f64 {
+        1.0 - self.progress().unwrap_or(0.0)
+    }
+
+    /// Sets whether each `update` records the eval of the swarm's best afterwards, for plotting
+    /// convergence. Disabled by default; disabling discards the recorded history.
+    pub fn set_record_history(&mut self, record: bool) {
+        self.record_history = record;
+        if !record {
+            self.history = Vec::new();
+        }
+    }
+
+    /// Reserves room for recording `iters` more updates in the history, if recording is enabled.
+    ///
+    /// This is purely an optimization hint to avoid reallocating during long runs whose length
+    /// is known in advance; the recorded history is the same either way.
+    pub fn reserve_history(&mut self, iters: usize) {
+        if self.record_history {
+            self.history.reserve(iters);
+        }
+    }
+
+    /// Returns the best evals recorded since history recording was enabled, one per `update`.
+    /// The history is empty while recording is disabled, and `reset` clears it.
+    pub fn history(&self) -> &Vec<T::Eval> {
+        &self.history
+    }
+
+    /// Sets whether each `update` records the position of the swarm's best afterwards, for
+    /// following how the incumbent moves. Disabled by default; disabling discards the recorded
+    /// positions.
+    ///
+    /// This is independent of `set_record_history`, as storing a position per update can take
+    /// much more memory than storing an eval.
+    pub fn set_record_best_positions(&mut self, record: bool) {
+        self.record_best_positions = record;
+        if !record {
+            self.best_position_history = Vec::new();
+        }
+    }
+
+    /// Returns the best positions recorded since recording them was enabled, one per `update`.
+    /// `reset` clears them.
+    pub fn best_position_history(&self) -> &[T::Pos] {
+        &self.best_position_history
+    }
+
+    /// Returns the number of objective evaluations performed so far, including the initial
+    /// population. Batched evaluations count once per particle.
+    pub fn eval_count(&self) -> usize {
+        self.eval_count
+    }
+
+    /// Returns the number of `update` calls made so far.
+    pub fn iteration(&self) -> usize {
+        self.iteration
+    }
+}
+
+impl<T, R> PSO<T, R>
+    where T: Particle + Clone,
+          T::Eval: Into<f64>,
+          R: Rng
+{
+    /// Requires an eval to exceed the previous one by at least `eps` to count as an improvement,
+    /// for personal bests, for the global best and for the stagnation counter. Defaults to 0,
+    /// where any strict increase is an improvement.
+    ///
+    /// A global best beaten by less than `eps` is kept, rather than replaced by the swarm's
+    /// current best, and so is one offered to `set_global_best`.
+    ///
+    /// The gain is measured in the order of the comparator set with `set_comparator`: under one
+    /// preferring smaller evals, an eval must fall below the previous one by `eps`.
+    pub fn set_improvement_epsilon(&mut self, eps: f64) {
+        self.improvement = Some(Improvement {
+                                    eps,
+                                    to_f64: ::eval_to_f64::<T::Eval>,
+                                });
+    }
+
+    /// Returns how much the last `update` raised the best eval found so far, which is never
+    /// negative. The first update's delta is measured from the initial population's best, and
+    /// it is 0 before any update or right after a `restart` or `reset`.
+    ///
+    /// Under a comparator set with `set_comparator`, the delta is measured in its order, so a
+    /// comparator preferring smaller evals counts how far the best fell.
+    ///
+    /// Improvements within the epsilon set with `set_improvement_epsilon` are not counted, as
+    /// for `stagnation`.
+    pub fn improvement_delta(&self) -> f64 {
+        gain(&self.comparator,
+             ::eval_to_f64::<T::Eval>,
+             &self.best_seen,
+             &self.prev_best_seen)
+    }
+
+    /// Returns the minimum, maximum, mean and standard deviation of the particles' current
+    /// evals, computed in a single pass. Unlike `diversity`, which measures the spread of the
+    /// positions, this measures the spread of the evals.
+    pub fn eval_stats(&self) -> EvalStats {
+        // Welford's online algorithm.
+        let mut stats = EvalStats {
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            mean: 0.0,
+            std: 0.0,
+        };
+        let mut m2 = 0.0;
+        for (i, e) in self.evals.iter().enumerate() {
+            let e = e.clone().into();
+            stats.min = stats.min.min(e);
+            stats.max = stats.max.max(e);
+            let delta = e - stats.mean;
+            stats.mean += delta / (i + 1) as f64;
+            m2 += delta * (e - stats.mean);
+        }
+        stats.std = (m2 / self.evals.len() as f64).sqrt();
+        stats
+    }
+
+    /// Returns whether the best eval found so far has improved by less than `tol` over the last
+    /// `patience` updates, so that a caller owning the loop can ask whether to stop. Fewer
+    /// than `patience` updates since construction or `reset` never count as converged.
+    ///
+    /// Unlike `stagnation`, this does not depend on the improvement epsilon, and `restart` does
+    /// not clear it. It keeps one eval per update to answer for any `patience`. As for
+    /// `improvement_delta`, the improvement is measured in the order of the comparator set with
+    /// `set_comparator`, if any.
+    pub fn has_converged(&self, tol: f64, patience: usize) -> bool {
+        let n = self.best_trace.len();
+        if n <= patience {
+            return false;
+        }
+        let improvement = gain(&self.comparator,
+                               ::eval_to_f64::<T::Eval>,
+                               &self.best_trace[n - 1],
+                               &self.best_trace[n - 1 - patience]);
+        improvement < tol
+    }
+
+    /// Returns whether any particle has escaped to infinity, with a position which is not
+    /// `Candidate::is_finite` or an eval which is not finite, so that a driver can abort the
+    /// run, or restart it with the velocities clamped.
+    pub fn is_diverging(&self) -> bool {
+        self.particles.iter().any(|p| !p.is_finite()) ||
+        self.evals.iter().any(|e| !::eval_to_f64(e).is_finite())
+    }
+
+    /// Updates until the best eval is within `epsilon` of the known `optimum`, or `max_iters`
+    /// times. Returns whether the optimum was reached and the number of updates run.
+    ///
+    /// This is the success criterion of benchmark studies, where the success rate over many
+    /// runs is reported.
+    pub fn run_until_optimum(&mut self,
+                             optimum: T::Eval,
+                             epsilon: f64,
+                             max_iters: usize)
+                             -> (bool, usize) {
+        let optimum = optimum.into();
+        let reached = |pso: &Self| (optimum - pso.best.1.clone().into()).abs() <= epsilon;
+
+        for i in 0..max_iters {
+            if reached(self) {
+                return (true, i);
+            }
+            self.update();
+        }
+        (reached(self), max_iters)
+    }
+
+    /// Polishes the particle with the best current eval by up to `steps` steps of gradient
+    /// ascent with the learning rate `lr`, and returns whether it improved. This suits smooth
+    /// objectives, whose optimum the swarm has located but not pinned down.
+    ///
+    /// The gradient of `eval` is estimated by central differences with step `h` along each
+    /// `Axes::axis`, costing `2 * dim + 1` evaluations per step. The polish stops at the first
+    /// step that does not improve the eval. The polished particle stays in the swarm, with its
+    /// personal best and the swarm's best updated.
+    pub fn polish(&mut self, steps: usize, h: f64, lr: f64) -> bool
+        where T: Axes
+    {
+        let index = self.best_index();
+        let mut current = self.particles[index].clone();
+        let mut eval = self.evals[index].clone();
+
+        let mut improved = false;
+        for _ in 0..steps {
+            let pos = current.pos();
+            let eval_at = |p: T::Pos| {
+                let mut probe = current.clone();
+                *probe.pos_mut() = p;
+                probe.eval().into()
+            };
+
+            let mut step = pos.clone() * 0.0;
+            for i in 0..current.dim() {
+                let axis = current.axis(i);
+                let slope = (eval_at(pos.clone() + axis.clone() * h) -
+                             eval_at(pos.clone() - axis.clone() * h)) / (2.0 * h);
+                step = step + axis * (lr * slope);
+            }
+
+            let mut candidate = current.clone();
+            *candidate.pos_mut() = self.confine(pos + step);
+            let e = candidate.eval();
+            self.eval_count += 2 * current.dim() + 1;
+            if !is_better(&self.comparator, &e, &eval) {
+                break;
+            }
+            current = candidate;
+            eval = e;
+            improved = true;
+        }
+
+        if improved {
+            if self.improves(&eval, &current.best().1) {
+                *current.best_mut() = (current.pos(), eval.clone());
+            }
+            self.particles[index] = current;
+            self.evals[index] = eval;
+            self.stale[index] = 0;
+            self.reselect_best();
+            self.recalc_neighborhood_best();
+        }
+        improved
+    }
+
+    /// Enables fitness sharing with the sharing radius `sigma`, which must be positive.
+    ///
+    /// Selecting the best particle and updating personal bests then compare shared fitness: an
+    /// eval divided by its niche count, the sum of `1 - d / sigma` over the particles within
+    /// `Metric::pos_distance` `d < sigma` of the position. Crowded regions are derated, so the
+    /// swarm keeps clusters around several optima instead of collapsing onto one; pairing it with
+    /// `Topology::Ring` keeps the clusters apart.
+    ///
+    /// Evals are derated by division, so they must be positive: the current evals are checked
+    /// here, with `MetaHeuristicsError::NonPositiveEval` if one is not, and the shared fitness
+    /// ignores any comparator, so sharing is rejected while one is set with `set_comparator`.
+    ///
+    /// # Panics
+    /// `update` panics once it evaluates a particle to a value which is not positive.
+    pub fn set_fitness_sharing(&mut self, sigma: f64) -> Result<(), MetaHeuristicsError>
+        where T: Metric
+    {
+        if sigma <= 0.0 || sigma.is_nan() {
+            return Err(MetaHeuristicsError::InvalidParameter {
+                           name: "sigma",
+                           value: sigma,
+                       });
+        }
+        if self.comparator.is_some() {
+            return Err(MetaHeuristicsError::IncompatibleSettings {
+                           setting: "fitness sharing",
+                           with: "a comparator",
+                       });
+        }
+        if self.evals.iter().any(|e| ::eval_to_f64(e) <= 0.0) {
+            return Err(MetaHeuristicsError::NonPositiveEval);
+        }
+
+        self.sharing = Some(Sharing {
+                                sigma,
+                                distance: T::pos_distance,
+                                to_f64: ::eval_to_f64::<T::Eval>,
+                            });
+        self.reselect_best();
+        Ok(())
+    }
+
+    /// Disables fitness sharing.
+    pub fn unset_fitness_sharing(&mut self) {
+        self.sharing = None;
+        self.reselect_best();
+    }
+}
+
+#[cfg(feature = "log")]
+impl<T, R> PSO<T, R>
+    where T: Particle + Clone,
+          T::Eval: fmt::Debug,
+          R: Rng
+{
+    /// Sets whether the trace logged by each `update` includes the swarm's best eval, formatted
+    /// with `Debug`. Disabled by default, so that evals need not implement `Debug` to be logged.
+    /// Requires the `log` feature.
+    pub fn set_log_best_eval(&mut self, log_best: bool) {
+        self.log_eval = if log_best {
+            Some(::debug_eval::<T::Eval>)
+        } else {
+            None
+        };
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T, R> PSO<T, R>
+    where T: Metric + Clone,
+          T::Eval: Into<f64>,
+          R: Rng
+{
+    /// Updates `iters` times like `run`, writing one line of JSON to `writer` after each
+    /// update, for streaming the progress of a run to monitoring tools as NDJSON. Requires the
+    /// `serde` feature.
+    ///
+    /// Each line is an object with the fields of `summary`: `iter`, the number of updates made
+    /// so far, `best_eval`, `diversity` and `eval_count`. A best eval or diversity which is not
+    /// finite is written as `null`, as JSON has no number for it. Stops at the first write
+    /// error.
+    pub fn run_streaming<W: io::Write>(&mut self, iters: usize, writer: &mut W) -> io::Result<()> {
+        for _ in 0..iters {
+            self.update();
+            let progress = Progress {
+                iter: self.iteration,
+                best_eval: ::eval_to_f64(self.best_ref().1),
+                diversity: self.diversity(),
+                eval_count: self.eval_count,
+            };
+            serde_json::to_writer(&mut *writer, &progress)?;
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+}
+
+/// A line of `PSO::run_streaming`.
+#[cfg(feature = "serde")]
+#[derive(Serialize)]
+struct Progress {
+    iter: usize,
+    best_eval: f64,
+    diversity: f64,
+    eval_count: usize,
+}
+
+#[cfg(feature = "plotters")]
+impl<T, R> PSO<T, R>
+    where T: Particle + Clone,
+          T::Eval: Into<f64>,
+          R: Rng
+{
+    /// Renders the best-eval history as a chart of iteration against best eval, to a PNG image
+    /// at `path`, or an SVG image if its extension is `svg`. Requires the `plotters` feature.
+    ///
+    /// The history is only recorded with `set_record_history`, so the chart starts at the
+    /// update it was enabled for, and is empty without it.
+    pub fn plot_convergence<P>(&self, path: P) -> Result<(), Box<dyn error::Error>>
+        where P: AsRef<Path>
+    {
+        let first = self.iteration - self.history.len() + 1;
+        let points: Vec<(f64, f64)> = self.history
+            .iter()
+            .enumerate()
+            .map(|(i, e)| ((first + i) as f64, e.clone().into()))
+            .collect();
+        ::plot::line_chart(path.as_ref(), &points, "iteration", "best eval")
+    }
+
+    /// Renders the current positions to a PNG or SVG image at `path` like `plot_convergence`,
+    /// with the swarm's best highlighted. One-dimensional positions are plotted against their
+    /// evals, and two-dimensional ones in the plane. Requires the `plotters` feature.
+    ///
+    /// Fails with `MetaHeuristicsError::UnsupportedDimension` for positions of any other
+    /// dimension, or of mixed dimensions.
+    pub fn plot_particles<P>(&self, path: P) -> Result<(), Box<dyn error::Error>>
+        where P: AsRef<Path>,
+              T: Components
+    {
+        let dim = self.best.0.components().len();
+        let point = |p: &T, e: &T::Eval| match p.components()[..] {
+            [x] if dim == 1 => Ok((x, e.clone().into())),
+            [x, y] if dim == 2 => Ok((x, y)),
+            ref c => Err(MetaHeuristicsError::UnsupportedDimension { dim: c.len() }),
+        };
+        let points = self.particles
+            .iter()
+            .zip(&self.evals)
+            .map(|(p, e)| point(p, e))
+            .collect::<Result<Vec<_>, _>>()?;
+        let best = point(&self.best.0, &self.best.1)?;
+        let (x_desc, y_desc) = if dim == 1 { ("position", "eval") } else { ("x", "y") };
+        ::plot::scatter_chart(path.as_ref(), &points, best, x_desc, y_desc)
+    }
+}
+
+impl<T, R> PSO<T, R>
+    where T: Particle + Clone,
+          T::Pos: Hash + Eq + Send + 'static,
+          T::Eval: Send + 'static,
+          R: Rng
+{
+    /// Memoizes the evals of `update` by position, for expensive deterministic objectives over
+    /// discrete positions such as `discrete::IntPos`, which the swarm revisits often. Only the
+    /// positions new to the cache are evaluated, with `Particle::eval_batch`; cache hits do not
+    /// count towards `eval_count`.
+    ///
+    /// The cache assumes the eval depends on the position only, so it is bypassed with
+    /// `set_time_varying`. It is never evicted; `disable_eval_cache` drops it.
+    pub fn enable_eval_cache(&mut self) {
+        let mut cache = HashMap::new();
+        self.eval_cache = Some(Box::new(move |particles: &[T]| {
+            let mut seen = HashSet::new();
+            let misses: Vec<T> = particles
+                .iter()
+                .filter(|p| {
+                            let pos = p.pos();
+                            !cache.contains_key(&pos) && seen.insert(pos)
+                        })
+                .cloned()
+                .collect();
+            for (p, e) in misses.iter().zip(T::eval_batch(&misses)) {
+                cache.insert(p.pos(), e);
+            }
+
+            let evals = particles.iter().map(|p| cache[&p.pos()].clone()).collect();
+            (evals, misses.len())
+        }));
+    }
+
+    pub fn disable_eval_cache(&mut self) {
+        self.eval_cache = None;
+    }
+
+    /// Returns the number of evals of `update` served by the cache instead of evaluated.
+    pub fn eval_cache_hits(&self) -> usize {
+        self.cache_hits
+    }
+}
+
+impl<T, R> PSO<T, R>
+    where T: Particle + Clone + Sync + 'static,
+          T::Eval: Send + Sync + 'static,
+          R: Rng
+{
+    /// Evaluates the swarm of each `update` on `threads` threads with `eval_parallel`, instead
+    /// of with `Particle::eval_batch`, once the swarm has more particles than the parallel
+    /// threshold. Smaller swarms are evaluated sequentially, as spawning the threads would cost
+    /// more than it saves.
+    ///
+    /// The threads are scoped `std::thread`s spawned by every update, not a `rayon` pool: this
+    /// crate has no `rayon` feature, and scoped threads need no dependency. Without a pool to
+    /// reuse, the threshold matters all the more.
+    ///
+    /// The swarm's best is then selected on the threads too, with `best_index_parallel`, unless
+    /// a comparator is set. All random numbers are drawn before the evaluation, and the
+    /// selection picks the same particle as a sequential one, so seeded runs are unchanged
+    /// whatever the number of threads.
+    ///
+    /// # Panics
+    /// Panics if `threads` is 0.
+    pub fn enable_parallel_eval(&mut self, threads: usize) {
+        assert!(threads > 0, "no threads");
+        self.parallel_eval = Some(Box::new(move |particles: &[T]| {
+                                                ::eval_parallel(particles, threads)
+                                            }));
+        self.parallel_best = Some(Box::new(move |evals: &[T::Eval]| {
+                                                ::best_index_parallel(evals, threads)
+                                            }));
+    }
+
+    pub fn disable_parallel_eval(&mut self) {
+        self.parallel_eval = None;
+        self.parallel_best = None;
+    }
+}
+
+impl<T, R> PSO<T, R>
+    where T: Particle + Clone + 'static,
+          T::Eval: Into<f64> + From<f64>,
+          R: Rng
+{
+    /// Evaluates every particle of each `update`, and every step of the local search, as the
+    /// average of `samples` calls to `Candidate::eval`, for noisy objectives. Defaults to 1,
+    /// a single call; remove it with `unset_resampling`.
+    ///
+    /// A single noisy eval favors the particles that were lucky, which then keep misleading
+    /// the swarm as personal and global bests. Averaging shrinks the noise's standard deviation
+    /// by the square root of `samples`, at the cost of as many evaluations, which `eval_count`
+    /// counts. The initial swarm and teleported particles are evaluated once. Resampling takes
+    /// precedence over the eval cache and parallel evaluation, but not over `set_time_varying`.
+    ///
+    /// Rejects `samples` of 0.
+    pub fn set_resampling(&mut self, samples: usize) -> Result<(), MetaHeuristicsError> {
+        if samples == 0 {
+            return Err(MetaHeuristicsError::InvalidParameter {
+                           name: "samples",
+                           value: 0.0,
+                       });
+        }
+
+        let resample = move |p: &T| {
+            let sum: f64 = (0..samples).map(|_| p.eval().into()).sum();
+            T::Eval::from(sum / samples as f64)
+        };
+        self.resampling = Some((samples, Box::new(resample)));
+        Ok(())
+    }
+}
+
+impl<T, R> PSO<T, R>
+    where T: Particle<Eval = f64> + Clone,
+          R: Rng
+{
+    /// Orders the evals by `f64::total_cmp`, a total order, rather than by `PartialOrd`, under
+    /// which NaN is incomparable to everything and counts as equal to whatever it is compared
+    /// with. The selection of the bests then depends only on the evals, not on the order in
+    /// which they are compared. This is `set_comparator(f64::total_cmp)`; remove it with
+    /// `unset_comparator`.
+    ///
+    /// The order puts negative NaN below every number and positive NaN above, and -0 below +0.
+    /// As positive NaN would then be the best eval, objectives which may produce NaN should
+    /// make it negative.
+    pub fn set_total_order_f64(&mut self) {
+        self.set_comparator(f64::total_cmp);
+    }
+}
+
+impl<T, R> PSO<T, R>
+    where T: Levy + Clone,
+          R: Rng
+{
+    /// Enables Lévy flights: after moving, each particle takes an additional `Levy::levy_step`
+    /// with probability `prob`. `exponent` is the stability index of the Lévy distribution and
+    /// must be in `(0, 2]`; 1.5 is the usual choice.
+    pub fn set_levy(&mut self, prob: f64, exponent: f64) -> Result<(), MetaHeuristicsError> {
+        if !(0.0..=1.0).contains(&prob) {
+            return Err(MetaHeuristicsError::InvalidParameter {
+                           name: "prob",
+                           value: prob,
+                       });
+        }
+        if !(exponent > 0.0 && exponent <= 2.0) {
+            return Err(MetaHeuristicsError::InvalidParameter {
+                           name: "exponent",
+                           value: exponent,
+                       });
+        }
+
+        self.levy = Some(LevyFlights {
+                             prob,
+                             exponent,
+                             step: T::levy_step::<R>,
+                         });
+        Ok(())
+    }
+
+    /// Disables Lévy flights.
+    pub fn unset_levy(&mut self) {
+        self.levy = None;
+    }
+}
+
+impl<T, R> PSO<T, R>
+    where T: VelocityNorm + Clone,
+          R: Rng
+{
+    /// Returns the mean velocity magnitude over the swarm.
+    pub fn mean_velocity_magnitude(&self) -> f64 {
+        let sum: f64 = self.particles.iter().map(T::vel_magnitude).sum();
+        sum / self.particles.len() as f64
+    }
+
+    /// Returns the largest velocity magnitude in the swarm. A steadily growing value means the
+    /// swarm is diverging.
+    pub fn max_velocity_magnitude(&self) -> f64 {
+        self.particles.iter().map(T::vel_magnitude).fold(0.0, f64::max)
+    }
+}
+
+impl<T, R> PSO<T, R>
+    where T: Metric + Clone,
+          R: Rng
+{
+    /// Sets whether every `update` adapts the inertia and the coefficients to the swarm's
+    /// evolutionary state, as Zhan et al.'s adaptive PSO (APSO) does. Disabled by default.
+    ///
+    /// At the start of each update, the inertia is set from the evolutionary factor `f` to
+    /// `1 / (1 + 1.5 * exp(-2.6 * f))`, which lies between 0.4 and 0.9, overriding the fixed
+    /// inertia and `set_inertia_fn`. The coefficients then take a step `delta`, drawn uniformly
+    /// from [0.05, 0.1] for each, by the state of `evolutionary_state`:
+    ///
+    /// * `Exploration` raises `c_local` by `delta` and lowers `c_global` by `delta`.
+    /// * `Exploitation` raises `c_local` by `delta / 2` and lowers `c_global` by `delta / 2`.
+    /// * `Convergence` raises both by `delta / 2`.
+    /// * `JumpingOut` lowers `c_local` by `delta` and raises `c_global` by `delta`.
+    ///
+    /// Each coefficient is clamped to [1.5, 2.5], and both are scaled down to sum up to 4 when
+    /// they exceed it. Enabling it sets both coefficients to APSO's initial 2; disabling it keeps
+    /// the values last adapted, which `config` reports. APSO's elitist learning in the
+    /// convergence state is available separately as `set_elitist_learning`.
+    pub fn set_apso(&mut self, apso: bool) {
+        if apso && self.apso.is_none() {
+            self.c_local = 2.0;
+            self.c_global = 2.0;
+        }
+        self.apso = if apso { Some(T::pos_distance) } else { None };
+    }
+
+    /// Sets the minimum length of a particle's move: whenever a move, measured with
+    /// `Metric::pos_distance`, would be shorter than `min_step`, the particle takes a step of
+    /// exactly `min_step` in a random direction from `RandomVelocity::random_vel` instead. This
+    /// keeps a swarm whose velocities vanish far from the optimum exploring. `min_step` must be
+    /// non-negative, and 0, the default, disables it.
+    pub fn set_min_step(&mut self, min_step: f64) -> Result<(), MetaHeuristicsError>
+        where T: RandomVelocity
+    {
+        if min_step < 0.0 || min_step.is_nan() {
+            return Err(MetaHeuristicsError::InvalidParameter {
+                           name: "min_step",
+                           value: min_step,
+                       });
+        }
+
+        self.min_step = if min_step > 0.0 {
+            Some(MinStep {
+                     length: min_step,
+                     distance: T::pos_distance,
+                     random_vel: T::random_vel::<R>,
+                 })
+        } else {
+            None
+        };
+        Ok(())
+    }
+
+    /// Sets whether a particle whose move leaves the search bounds is replaced by a fresh one
+    /// from `RegionSampling::random_in` within them, its personal best and velocity included,
+    /// instead of being brought back by the boundary behavior. This keeps injecting diversity
+    /// instead of piling particles up at the walls. The stray position is detected with
+    /// `Metric::pos_distance` to its clamped image. Disabled by default.
+    ///
+    /// `random_in` draws from the swarm's generator, so seeded runs reproduce the fresh
+    /// particles. Single moves of the local search and of restarts follow the boundary behavior
+    /// instead, as they have no particle to replace.
+    pub fn set_reinitialization(&mut self, reinitialization: bool)
+        where T: RegionSampling
+    {
+        self.reinitialization = if reinitialization {
+            Some(Reinitialization {
+                     distance: T::pos_distance,
+                     random_in: T::random_in::<R>,
+                 })
+        } else {
+            None
+        };
+    }
+
+    /// Returns the mean distance of the particles from the swarm's centroid, measured with
+    /// `Metric::pos_distance`.
+    pub fn diversity(&self) -> f64 {
+        let centroid = self.centroid_position();
+        let sum: f64 = self.particles
+            .iter()
+            .map(|p| T::pos_distance(&p.pos(), &centroid))
+            .sum();
+        sum / self.particles.len() as f64
+    }
+
+    /// Returns the distance of each particle from the swarm's current best, in index order,
+    /// measured with `Metric::pos_distance`.
+    ///
+    /// Unlike `diversity`, this is relative to the incumbent: the distances shrink as the swarm
+    /// converges on it, and split into groups while it is spread over several clusters.
+    pub fn distances_to_best(&self) -> Vec<f64> {
+        let best = self.best.0.pos();
+        self.particles
+            .iter()
+            .map(|p| T::pos_distance(&p.pos(), &best))
+            .collect()
+    }
+
+    /// Returns APSO's evolutionary factor of the swarm, `(d_g - d_min) / (d_max - d_min)`,
+    /// where each particle's mean distance to the others is measured with
+    /// `Metric::pos_distance`, `d_min` and `d_max` are the smallest and largest of them, and
+    /// `d_g` is that of the swarm's best. The factor lies in [0, 1], and is 0 when the mean
+    /// distances are all equal, as with fewer than two particles.
+    ///
+    /// A small factor means the best lies amid the swarm, and a large one that it lies away
+    /// from the others. This takes `O(n²)` distance computations.
+    pub fn evolutionary_factor(&self) -> f64 {
+        self.evolutionary_factor_by(T::pos_distance)
+    }
+
+    /// Returns the evolutionary state the swarm is in, classified from `evolutionary_factor`.
+    pub fn evolutionary_state(&self) -> EvolutionaryState {
+        EvolutionaryState::from_factor(self.evolutionary_factor())
+    }
+
+    /// Groups the particles into clusters by single linkage: two particles are in the same
+    /// cluster if a chain of particles leads from one to the other, each within `radius` of
+    /// the next, as measured by `Metric::pos_distance`. The number of clusters tells how many
+    /// basins the swarm occupies.
+    ///
+    /// Each cluster lists its particles' indices in ascending order, and the clusters are
+    /// ordered by their smallest index. This takes `O(n²)` distance computations.
+    pub fn clusters(&self, radius: f64) -> Vec<Vec<usize>> {
+        let n = self.particles.len();
+        let positions: Vec<T::Pos> = self.particles.iter().map(|p| p.pos()).collect();
+
+        let mut assigned = vec![false; n];
+        let mut clusters = Vec::new();
+        for start in 0..n {
+            if assigned[start] {
+                continue;
+            }
+
+            assigned[start] = true;
+            let mut members = vec![start];
+            let mut next = 0;
+            while next < members.len() {
+                let i = members[next];
+                next += 1;
+                for j in 0..n {
+                    if !assigned[j] && T::pos_distance(&positions[i], &positions[j]) <= radius {
+                        assigned[j] = true;
+                        members.push(j);
+                    }
+                }
+            }
+            members.sort_unstable();
+            clusters.push(members);
+        }
+        clusters
+    }
+
+    /// Updates until `diversity()` falls below `diversity_threshold`, or `max_iters` times.
+    /// Returns the number of updates run.
+    ///
+    /// This detects a swarm which has collapsed to a point, even if its best eval is still
+    /// changing.
+    pub fn run_until_converged(&mut self, max_iters: usize, diversity_threshold: f64) -> usize {
+        for i in 0..max_iters {
+            self.update();
+            if self.diversity() < diversity_threshold {
+                #[cfg(feature = "log")]
+                debug!("iteration {}: converged below diversity {}",
+                       self.iteration,
+                       diversity_threshold);
+                return i + 1;
+            }
+        }
+        max_iters
+    }
+
+    /// Summarizes the run so far.
+    pub fn summary(&self) -> RunSummary<T::Eval> {
+        RunSummary {
+            best_eval: self.best_ref().1.clone(),
+            iterations: self.iteration,
+            eval_count: self.eval_count,
+            best_iteration: self.iteration.saturating_sub(self.stagnation),
+            diversity: self.diversity(),
+        }
+    }
+}
+
+impl<T, R> PSO<T, R>
+    where T: ComponentBounds + Clone,
+          R: Rng
+{
+    /// Confines the particles to the box between `lower` and `upper`: positions leaving it
+    /// after a move are brought back componentwise with `ComponentBounds::component_min` and
+    /// `ComponentBounds::component_max`, as chosen by `set_boundary_behavior`. Not set by
+    /// default.
+    ///
+    /// Each component of `lower` and `upper` bounds the same component of the positions, so
+    /// every dimension may have a different range.
+    pub fn set_bounds(&mut self, lower: T::Pos, upper: T::Pos) {
+        self.bounds = Some(Bounds::new::<T>(lower, upper));
+    }
+
+    /// Removes the search bounds.
+    pub fn unset_bounds(&mut self) {
+        self.bounds = None;
+    }
+
+    /// Returns the search bounds, if set.
+    pub fn bounds(&self) -> Option<(T::Pos, T::Pos)> {
+        self.bounds.as_ref().map(|b| (b.lower.clone(), b.upper.clone()))
+    }
+
+    /// Configures a box-constrained search in one call: sets the search bounds, a velocity limit
+    /// of `vmax_fraction` times their range, and the boundary behavior. This is
+    /// `set_bounds`, `set_velocity_limit_fraction` and `set_boundary_behavior` in this order;
+    /// `DEFAULT_VELOCITY_FRACTION` is the recommended fraction.
+    ///
+    /// Rejects a negative or NaN `vmax_fraction`, leaving the optimizer unchanged.
+    pub fn bounded(&mut self,
+                   lower: T::Pos,
+                   upper: T::Pos,
+                   vmax_fraction: f64,
+                   behavior: BoundaryBehavior)
+                   -> Result<(), MetaHeuristicsError> {
+        check_fraction(vmax_fraction)?;
+
+        self.set_bounds(lower, upper);
+        self.set_velocity_limit_fraction(vmax_fraction)?;
+        self.set_boundary_behavior(behavior);
+        Ok(())
+    }
+
+    /// Clamps each velocity component to `[-vmax, vmax]`, componentwise, after every velocity
+    /// update. Not set by default.
+    pub fn set_velocity_limit(&mut self, vmax: T::Pos) {
+        self.vel_limit = Some(Bounds::new::<T>(vmax.clone() * -1.0, vmax));
+    }
+
+    /// Sets the velocity limit of each dimension to `frac` times the range of the search bounds
+    /// in that dimension, 0.2 being a common choice. The limit is derived once, from the bounds
+    /// set at the time of the call.
+    ///
+    /// Returns `MetaHeuristicsError::BoundsNotSet` if no bounds have been set with `set_bounds`,
+    /// and rejects a negative or NaN `frac`.
+    pub fn set_velocity_limit_fraction(&mut self, frac: f64) -> Result<(), MetaHeuristicsError> {
+        let (lower, upper) = self.bounds().ok_or(MetaHeuristicsError::BoundsNotSet)?;
+        check_fraction(frac)?;
+
+        self.set_velocity_limit((upper - lower) * frac);
+        Ok(())
+    }
+
+    /// Removes the velocity limit.
+    pub fn unset_velocity_limit(&mut self) {
+        self.vel_limit = None;
+    }
+
+    /// Returns the velocity limit, if set.
+    pub fn velocity_limit(&self) -> Option<T::Pos> {
+        self.vel_limit.as_ref().map(|b| b.upper.clone())
+    }
+
+    /// Returns the componentwise minimum and maximum of the particles' positions, i.e. the
+    /// bounding box of the swarm.
+    pub fn position_bounds(&self) -> (T::Pos, T::Pos) {
+        let mut lower = self.particles[0].pos();
+        let mut upper = lower.clone();
+        for p in &self.particles[1..] {
+            let pos = p.pos();
+            lower = T::component_min(&lower, &pos);
+            upper = T::component_max(&upper, &pos);
+        }
+        (lower, upper)
+    }
+}
+
+impl<T, R> PSO<T, R>
+    where T: RandomVelocity + Clone,
+          R: Rng
+{
+    /// Replaces every particle's velocity with `RandomVelocity::random_vel(scale)`, drawn from the
+    /// optimizer's random number generator.
+    ///
+    /// Particles otherwise keep the velocity `new_random` gave them, so this decouples the
+    /// initial velocity distribution from the user's constructor. Call it before the first
+    /// `update` to widen early exploration; `kick_velocities` does the same later in the run.
+    pub fn randomize_velocities(&mut self, scale: f64) {
+        for p in &mut self.particles {
+            *p.vel_mut() = T::random_vel(scale, &mut self.rng);
+        }
+    }
+
+    /// Kicks a swarm whose velocities have collapsed back into motion, replacing every
+    /// particle's velocity with `RandomVelocity::random_vel(scale)` like `randomize_velocities`.
+    ///
+    /// This is a gentler diversification than `restart`: positions and personal bests are left
+    /// intact, so nothing found is lost.
+    pub fn kick_velocities(&mut self, scale: f64) {
+        self.randomize_velocities(scale);
+    }
+
+    /// Re-seeds the swarm around its current best, for intensifying the search in a promising
+    /// basin once the swarm has broadly converged, where `restart` would diversify it instead.
+    ///
+    /// The particle with the best current eval is kept. Every other particle is moved to the
+    /// swarm's best displaced by `RandomVelocity::random_vel(spread)`, with a zero velocity and a
+    /// fresh personal best, so its components lie within `spread` of the best's. The counters
+    /// reset as with `restart`.
+    pub fn restart_around_best(&mut self, spread: f64) {
+        let keep = self.best_index();
+        let others: Vec<usize> = (0..self.particles.len()).filter(|&i| i != keep).collect();
+        let center = self.best.0.clone();
+        let zero = center.vel() * 0.0;
+
+        let fresh: Vec<T> = others
+            .iter()
+            .map(|_| {
+                     let mut p = center.clone();
+                     let pos = center.pos() + T::random_vel(spread, &mut self.rng);
+                     *p.pos_mut() = self.confine(pos);
+                     *p.vel_mut() = zero.clone();
+                     p
+                 })
+            .collect();
+        let evals = T::eval_batch(&fresh);
+        self.eval_count += evals.len();
+
+        for ((&i, mut p), e) in others.iter().zip(fresh).zip(evals) {
+            *p.best_mut() = (p.pos(), e.clone());
+            self.particles[i] = p;
+            self.evals[i] = e;
+            self.stale[i] = 0;
+            self.improved[i] = false;
+        }
+
+        self.reselect_best();
+        self.recalc_neighborhood_best();
+        self.stagnation = 0;
+        self.best_seen = self.best.1.clone();
+        self.prev_best_seen = self.best.1.clone();
+        #[cfg(feature = "log")]
+        debug!("iteration {}: restarted around the best", self.iteration);
+    }
+
+    /// Makes each `update` end with a local search, for a memetic PSO: `steps` times, the
+    /// particle with the best current eval is perturbed by
+    /// `RandomVelocity::random_vel(step_size)`, and moved there if that evaluates better. Such
+    /// moves update its personal best, and so the swarm's best, like any other move. Not set by
+    /// default.
+    ///
+    /// Each step costs one evaluation. Rejects a `step_size` which is not positive.
+    pub fn set_local_search(&mut self,
+                            steps: usize,
+                            step_size: f64)
+                            -> Result<(), MetaHeuristicsError> {
+        if step_size <= 0.0 || step_size.is_nan() {
+            return Err(MetaHeuristicsError::InvalidParameter {
+                           name: "step_size",
+                           value: step_size,
+                       });
+        }
+
+        self.local_search = Some(LocalSearch {
+                                     steps,
+                                     step_size,
+                                     random_vel: T::random_vel::<R>,
+                                 });
+        self.mutation_step = None;
+        Ok(())
+    }
+
+    /// Disables the local search.
+    pub fn unset_local_search(&mut self) {
+        self.local_search = None;
+    }
+}
+
+impl<T, R> PSO<T, R>
+    where T: Gaussian + Clone,
+          R: Rng
+{
+    /// Sets whether `update` moves the particles as Kennedy's bare bones PSO, replacing the
+    /// movement of the mode: each new position is drawn by `Gaussian::gaussian_sample` around the
+    /// midpoint of the particle's personal best and its social attractor, with the componentwise
+    /// distance between the two as standard deviation. Disabled by default.
+    ///
+    /// Velocities are neither used nor updated, so the particles' `vel` is ignored, as are the
+    /// coefficients and the velocity limit.
+    pub fn set_bare_bones(&mut self, bare_bones: bool) {
+        self.bare_bones = if bare_bones {
+            Some(T::gaussian_sample::<R>)
+        } else {
+            None
+        };
+    }
+}
+
+impl<T, R> PSO<T, R>
+    where T: GrowDimension + Clone,
+          R: Rng
+{
+    /// Appends a dimension initialized to `initial_value` to every particle with
+    /// `GrowDimension::push_dimension`, for progressively widening the search from a
+    /// low-dimensional subspace.
+    ///
+    /// The particles are re-evaluated in the wider space and their personal bests restart from
+    /// their current positions, as evals from before the growth are not comparable. For the same
+    /// reason the archive is cleared and the stagnation count restarts. The bounds and the
+    /// velocity limit are left as they are, so set them again for the new dimension if used.
+    pub fn grow_dimension(&mut self, initial_value: f64) {
+        for p in &mut self.particles {
+            p.push_dimension(initial_value, &mut self.rng);
+        }
+        self.evals = T::eval_batch(&self.particles);
+        self.eval_count += self.evals.len();
+        for (p, e) in self.particles.iter_mut().zip(&self.evals) {
+            *p.best_mut() = (p.pos(), e.clone());
+        }
+        self.stale = vec![0; self.particles.len()];
+        self.improved = vec![false; self.particles.len()];
+
+        self.archive.clear();
+        self.reselect_best();
+        self.recalc_neighborhood_best();
+        self.best_seen = self.best.1.clone();
+        self.prev_best_seen = self.best.1.clone();
+        self.stagnation = 0;
+    }
+}
+
+impl<T, R> PSO<T, R>
+    where T: Axes + Clone,
+          R: Rng
+{
+    /// Enables the elitist learning of adaptive PSO: after each update, a copy of the swarm's
+    /// best is moved along one random dimension by a Gaussian step of standard deviation
+    /// `sigma`, and replaces the worst particle if it evaluates better, becoming the swarm's
+    /// best through `set_global_best`. This costs one evaluation per update.
+    ///
+    /// `sigma` shrinks linearly from `sigma_start` to `sigma_end` over the budget of
+    /// `set_linear_decay`, and stays at `sigma_start` without one. Unlike the local search,
+    /// which refines the best, the wide early steps let the incumbent jump out of its basin. The
+    /// dimensions are those of `Axes::axis`.
+    ///
+    /// # Errors
+    /// Returns `MetaHeuristicsError::InvalidParameter` if `sigma_start` or `sigma_end` is
+    /// negative or not finite.
+    pub fn set_elitist_learning(&mut self,
+                                sigma_start: f64,
+                                sigma_end: f64)
+                                -> Result<(), MetaHeuristicsError> {
+        for &(name, value) in &[("sigma_start", sigma_start), ("sigma_end", sigma_end)] {
+            if !(value >= 0.0 && value.is_finite()) {
+                return Err(MetaHeuristicsError::InvalidParameter { name, value });
+            }
+        }
+        self.elitist_learning = Some(ElitistLearning {
+                                         sigma_start,
+                                         sigma_end,
+                                         axis: T::axis,
+                                     });
+        Ok(())
+    }
+
+    pub fn unset_elitist_learning(&mut self) {
+        self.elitist_learning = None;
+    }
+}
+
+impl<T, R> Optimizer for PSO<T, R>
+    where T: Particle + Clone,
+          R: Rng
+{
+    type Solution = T;
+    type Eval = T::Eval;
+
+    fn step(&mut self) {
+        self.update();
+    }
+
+    fn best(&self) -> (T, T::Eval) {
+        PSO::best(self)
+    }
+
+    fn replace_worst(&mut self, solution: T) {
+        let worst = self.worst_index();
+        self.set_particle(worst, solution);
+    }
+
+    fn population(&self) -> Vec<T> {
+        self.particles.clone()
+    }
+
+    fn reset(&mut self) {
+        PSO::reset(self);
+    }
+
+    fn eval_count(&self) -> usize {
+        PSO::eval_count(self)
+    }
+
+    /// Counts the evaluations of the swarm, twice over with `set_time_varying` and once per
+    /// sample with `set_resampling`, plus the local search steps, the teleported particle and
+    /// the perturbed best of elitist learning.
+    fn step_evals(&self) -> usize {
+        let samples = if self.time_varying {
+            1
+        } else {
+            self.resampling()
+        };
+        let swarm = if self.time_varying {
+            2 * self.particles.len()
+        } else {
+            samples * self.particles.len()
+        };
+        swarm + samples * self.local_search.as_ref().map_or(0, |search| search.steps) +
+        self.teleport.map_or(0, |_| 1) + self.elitist_learning.as_ref().map_or(0, |_| 1)
+    }
+
+    /// Compares by the comparator set with `set_comparator`, if any.
+    fn is_better(&self, a: &T::Eval, b: &T::Eval) -> bool {
+        is_better(&self.comparator, a, b)
+    }
+}
+
+/// Iterates over the particles, like `particles().iter()`.
+impl<'a, T, R> IntoIterator for &'a PSO<T, R>
+    where T: Particle,
+          R: Rng
+{
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.particles.iter()
+    }
+}
+
+/// Draws a symmetric Lévy-stable step length with stability index `exponent` using Mantegna's
+/// algorithm.
+pub fn levy_sample<R: Rng>(exponent: f64, rng: &mut R) -> f64 {
+    use std::f64::consts::PI;
+
+    let sigma = (gamma(1.0 + exponent) * (PI * exponent / 2.0).sin() /
+                 (gamma((1.0 + exponent) / 2.0) * exponent * 2f64.powf((exponent - 1.0) / 2.0)))
+            .powf(1.0 / exponent);
+    let StandardNormal(u) = rng.gen::<StandardNormal>();
+    let StandardNormal(v) = rng.gen::<StandardNormal>();
+    u * sigma / v.abs().powf(1.0 / exponent)
+}
+
+/// Lanczos approximation of the gamma function.
+fn gamma(x: f64) -> f64 {
+    use std::f64::consts::PI;
+
+    const G: f64 = 7.0;
+    const COEFFS: [f64; 9] = [0.999_999_999_999_809_9,
+                              676.520_368_121_885_1,
+                              -1_259.139_216_722_402_8,
+                              771.323_428_777_653_1,
+                              -176.615_029_162_140_6,
+                              12.507_343_278_686_905,
+                              -0.138_571_095_265_720_12,
+                              9.984_369_578_019_572e-6,
+                              1.505_632_735_149_311_6e-7];
+
+    if x < 0.5 {
+        PI / ((PI * x).sin() * gamma(1.0 - x))
+    } else {
+        let x = x - 1.0;
+        let t = x + G + 0.5;
+        let mut a = COEFFS[0];
+        for (i, &c) in COEFFS.iter().enumerate().skip(1) {
+            a += c / (x + i as f64);
+        }
+        (2.0 * PI).sqrt() * t.powf(x + 0.5) * (-t).exp() * a
+    }
+}
+
+/// Rejects a negative or NaN fraction of the search bounds' range.
+fn check_fraction(frac: f64) -> Result<(), MetaHeuristicsError> {
+    if frac < 0.0 || frac.is_nan() {
+        return Err(MetaHeuristicsError::InvalidParameter {
+                       name: "frac",
+                       value: frac,
+                   });
+    }
+    Ok(())
+}
+
+/// Indices of the particle `i` and its `k` neighbors on each side in a ring of `n` particles.
+fn ring_neighbors(i: usize, k: usize, n: usize) -> impl Iterator<Item = usize> {
+    let k = cmp::min(k, n / 2);
+    (0..2 * k + 1).map(move |d| (i + n - k + d) % n)
+}
+
+/// Orders two evals by `comparator` if set, treating incomparable evals as equal otherwise.
+fn compare<E: PartialOrd>(comparator: &Option<Comparator<E>>, a: &E, b: &E) -> cmp::Ordering {
+    match *comparator {
+        Some(ref comparator) => comparator(a, b),
+        None => a.partial_cmp(b).unwrap_or(cmp::Ordering::Equal),
+    }
+}
+
+fn is_better<E: PartialOrd>(comparator: &Option<Comparator<E>>, a: &E, b: &E) -> bool {
+    compare(comparator, a, b) == cmp::Ordering::Greater
+}
+
+/// How much `new` improves on `old`: the distance between their values, negative if `new` is
+/// worse by `comparator`, so that a comparator preferring smaller values gains from decreases.
+fn gain<E: PartialOrd>(comparator: &Option<Comparator<E>>,
+                       to_f64: fn(&E) -> f64,
+                       new: &E,
+                       old: &E)
+                       -> f64 {
+    let distance = (to_f64(new) - to_f64(old)).abs();
+    match compare(comparator, new, old) {
+        cmp::Ordering::Greater => distance,
+        cmp::Ordering::Less => -distance,
+        cmp::Ordering::Equal => 0.0,
+    }
+}
+
+fn rand_01<R: Rng>(rng: &mut R) -> f64 {
+    use rand::Closed01;
+
+    let Closed01(val) = rng.gen::<Closed01<_>>();
+    val
+}
+
+/// Summarizes the swarm configuration and progress rather than dumping every particle.
+impl<T, R> fmt::Debug for PSO<T, R>
+    where T: Particle,
+          T::Eval: fmt::Debug,
+          R: Rng
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PSO")
+            .field("particles", &self.particles.len())
+            .field("inetia", &self.inetia)
+            .field("c_local", &self.c_local)
+            .field("c_global", &self.c_global)
+            .field("iteration", &self.iteration)
+            .field("eval_count", &self.eval_count)
+            .field("best_eval", &self.best.1)
+            .finish()
     }
 }
@@ -0,0 +1,442 @@
+//! Simulated Annealing.
+//!
+//! # Example
+//! ```
+//! extern crate meta_heuristics;
+//! extern crate rand;
+//!
+//! use meta_heuristics::{sa, Candidate, RandomCandidate};
+//! use rand::{Closed01, Rng};
+//!
+//! #[derive(Clone, Copy)]
+//! struct State {
+//!     pos: f64,
+//! }
+//!
+//! fn eval_func(x: f64) -> f64 {
+//!     1.0 - ((x - 3.0) * x + 2.0) * x * x
+//! }
+//!
+//! impl Candidate for State {
+//!     type Pos = f64;
+//!     type Eval = f64;
+//!
+//!     fn eval(&self) -> f64 {
+//!         eval_func(self.pos)
+//!     }
+//!     fn pos(&self) -> f64 {
+//!         self.pos
+//!     }
+//!     fn pos_mut(&mut self) -> &mut f64 {
+//!         &mut self.pos
+//!     }
+//! }
+//!
+//! impl RandomCandidate for State {
+//!     fn new_random() -> Self {
+//!         let Closed01(x) = rand::random::<Closed01<f64>>();
+//!         State { pos: 4.0 * x - 1.0 }
+//!     }
+//! }
+//!
+//! impl sa::Neighbor for State {
+//!     fn neighbor<R: Rng>(&self, rng: &mut R) -> Self {
+//!         let Closed01(d) = rng.gen::<Closed01<f64>>();
+//!         State { pos: self.pos + d - 0.5 }
+//!     }
+//! }
+//!
+//! fn main() {
+//!     let schedule = sa::Geometric { initial: 1.0, alpha: 0.99 };
+//!     let mut sa: sa::SimulatedAnnealing<State, _> = sa::SimulatedAnnealing::new(schedule);
+//!
+//!     for _ in 0..1000 {
+//!         sa.step();
+//!     }
+//!     assert!(sa.best().1 > 1.5);
+//!     assert_eq!(sa.eval_count(), 1 + 1000);
+//!
+//!     sa.reheat();
+//!     assert_eq!(sa.iteration(), 0);
+//!     assert_eq!(sa.current().1, sa.best().1);
+//! }
+//! ```
+
+use rand::{self, Closed01, Rng, ThreadRng};
+
+use {Candidate, Optimizer, RandomCandidate};
+
+pub trait Neighbor: Candidate {
+    /// Returns a random candidate near `self`, the move proposed at each step.
+    fn neighbor<R: Rng>(&self, rng: &mut R) -> Self where Self: Sized;
+}
+
+/// The temperature of each step of `SimulatedAnnealing`.
+///
+/// # Example
+/// ```
+/// use meta_heuristics::sa::{self, CoolingSchedule};
+///
+/// let geometric = sa::Geometric { initial: 10.0, alpha: 0.5 };
+/// assert_eq!(geometric.temperature(0), 10.0);
+/// assert_eq!(geometric.temperature(2), 2.5);
+///
+/// let linear = sa::Linear { initial: 10.0, rate: 2.0 };
+/// assert_eq!(linear.temperature(3), 4.0);
+/// assert_eq!(linear.temperature(10), 0.0);
+///
+/// let logarithmic = sa::Logarithmic { scale: 2.0 };
+/// assert_eq!(logarithmic.temperature(0), 2.0 / 2f64.ln());
+/// assert_eq!(logarithmic.temperature(8), 2.0 / 10f64.ln());
+///
+/// let custom = sa::FnSchedule(|iter| 1.0 / (iter + 1) as f64);
+/// assert_eq!(custom.temperature(3), 0.25);
+///
+/// // Reheats to the initial temperature after 2 steps without improvement.
+/// let mut adaptive = sa::Adaptive::new(8.0, 0.5, 2);
+/// assert_eq!(adaptive.temperature(2), 2.0);
+/// adaptive.observe(2, false);
+/// adaptive.observe(3, false);
+/// assert_eq!(adaptive.temperature(4), 8.0);
+/// assert_eq!(adaptive.temperature(5), 4.0);
+/// ```
+pub trait CoolingSchedule {
+    /// Returns the temperature of the step at iteration `iter`, counting from 0.
+    fn temperature(&self, iter: usize) -> f64;
+
+    /// Called after the step at iteration `iter` with whether it improved the best solution,
+    /// so that adaptive schedules can react. The default does nothing.
+    fn observe(&mut self, iter: usize, improved: bool) {
+        let _ = (iter, improved);
+    }
+
+    /// Called by `SimulatedAnnealing::reheat` as the iteration restarts from 0, so that
+    /// adaptive schedules can forget what they observed. The default does nothing.
+    fn restart(&mut self) {}
+}
+
+/// `initial * alpha^iter`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Geometric {
+    pub initial: f64,
+    pub alpha: f64,
+}
+
+impl CoolingSchedule for Geometric {
+    fn temperature(&self, iter: usize) -> f64 {
+        self.initial * self.alpha.powi(iter as i32)
+    }
+}
+
+/// `initial - rate * iter`, stopping at 0.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Linear {
+    pub initial: f64,
+    pub rate: f64,
+}
+
+impl CoolingSchedule for Linear {
+    fn temperature(&self, iter: usize) -> f64 {
+        (self.initial - self.rate * iter as f64).max(0.0)
+    }
+}
+
+/// `scale / ln(iter + 2)`, the slow schedule of the convergence proofs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Logarithmic {
+    pub scale: f64,
+}
+
+impl CoolingSchedule for Logarithmic {
+    fn temperature(&self, iter: usize) -> f64 {
+        self.scale / (iter as f64 + 2.0).ln()
+    }
+}
+
+/// A geometric schedule which reheats to its initial temperature after `patience` consecutive
+/// steps without improvement.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Adaptive {
+    initial: f64,
+    alpha: f64,
+    patience: usize,
+    stagnation: usize,
+    start: usize,
+}
+
+impl Adaptive {
+    pub fn new(initial: f64, alpha: f64, patience: usize) -> Self {
+        Adaptive {
+            initial,
+            alpha,
+            patience,
+            stagnation: 0,
+            start: 0,
+        }
+    }
+}
+
+impl CoolingSchedule for Adaptive {
+    fn temperature(&self, iter: usize) -> f64 {
+        self.initial * self.alpha.powi(iter.saturating_sub(self.start) as i32)
+    }
+
+    fn observe(&mut self, iter: usize, improved: bool) {
+        if improved {
+            self.stagnation = 0;
+        } else {
+            self.stagnation += 1;
+            if self.stagnation >= self.patience {
+                self.stagnation = 0;
+                self.start = iter + 1;
+            }
+        }
+    }
+
+    fn restart(&mut self) {
+        self.stagnation = 0;
+        self.start = 0;
+    }
+}
+
+/// A schedule computed by a closure of the iteration.
+pub struct FnSchedule<F: Fn(usize) -> f64>(pub F);
+
+impl<F: Fn(usize) -> f64> CoolingSchedule for FnSchedule<F> {
+    fn temperature(&self, iter: usize) -> f64 {
+        (self.0)(iter)
+    }
+}
+
+/// Simulated Annealing over a single current solution.
+///
+/// Each `step` proposes a `Neighbor::neighbor` of the current solution and accepts it if it is
+/// not worse, or otherwise with the Metropolis probability `exp((e_new - e_current) / t)` at the
+/// schedule's temperature `t`.
+pub struct SimulatedAnnealing<T: Neighbor + Clone, S: CoolingSchedule, R: Rng = ThreadRng> {
+    current: (T, T::Eval),
+    best: (T, T::Eval),
+    schedule: S,
+    time_varying: bool,
+    eval_count: usize,
+    iteration: usize,
+    // Steps since construction, which unlike `iteration` `reheat` does not restart.
+    steps: usize,
+    rng: R,
+}
+
+impl<T, S> SimulatedAnnealing<T, S, ThreadRng>
+    where T: RandomCandidate + Neighbor + Clone,
+          T::Eval: Into<f64>,
+          S: CoolingSchedule
+{
+    /// Starts from `T::new_random()`, cooling with `schedule`.
+    pub fn new(schedule: S) -> Self {
+        Self::new_with_rng(T::new_random(), schedule, rand::thread_rng())
+    }
+}
+
+impl<T, S, R> SimulatedAnnealing<T, S, R>
+    where T: Neighbor + Clone,
+          T::Eval: Into<f64>,
+          S: CoolingSchedule,
+          R: Rng
+{
+    /// Starts from `initial`, cooling with `schedule` and drawing the moves from `rng`.
+    pub fn new_with_rng(initial: T, schedule: S, rng: R) -> Self {
+        let e = initial.eval();
+        Self {
+            current: (initial.clone(), e.clone()),
+            best: (initial, e),
+            schedule,
+            time_varying: false,
+            eval_count: 1,
+            iteration: 0,
+            steps: 0,
+            rng,
+        }
+    }
+
+    pub fn step(&mut self) {
+        let temperature = self.schedule.temperature(self.iteration);
+        let candidate = self.current.0.neighbor(&mut self.rng);
+        let e = if self.time_varying {
+            let steps = self.steps + 1;
+            // The current and best solutions were evaluated at an earlier step.
+            self.current.1 = self.current.0.eval_at(steps);
+            self.best.1 = self.best.0.eval_at(steps);
+            self.eval_count += 2;
+            candidate.eval_at(steps)
+        } else {
+            candidate.eval()
+        };
+        self.eval_count += 1;
+
+        let accept = e >= self.current.1 || {
+            let delta = e.clone().into() - self.current.1.clone().into();
+            let Closed01(r) = self.rng.gen::<Closed01<f64>>();
+            temperature > 0.0 && r < (delta / temperature).exp()
+        };
+
+        let improved = e > self.best.1;
+        if improved {
+            self.best = (candidate.clone(), e.clone());
+        }
+        if accept {
+            self.current = (candidate, e);
+        }
+
+        self.schedule.observe(self.iteration, improved);
+        self.iteration += 1;
+        self.steps += 1;
+    }
+
+    /// Sets whether the objective changes over time, as `Candidate::eval_at` describes.
+    /// Disabled by default.
+    ///
+    /// When enabled, each `step` evaluates the neighbor with `eval_at` the number of steps made
+    /// since construction, counting this one, and re-evaluates the current and best solutions
+    /// at the same iteration before comparing them, which triples the evaluations per step.
+    /// Unlike `iteration()`, this count is not restarted by `reheat`, so the objective's
+    /// schedule keeps running across reheats. The initial solution is evaluated with `eval`,
+    /// which should agree with `eval_at(0)`.
+    ///
+    /// # Example
+    /// ```
+    /// # extern crate meta_heuristics;
+    /// # extern crate rand;
+    /// # use meta_heuristics::{sa, seeded_rng, Candidate};
+    /// # use rand::{Closed01, Rng};
+    /// // A peak drifting from 0 by 0.01 per step.
+    /// fn eval_func(x: f64, step: usize) -> f64 {
+    ///     -(x - 0.01 * step as f64).powi(2)
+    /// }
+    ///
+    /// #[derive(Clone, Copy)]
+    /// struct State {
+    ///     pos: f64,
+    /// }
+    ///
+    /// impl Candidate for State {
+    ///     type Pos = f64;
+    ///     type Eval = f64;
+    ///
+    ///     fn eval(&self) -> f64 {
+    ///         eval_func(self.pos, 0)
+    ///     }
+    ///     fn eval_at(&self, step: usize) -> f64 {
+    ///         eval_func(self.pos, step)
+    ///     }
+    /// #     fn pos(&self) -> f64 { self.pos }
+    /// #     fn pos_mut(&mut self) -> &mut f64 { &mut self.pos }
+    /// }
+    /// # impl sa::Neighbor for State {
+    /// #     fn neighbor<R: Rng>(&self, rng: &mut R) -> Self {
+    /// #         let Closed01(d) = rng.gen::<Closed01<f64>>();
+    /// #         State { pos: self.pos + d - 0.5 }
+    /// #     }
+    /// # }
+    ///
+    /// # fn main() {
+    /// let schedule = sa::Geometric { initial: 0.1, alpha: 0.99 };
+    /// let mut sa =
+    ///     sa::SimulatedAnnealing::new_with_rng(State { pos: 0.0 }, schedule, seeded_rng(42));
+    /// sa.set_time_varying(true);
+    /// for _ in 0..500 {
+    ///     sa.step();
+    /// }
+    ///
+    /// // The best solution follows the peak to 5, with its eval as of the last step.
+    /// let (best, e) = sa.best();
+    /// assert!((best.pos - 5.0).abs() < 0.2);
+    /// assert_eq!(e, best.eval_at(500));
+    /// assert_eq!(sa.eval_count(), 1 + 3 * 500);
+    /// # }
+    /// ```
+    pub fn set_time_varying(&mut self, time_varying: bool) {
+        self.time_varying = time_varying;
+    }
+
+    /// Returns the current solution and its eval.
+    pub fn current(&self) -> &(T, T::Eval) {
+        &self.current
+    }
+
+    /// Returns the best solution found so far and its eval.
+    pub fn best(&self) -> (T, T::Eval) {
+        self.best.clone()
+    }
+
+    pub fn schedule(&self) -> &S {
+        &self.schedule
+    }
+
+    /// Reheats the schedule, continuing from the best solution found so far at the initial
+    /// temperature. The evaluation count keeps running, as the best solution is not reevaluated.
+    pub fn reheat(&mut self) {
+        self.current = self.best.clone();
+        self.schedule.restart();
+        self.iteration = 0;
+    }
+
+    /// Returns the number of objective evaluations made so far, one per `step` besides that of
+    /// the initial solution, or three with `set_time_varying`.
+    pub fn eval_count(&self) -> usize {
+        self.eval_count
+    }
+
+    /// Returns the number of `step` calls made so far.
+    pub fn iteration(&self) -> usize {
+        self.iteration
+    }
+}
+
+impl<T, S, R> Optimizer for SimulatedAnnealing<T, S, R>
+    where T: Neighbor + Clone,
+          T::Eval: Into<f64>,
+          S: CoolingSchedule,
+          R: Rng
+{
+    type Solution = T;
+    type Eval = T::Eval;
+
+    fn step(&mut self) {
+        SimulatedAnnealing::step(self);
+    }
+
+    fn best(&self) -> (T, T::Eval) {
+        SimulatedAnnealing::best(self)
+    }
+
+    /// Continues the search from `solution`, as the population is the single current solution.
+    fn replace_worst(&mut self, solution: T) {
+        let e = solution.eval();
+        self.eval_count += 1;
+        if e > self.best.1 {
+            self.best = (solution.clone(), e.clone());
+        }
+        self.current = (solution, e);
+    }
+
+    /// Returns the current solution, which is the whole population.
+    fn population(&self) -> Vec<T> {
+        vec![self.current.0.clone()]
+    }
+
+    /// Reheats the schedule by `reheat`, as the search is a single trajectory.
+    fn reset(&mut self) {
+        self.reheat();
+    }
+
+    fn eval_count(&self) -> usize {
+        SimulatedAnnealing::eval_count(self)
+    }
+
+    fn step_evals(&self) -> usize {
+        if self.time_varying {
+            3
+        } else {
+            1
+        }
+    }
+}
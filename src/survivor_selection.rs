@@ -0,0 +1,108 @@
+//! Strategies choosing which individuals of a population and its offspring survive into the
+//! next generation.
+//!
+//! The strategies work on scored individuals, pairs of an individual and its eval, so that
+//! nothing is evaluated again. Like the optimizers, they keep the individuals of higher evals.
+//!
+//! # Example
+//! ```
+//! use meta_heuristics::survivor_selection::Strategy;
+//!
+//! let parents = vec![("a", 3.0), ("b", 1.0), ("c", 2.0)];
+//! let offspring = vec![("d", 4.0), ("e", 0.0)];
+//!
+//! let next = Strategy::Elitist.select(parents.clone(), offspring.clone());
+//! assert_eq!(next, vec![("d", 4.0), ("a", 3.0), ("c", 2.0)]);
+//!
+//! let next = Strategy::Generational.select(parents.clone(), offspring.clone());
+//! assert_eq!(next, vec![("d", 4.0), ("e", 0.0)]);
+//!
+//! let next = Strategy::SteadyState { replace: 1 }.select(parents, offspring);
+//! assert_eq!(next, vec![("a", 3.0), ("d", 4.0), ("c", 2.0)]);
+//! ```
+
+use std::cmp;
+
+/// A survivor selection strategy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Strategy {
+    /// Keeps the best individuals among the parents and the offspring together, as many as there
+    /// were parents, ordered from the best. Equally good individuals keep their order, parents
+    /// first.
+    ///
+    /// # Example
+    /// ```
+    /// use meta_heuristics::survivor_selection::Strategy;
+    ///
+    /// let parents = vec![(0, 5), (1, 2), (2, 7), (3, 2)];
+    /// let offspring = vec![(4, 2), (5, 9), (6, 1)];
+    ///
+    /// let next = Strategy::Elitist.select(parents, offspring);
+    /// assert_eq!(next, vec![(5, 9), (2, 7), (0, 5), (1, 2)]);
+    /// ```
+    Elitist,
+    /// Replaces the parents by the offspring wholesale, however good either are.
+    Generational,
+    /// Replaces the `replace` worst parents by the `replace` best offspring in their places, the
+    /// worst parent by the best offspring. Fewer are replaced if there are fewer parents or
+    /// offspring.
+    /// An offspring takes the place of a parent even if it is worse.
+    ///
+    /// # Example
+    /// ```
+    /// use meta_heuristics::survivor_selection::Strategy;
+    ///
+    /// let parents = vec![(0, 5), (1, 2), (2, 7), (3, 3)];
+    /// let offspring = vec![(4, 1), (5, 9), (6, 4)];
+    ///
+    /// let next = Strategy::SteadyState { replace: 2 }.select(parents.clone(), offspring.clone());
+    /// assert_eq!(next, vec![(0, 5), (5, 9), (2, 7), (6, 4)]);
+    ///
+    /// let next = Strategy::SteadyState { replace: 10 }.select(parents, offspring);
+    /// assert_eq!(next, vec![(4, 1), (5, 9), (2, 7), (6, 4)]);
+    /// ```
+    SteadyState { replace: usize },
+}
+
+impl Strategy {
+    /// Returns the next generation of `parents` given their `offspring`, ordering evals by
+    /// `PartialOrd`. An eval incomparable to another, such as NaN, counts as equal to it.
+    pub fn select<T, E: PartialOrd>(&self,
+                                    parents: Vec<(T, E)>,
+                                    offspring: Vec<(T, E)>)
+                                    -> Vec<(T, E)> {
+        self.select_by(parents,
+                       offspring,
+                       |a, b| a.partial_cmp(b).unwrap_or(cmp::Ordering::Equal))
+    }
+
+    /// Returns the next generation like `select`, but ordering the evals by `comparator`.
+    pub fn select_by<T, E, F>(&self,
+                              mut parents: Vec<(T, E)>,
+                              mut offspring: Vec<(T, E)>,
+                              comparator: F)
+                              -> Vec<(T, E)>
+        where F: Fn(&E, &E) -> cmp::Ordering
+    {
+        match *self {
+            Strategy::Elitist => {
+                let size = parents.len();
+                parents.append(&mut offspring);
+                parents.sort_by(|a, b| comparator(&b.1, &a.1));
+                parents.truncate(size);
+                parents
+            }
+            Strategy::Generational => offspring,
+            Strategy::SteadyState { replace } => {
+                // The best offspring replaces the worst parent, and so on.
+                let mut worst: Vec<usize> = (0..parents.len()).collect();
+                worst.sort_by(|&a, &b| comparator(&parents[a].1, &parents[b].1));
+                offspring.sort_by(|a, b| comparator(&b.1, &a.1));
+                for (i, o) in worst.into_iter().zip(offspring).take(replace) {
+                    parents[i] = o;
+                }
+                parents
+            }
+        }
+    }
+}
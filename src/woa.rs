@@ -0,0 +1,460 @@
+//! Whale Optimization Algorithm.
+//!
+//! # Example
+//! ```
+//! extern crate meta_heuristics;
+//! extern crate rand;
+//!
+//! use meta_heuristics::{woa, Candidate, RandomCandidate};
+//!
+//! #[derive(Clone, Copy)]
+//! struct Whale {
+//!     pos: f64,
+//! }
+//!
+//! // The Sphere function, negated to be maximized.
+//! fn sphere(x: f64) -> f64 {
+//!     -x * x
+//! }
+//!
+//! impl Candidate for Whale {
+//!     type Pos = f64;
+//!     type Eval = f64;
+//!
+//!     fn eval(&self) -> f64 {
+//!         sphere(self.pos)
+//!     }
+//!     fn pos(&self) -> f64 {
+//!         self.pos
+//!     }
+//!     fn pos_mut(&mut self) -> &mut f64 {
+//!         &mut self.pos
+//!     }
+//! }
+//!
+//! impl RandomCandidate for Whale {
+//!     fn new_random() -> Self {
+//!         use rand::{random, Closed01};
+//!
+//!         let Closed01(x) = random::<Closed01<f64>>();
+//!         Self { pos: 10.0 * x - 5.0 }
+//!     }
+//! }
+//!
+//! fn main() {
+//!     let mut woa: woa::WhaleOptimization<Whale> = woa::WhaleOptimization::new(10, 100);
+//!
+//!     for _ in 0..100 {
+//!         woa.update();
+//!     }
+//!
+//!     assert_eq!(woa.whales().len(), 10);
+//!     assert_eq!(woa.eval_count(), 10 + 100 * 10);
+//!     assert!(woa.best().1 > -1e-6);
+//!
+//!     woa.reset();
+//!     assert_eq!((woa.iteration(), woa.eval_count()), (0, 10));
+//! }
+//! ```
+
+use std::{f64, fmt};
+use rand::{self, Closed01, Rng, ThreadRng};
+
+use {Candidate, Optimizer, RandomCandidate};
+
+/// Componentwise operations on positions, required by `WhaleOptimization::set_componentwise`.
+pub trait Componentwise: Candidate {
+    /// Returns `target - A |C target - pos|` with the componentwise product and absolute value,
+    /// drawing each component of `A` uniformly from `[-a, a]` and of `C` from `[0, 2]`. Each
+    /// component of `target` is taken from `leader` if the component of `A` is less than 1 in
+    /// magnitude, and from `random`, the position of a random whale, otherwise.
+    fn encircle<R: Rng>(pos: &Self::Pos,
+                        leader: &Self::Pos,
+                        random: &Self::Pos,
+                        a: f64,
+                        rng: &mut R)
+                        -> Self::Pos;
+
+    /// Returns the componentwise absolute value of `pos`.
+    fn abs(pos: &Self::Pos) -> Self::Pos;
+}
+
+/// The moves of `Componentwise` used by the published update.
+struct ComponentwiseMoves<P, R> {
+    encircle: fn(&P, &P, &P, f64, &mut R) -> P,
+    abs: fn(&P) -> P,
+}
+
+/// The Whale Optimization Algorithm of Mirjalili and Lewis (2016).
+///
+/// Each `update` moves every whale either by encircling the best solution found so far, or by
+/// a logarithmic spiral around it, chosen with equal probability. While the coefficient `a`
+/// decreases linearly from 2 to 0 over `max_iters` updates, encircling whales may instead
+/// search around a random whale, which makes the early iterations explore.
+///
+/// Positions are only known to support addition, subtraction and scaling, so by default the
+/// random coefficients are drawn per whale rather than per dimension, and the distances to the
+/// leader are not taken componentwise absolute: a whale encircles to `X* - A (C X* - X)`
+/// rather than to `X* - A |C X* - X|`. `set_componentwise` enables the published update for
+/// positions implementing `Componentwise`.
+pub struct WhaleOptimization<T: Candidate + Clone, R: Rng = ThreadRng> {
+    whales: Vec<(T, T::Eval)>,
+    best: (T, T::Eval),
+    max_iters: usize,
+    spiral: f64,
+    componentwise: Option<ComponentwiseMoves<T::Pos, R>>,
+    time_varying: bool,
+    eval_count: usize,
+    iteration: usize,
+    rng: R,
+}
+
+impl<T: RandomCandidate + Clone> WhaleOptimization<T, ThreadRng> {
+    /// Creates `whales_num` random whales, with `a` reaching 0 after `max_iters` updates.
+    pub fn new(whales_num: usize, max_iters: usize) -> Self {
+        Self::new_with_rng(whales_num, max_iters, rand::thread_rng())
+    }
+}
+
+impl<T, R> WhaleOptimization<T, R>
+    where T: RandomCandidate + Clone,
+          R: Rng
+{
+    /// Creates the algorithm like `new`, but drawing its randomness from `rng`.
+    ///
+    /// # Panics
+    /// Panics if `whales_num` is 0.
+    pub fn new_with_rng(whales_num: usize, max_iters: usize, rng: R) -> Self {
+        assert!(whales_num > 0, "no whales");
+
+        let whales = Self::random_whales(whales_num);
+        let best = whales[Self::best_index(&whales)].clone();
+
+        Self {
+            whales,
+            best,
+            max_iters,
+            spiral: 1.0,
+            componentwise: None,
+            time_varying: false,
+            eval_count: whales_num,
+            iteration: 0,
+            rng,
+        }
+    }
+
+    /// Replaces every whale by a new random one and restarts the schedule of `a`, keeping the
+    /// spiral shape and the random number generator.
+    pub fn reset(&mut self) {
+        self.whales = Self::random_whales(self.whales.len());
+        self.best = self.whales[Self::best_index(&self.whales)].clone();
+        self.eval_count = self.whales.len();
+        self.iteration = 0;
+    }
+
+    pub fn update(&mut self) {
+        let iteration = self.iteration + 1;
+        let a = 2.0 * (1.0 - self.iteration as f64 / self.max_iters.max(1) as f64).max(0.0);
+        let leader = self.best.0.pos();
+
+        for i in 0..self.whales.len() {
+            let pos = self.whales[i].0.pos();
+            let encircling = rand_01(&mut self.rng) < 0.5;
+            let new_pos = match self.componentwise {
+                Some(ComponentwiseMoves { encircle, .. }) if encircling => {
+                    let j = self.rng.gen_range(0, self.whales.len());
+                    let random = self.whales[j].0.pos();
+                    encircle(&pos, &leader, &random, a, &mut self.rng)
+                }
+                None if encircling => {
+                    let coeff_a = a * (2.0 * rand_01(&mut self.rng) - 1.0);
+                    let coeff_c = 2.0 * rand_01(&mut self.rng);
+                    let target = if coeff_a.abs() < 1.0 {
+                        leader.clone()
+                    } else {
+                        let j = self.rng.gen_range(0, self.whales.len());
+                        self.whales[j].0.pos()
+                    };
+                    target.clone() - (target * coeff_c - pos) * coeff_a
+                }
+                _ => {
+                    let l = 2.0 * rand_01(&mut self.rng) - 1.0;
+                    let scale = (self.spiral * l).exp() * (2.0 * f64::consts::PI * l).cos();
+                    let distance = match self.componentwise {
+                        Some(ComponentwiseMoves { abs, .. }) => abs(&(leader.clone() - pos)),
+                        None => leader.clone() - pos,
+                    };
+                    distance * scale + leader.clone()
+                }
+            };
+
+            let whale = &mut self.whales[i];
+            *whale.0.pos_mut() = new_pos;
+            whale.1 = if self.time_varying {
+                whale.0.eval_at(iteration)
+            } else {
+                whale.0.eval()
+            };
+        }
+        self.eval_count += self.whales.len();
+
+        if self.time_varying {
+            // The best so far was evaluated at an earlier iteration.
+            self.best.1 = self.best.0.eval_at(iteration);
+            self.eval_count += 1;
+        }
+
+        let best = Self::best_index(&self.whales);
+        if self.whales[best].1 > self.best.1 {
+            self.best = self.whales[best].clone();
+        }
+        self.iteration += 1;
+    }
+
+    /// Sets the constant `b` shaping the logarithmic spiral. Defaults to 1.
+    pub fn set_spiral(&mut self, spiral: f64) {
+        self.spiral = spiral;
+    }
+
+    /// Sets whether the objective changes over time, as `Candidate::eval_at` describes.
+    /// Disabled by default.
+    ///
+    /// When enabled, each `update` evaluates the moved whales with `eval_at` the value
+    /// `iteration()` takes after the update, and re-evaluates the best so far at the same
+    /// iteration before comparing them, at the cost of one more evaluation per update. The
+    /// initial whales are evaluated with `eval`, which should agree with `eval_at(0)`.
+    ///
+    /// # Example
+    /// ```
+    /// # extern crate meta_heuristics;
+    /// # extern crate rand;
+    /// # use meta_heuristics::{woa, seeded_rng, Candidate, RandomCandidate};
+    /// # use rand::Closed01;
+    /// // A peak drifting from 0 by 0.01 per iteration.
+    /// fn eval_func(x: f64, iteration: usize) -> f64 {
+    ///     -(x - 0.01 * iteration as f64).powi(2)
+    /// }
+    ///
+    /// #[derive(Clone, Copy)]
+    /// struct Whale {
+    ///     pos: f64,
+    /// }
+    ///
+    /// impl Candidate for Whale {
+    ///     type Pos = f64;
+    ///     type Eval = f64;
+    ///
+    ///     fn eval(&self) -> f64 {
+    ///         eval_func(self.pos, 0)
+    ///     }
+    ///     fn eval_at(&self, iteration: usize) -> f64 {
+    ///         eval_func(self.pos, iteration)
+    ///     }
+    /// #     fn pos(&self) -> f64 { self.pos }
+    /// #     fn pos_mut(&mut self) -> &mut f64 { &mut self.pos }
+    /// }
+    /// # impl RandomCandidate for Whale {
+    /// #     fn new_random() -> Self {
+    /// #         Self { pos: 4.0 * rand::random::<Closed01<f64>>().0 - 2.0 }
+    /// #     }
+    /// # }
+    ///
+    /// # fn main() {
+    /// let mut woa = woa::WhaleOptimization::<Whale, _>::new_with_rng(10, 200, seeded_rng(42));
+    /// woa.set_time_varying(true);
+    /// for _ in 0..100 {
+    ///     woa.update();
+    /// }
+    ///
+    /// // The best whale follows the peak to 1, with its eval as of the last update.
+    /// let (best, e) = woa.best();
+    /// assert!((best.pos - 1.0).abs() < 0.1);
+    /// assert_eq!(e, best.eval_at(100));
+    /// assert_eq!(woa.eval_count(), 10 + 100 * 11);
+    /// # }
+    /// ```
+    pub fn set_time_varying(&mut self, time_varying: bool) {
+        self.time_varying = time_varying;
+    }
+
+    /// Returns whether the published componentwise update is enabled with `set_componentwise`.
+    pub fn componentwise(&self) -> bool {
+        self.componentwise.is_some()
+    }
+
+    /// Returns the best whale found so far and its eval.
+    pub fn best(&self) -> (T, T::Eval) {
+        self.best.clone()
+    }
+
+    pub fn whales(&self) -> &[(T, T::Eval)] {
+        &self.whales
+    }
+
+    /// Returns the number of objective evaluations made so far, including those of the initial
+    /// whales.
+    pub fn eval_count(&self) -> usize {
+        self.eval_count
+    }
+
+    /// Returns the number of `update` calls made so far.
+    pub fn iteration(&self) -> usize {
+        self.iteration
+    }
+
+    fn random_whales(whales_num: usize) -> Vec<(T, T::Eval)> {
+        ::build_population(whales_num, |_| {
+            let w = T::new_random();
+            let e = w.eval();
+            (w, e)
+        })
+    }
+
+    fn best_index(whales: &[(T, T::Eval)]) -> usize {
+        let mut best = 0;
+        for (i, w) in whales.iter().enumerate() {
+            if w.1 > whales[best].1 {
+                best = i;
+            }
+        }
+        best
+    }
+}
+
+impl<T, R> WhaleOptimization<T, R>
+    where T: Componentwise + RandomCandidate + Clone,
+          R: Rng
+{
+    /// Moves the whales by the update as published, drawing the coefficients per component and
+    /// measuring the distances to the encircled and spiraled positions componentwise absolute.
+    /// Disabled by default, where the update is the variant described on `WhaleOptimization`.
+    ///
+    /// # Example
+    /// ```
+    /// # extern crate meta_heuristics;
+    /// # extern crate rand;
+    /// # use meta_heuristics::{woa, Candidate, RandomCandidate};
+    /// # use rand::{Closed01, Rng};
+    /// # #[derive(Clone, Copy)]
+    /// # struct Whale { pos: f64 }
+    /// # impl Candidate for Whale {
+    /// #     type Pos = f64;
+    /// #     type Eval = f64;
+    /// #     fn eval(&self) -> f64 { -self.pos * self.pos }
+    /// #     fn pos(&self) -> f64 { self.pos }
+    /// #     fn pos_mut(&mut self) -> &mut f64 { &mut self.pos }
+    /// # }
+    /// # impl RandomCandidate for Whale {
+    /// #     fn new_random() -> Self {
+    /// #         Self { pos: 10.0 * rand::random::<Closed01<f64>>().0 - 5.0 }
+    /// #     }
+    /// # }
+    /// impl woa::Componentwise for Whale {
+    ///     fn encircle<R: Rng>(pos: &f64, leader: &f64, random: &f64, a: f64, rng: &mut R) -> f64 {
+    ///         let coeff_a = a * (2.0 * rng.gen::<Closed01<f64>>().0 - 1.0);
+    ///         let coeff_c = 2.0 * rng.gen::<Closed01<f64>>().0;
+    ///         let target = if coeff_a.abs() < 1.0 { leader } else { random };
+    ///         target - coeff_a * (coeff_c * target - pos).abs()
+    ///     }
+    ///
+    ///     fn abs(pos: &f64) -> f64 {
+    ///         pos.abs()
+    ///     }
+    /// }
+    ///
+    /// # fn main() {
+    /// let mut woa: woa::WhaleOptimization<Whale> = woa::WhaleOptimization::new(10, 100);
+    /// woa.set_componentwise(true);
+    /// assert!(woa.componentwise());
+    ///
+    /// for _ in 0..100 {
+    ///     woa.update();
+    /// }
+    /// assert!(woa.best().1 > -1e-6);
+    /// # }
+    /// ```
+    pub fn set_componentwise(&mut self, componentwise: bool) {
+        self.componentwise = if componentwise {
+            Some(ComponentwiseMoves {
+                     encircle: T::encircle::<R>,
+                     abs: T::abs,
+                 })
+        } else {
+            None
+        };
+    }
+}
+
+impl<T, R> Optimizer for WhaleOptimization<T, R>
+    where T: RandomCandidate + Clone,
+          R: Rng
+{
+    type Solution = T;
+    type Eval = T::Eval;
+
+    fn step(&mut self) {
+        self.update();
+    }
+
+    fn best(&self) -> (T, T::Eval) {
+        WhaleOptimization::best(self)
+    }
+
+    fn replace_worst(&mut self, solution: T) {
+        let mut worst = 0;
+        for (i, w) in self.whales.iter().enumerate() {
+            if w.1 < self.whales[worst].1 {
+                worst = i;
+            }
+        }
+
+        let e = solution.eval();
+        self.eval_count += 1;
+        if e > self.best.1 {
+            self.best = (solution.clone(), e.clone());
+        }
+        self.whales[worst] = (solution, e);
+    }
+
+    fn population(&self) -> Vec<T> {
+        self.whales.iter().map(|w| w.0.clone()).collect()
+    }
+
+    fn reset(&mut self) {
+        WhaleOptimization::reset(self);
+    }
+
+    fn eval_count(&self) -> usize {
+        WhaleOptimization::eval_count(self)
+    }
+
+    fn step_evals(&self) -> usize {
+        if self.time_varying {
+            self.whales.len() + 1
+        } else {
+            self.whales.len()
+        }
+    }
+}
+
+fn rand_01<R: Rng>(rng: &mut R) -> f64 {
+    let Closed01(val) = rng.gen::<Closed01<_>>();
+    val
+}
+
+impl<T, R> fmt::Debug for WhaleOptimization<T, R>
+    where T: Candidate + Clone,
+          T::Eval: fmt::Debug,
+          R: Rng
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WhaleOptimization")
+            .field("whales", &self.whales.len())
+            .field("max_iters", &self.max_iters)
+            .field("spiral", &self.spiral)
+            .field("componentwise", &self.componentwise.is_some())
+            .field("iteration", &self.iteration)
+            .field("best_eval", &self.best.1)
+            .finish()
+    }
+}
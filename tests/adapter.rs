@@ -0,0 +1,70 @@
+extern crate meta_heuristics;
+extern crate rand;
+
+mod common;
+
+use meta_heuristics::{pso, RandomCandidate};
+use common::{eval_func, Particle};
+
+#[test]
+fn eval_map() {
+    use meta_heuristics::adapter::{EvalMap, MapEval};
+
+    // A logarithmic compression which, unlike `ln`, is defined and increasing on all reals.
+    #[derive(Clone, Copy, Default)]
+    struct SymLog;
+
+    impl EvalMap<f64> for SymLog {
+        type Output = f64;
+
+        fn map(&self, e: f64) -> f64 {
+            e.signum() * e.abs().ln_1p()
+        }
+    }
+
+    let mut pso: pso::PSO<MapEval<Particle, SymLog>> = pso::PSO::new(16, 0.9, 0.9, 0.9);
+    for _ in 0..30 {
+        pso.update();
+    }
+
+    let (p, e) = pso.best();
+    assert_eq!(e, SymLog.map(eval_func(p.inner().pos)));
+    assert!(eval_func(p.inner().pos) > 1.5);
+}
+
+#[test]
+fn closure_map() {
+    use meta_heuristics::adapter::MapEval;
+
+    // Squashes the evals into (-π/2, π/2), at a scale picked at run time.
+    let scale = 2.0;
+    let squash = move |e: f64| (e / scale).atan();
+    let sampler = move || MapEval::new_with_map(Particle::new_random(), squash);
+    let mut pso = pso::PSO::new_with_sampler(16, sampler, 0.9, 0.9, 0.9);
+    for _ in 0..30 {
+        pso.update();
+    }
+
+    let (p, e) = pso.best();
+    assert_eq!(e, (eval_func(p.inner().pos) / scale).atan());
+    assert!(eval_func(p.inner().pos) > 1.5);
+}
+
+#[test]
+fn wrapped_capabilities() {
+    use meta_heuristics::adapter::MapEval;
+
+    // Variants relying on the wrapped capabilities work through the adapter.
+    let sampler = || MapEval::new_with_map(Particle::new_random(), |e: f64| 2.0 * e);
+    let mut pso = pso::PSO::new_with_sampler(16, sampler, 0.0, 0.0, 0.0);
+    pso.set_bare_bones(true);
+    pso.set_validate(true);
+    for _ in 0..50 {
+        pso.update();
+    }
+    pso.polish(10, 1e-4, 0.1);
+
+    let (p, e) = pso.best();
+    assert_eq!(e, 2.0 * eval_func(p.inner().pos));
+    assert!(eval_func(p.inner().pos) > 1.5);
+}
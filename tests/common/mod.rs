@@ -0,0 +1,416 @@
+//! The candidate types shared by the integration tests.
+
+// Each test crate uses only some of the fixtures.
+#![allow(dead_code)]
+
+use std::cell::Cell;
+use std::ops;
+use std::rc::Rc;
+
+use rand::{self, Closed01, Rng, XorShiftRng};
+use rand::distributions::normal::StandardNormal;
+
+use meta_heuristics::{seeded_rng, Candidate, Components, RandomCandidate, SeededCandidate};
+use meta_heuristics::firefly::{self, RandomStep, ScaledStep};
+use meta_heuristics::pso;
+
+/// The one-dimensional objective of the tests, with its global maximum about 1.62 near
+/// `x = 1.64` and a local maximum 1 at `x = 0`.
+pub fn eval_func(x: f64) -> f64 {
+    1.0 - ((x - 3.0) * x + 2.0) * x * x
+}
+
+/// A particle maximizing `eval_func`, created at a random position in `[-1, 3]` at rest.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Particle {
+    pub pos: f64,
+    pub vel: f64,
+    pub best: (f64, f64),
+}
+
+impl Particle {
+    /// Creates a particle at rest at `x`, with its personal best there.
+    pub fn at(x: f64) -> Self {
+        Particle { pos: x, vel: 0.0, best: (x, eval_func(x)) }
+    }
+}
+
+impl Candidate for Particle {
+    type Pos = f64;
+    type Eval = f64;
+
+    fn eval(&self) -> f64 {
+        eval_func(self.pos)
+    }
+
+    fn pos(&self) -> f64 {
+        self.pos
+    }
+
+    fn pos_mut(&mut self) -> &mut f64 {
+        &mut self.pos
+    }
+}
+
+impl Components for Particle {
+    fn components(&self) -> Vec<f64> {
+        vec![self.pos]
+    }
+}
+
+impl RandomCandidate for Particle {
+    fn new_random() -> Self {
+        Self::new_random_seeded(rand::random())
+    }
+}
+
+impl SeededCandidate for Particle {
+    fn new_random_seeded(seed: u64) -> Self {
+        let Closed01(x) = seeded_rng(seed).gen::<Closed01<f64>>();
+        Particle::at(4.0 * x - 1.0)
+    }
+}
+
+impl pso::Particle for Particle {
+    fn vel(&self) -> f64 {
+        self.vel
+    }
+
+    fn best(&self) -> (f64, f64) {
+        self.best
+    }
+
+    fn vel_mut(&mut self) -> &mut f64 {
+        &mut self.vel
+    }
+
+    fn best_mut(&mut self) -> &mut (f64, f64) {
+        &mut self.best
+    }
+}
+
+impl pso::VelocityNorm for Particle {
+    fn vel_magnitude(&self) -> f64 {
+        self.vel.abs()
+    }
+}
+
+impl pso::Metric for Particle {
+    fn pos_distance(a: &f64, b: &f64) -> f64 {
+        (a - b).abs()
+    }
+}
+
+impl pso::ComponentBounds for Particle {
+    fn component_min(a: &f64, b: &f64) -> f64 {
+        a.min(*b)
+    }
+
+    fn component_max(a: &f64, b: &f64) -> f64 {
+        a.max(*b)
+    }
+}
+
+impl pso::RegionSampling for Particle {
+    fn random_in<R: Rng>(lower: &f64, upper: &f64, rng: &mut R) -> Self {
+        let Closed01(x) = rng.gen::<Closed01<f64>>();
+        Particle::at(lower + (upper - lower) * x)
+    }
+}
+
+impl pso::RandomVelocity for Particle {
+    fn random_vel<R: Rng>(scale: f64, rng: &mut R) -> f64 {
+        let Closed01(v) = rng.gen::<Closed01<f64>>();
+        scale * (2.0 * v - 1.0)
+    }
+}
+
+impl pso::Gaussian for Particle {
+    fn gaussian_sample<R: Rng>(mean: &f64, std: &f64, rng: &mut R) -> f64 {
+        let StandardNormal(z) = rng.gen::<StandardNormal>();
+        mean + std * z
+    }
+}
+
+impl pso::Axes for Particle {
+    fn axis(&self, _: usize) -> f64 {
+        1.0
+    }
+}
+
+impl pso::Levy for Particle {
+    fn levy_step<R: Rng>(&mut self, exponent: f64, rng: &mut R) {
+        self.pos += 0.1 * pso::levy_sample(exponent, rng);
+    }
+}
+
+impl firefly::Firefly for Particle {
+    fn distance(&self, rhs: &Self) -> f64 {
+        (self.pos - rhs.pos).abs()
+    }
+}
+
+impl RandomStep for Particle {
+    fn random_step<R: Rng>(scale: f64, rng: &mut R) -> f64 {
+        let Closed01(x) = rng.gen::<Closed01<f64>>();
+        scale * (x - 0.5)
+    }
+}
+
+/// A firefly maximizing `eval_func`, created at a random position in `[-1.5, 2.5]`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Point {
+    pub pos: f64,
+}
+
+impl Candidate for Point {
+    type Pos = f64;
+    type Eval = f64;
+
+    fn eval(&self) -> f64 {
+        eval_func(self.pos)
+    }
+
+    fn pos(&self) -> f64 {
+        self.pos
+    }
+
+    fn pos_mut(&mut self) -> &mut f64 {
+        &mut self.pos
+    }
+}
+
+impl RandomCandidate for Point {
+    fn new_random() -> Self {
+        Self::new_random_seeded(rand::random())
+    }
+}
+
+impl SeededCandidate for Point {
+    fn new_random_seeded(seed: u64) -> Self {
+        let Closed01(x) = seeded_rng(seed).gen::<Closed01<f64>>();
+        Point { pos: 4.0 * x - 1.5 }
+    }
+}
+
+impl firefly::Firefly for Point {
+    fn distance(&self, rhs: &Self) -> f64 {
+        (self.pos - rhs.pos).abs()
+    }
+}
+
+impl RandomStep for Point {
+    fn random_step<R: Rng>(scale: f64, rng: &mut R) -> f64 {
+        let Closed01(x) = rng.gen::<Closed01<f64>>();
+        scale * (x - 0.5)
+    }
+}
+
+impl ScaledStep for Point {
+    fn random_step_in<R: Rng>(range: &f64, rng: &mut R) -> f64 {
+        let Closed01(r) = rng.gen::<Closed01<f64>>();
+        range * (r - 0.5)
+    }
+}
+
+impl firefly::Brightness for Point {
+    /// Ranks the fireflies by position, the leftmost brightest, whatever their evals.
+    fn brightness(&self) -> f64 {
+        -self.pos
+    }
+}
+
+/// Defines a particle type like `Particle`, but maximizing the given function of its position
+/// and created at rest at a random position in `[-1, 3]`.
+#[macro_export]
+macro_rules! particle_maximizing {
+    ($name:ident, |$x:ident| $eval:expr) => {
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        pub struct $name {
+            pub pos: f64,
+            pub vel: f64,
+            pub best: (f64, f64),
+        }
+
+        impl $name {
+            pub fn objective($x: f64) -> f64 {
+                $eval
+            }
+
+            pub fn at(x: f64) -> Self {
+                $name { pos: x, vel: 0.0, best: (x, Self::objective(x)) }
+            }
+        }
+
+        impl ::meta_heuristics::Candidate for $name {
+            type Pos = f64;
+            type Eval = f64;
+
+            fn eval(&self) -> f64 {
+                Self::objective(self.pos)
+            }
+
+            fn pos(&self) -> f64 {
+                self.pos
+            }
+
+            fn pos_mut(&mut self) -> &mut f64 {
+                &mut self.pos
+            }
+
+            fn is_finite(&self) -> bool {
+                self.pos.is_finite()
+            }
+        }
+
+        impl ::meta_heuristics::RandomCandidate for $name {
+            fn new_random() -> Self {
+                ::meta_heuristics::SeededCandidate::new_random_seeded(::rand::random())
+            }
+        }
+
+        impl ::meta_heuristics::SeededCandidate for $name {
+            fn new_random_seeded(seed: u64) -> Self {
+                use rand::Rng;
+
+                let ::rand::Closed01(x) =
+                    ::meta_heuristics::seeded_rng(seed).gen::<::rand::Closed01<f64>>();
+                Self::at(4.0 * x - 1.0)
+            }
+        }
+
+        impl ::meta_heuristics::pso::Particle for $name {
+            fn vel(&self) -> f64 {
+                self.vel
+            }
+
+            fn best(&self) -> (f64, f64) {
+                self.best
+            }
+
+            fn vel_mut(&mut self) -> &mut f64 {
+                &mut self.vel
+            }
+
+            fn best_mut(&mut self) -> &mut (f64, f64) {
+                &mut self.best
+            }
+        }
+
+        impl ::meta_heuristics::pso::Metric for $name {
+            fn pos_distance(a: &f64, b: &f64) -> f64 {
+                (a - b).abs()
+            }
+        }
+
+        impl ::meta_heuristics::pso::Axes for $name {
+            fn axis(&self, _: usize) -> f64 {
+                1.0
+            }
+        }
+    };
+}
+
+/// A two-dimensional position.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct P2(pub [f64; 2]);
+
+impl P2 {
+    /// Combines the coordinates of `self` and `rhs` pairwise with `f`.
+    pub fn zip(self, rhs: P2, f: fn(f64, f64) -> f64) -> P2 {
+        P2([f(self.0[0], rhs.0[0]), f(self.0[1], rhs.0[1])])
+    }
+}
+
+impl ops::Add for P2 {
+    type Output = P2;
+
+    fn add(self, rhs: P2) -> P2 {
+        self.zip(rhs, |a, b| a + b)
+    }
+}
+
+impl ops::Sub for P2 {
+    type Output = P2;
+
+    fn sub(self, rhs: P2) -> P2 {
+        self.zip(rhs, |a, b| a - b)
+    }
+}
+
+impl ops::Mul<f64> for P2 {
+    type Output = P2;
+
+    fn mul(self, rhs: f64) -> P2 {
+        P2([self.0[0] * rhs, self.0[1] * rhs])
+    }
+}
+
+/// A generator counting the random numbers drawn from it in the shared counter.
+pub struct Counting(pub XorShiftRng, pub Rc<Cell<usize>>);
+
+impl Rng for Counting {
+    fn next_u32(&mut self) -> u32 {
+        self.1.set(self.1.get() + 1);
+        self.0.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.1.set(self.1.get() + 1);
+        self.0.next_u64()
+    }
+}
+
+/// Defines a firefly type like `Point`, but maximizing the given function of its position.
+#[macro_export]
+macro_rules! point_maximizing {
+    ($name:ident, |$x:ident| $eval:expr) => {
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        pub struct $name {
+            pub pos: f64,
+        }
+
+        impl $name {
+            pub fn objective($x: f64) -> f64 {
+                $eval
+            }
+        }
+
+        impl ::meta_heuristics::Candidate for $name {
+            type Pos = f64;
+            type Eval = f64;
+
+            fn eval(&self) -> f64 {
+                Self::objective(self.pos)
+            }
+
+            fn pos(&self) -> f64 {
+                self.pos
+            }
+
+            fn pos_mut(&mut self) -> &mut f64 {
+                &mut self.pos
+            }
+        }
+
+        impl ::meta_heuristics::RandomCandidate for $name {
+            fn new_random() -> Self {
+                let ::rand::Closed01(x) = ::rand::random::<::rand::Closed01<f64>>();
+                $name { pos: 4.0 * x - 1.5 }
+            }
+        }
+
+        impl ::meta_heuristics::firefly::Firefly for $name {
+            fn distance(&self, rhs: &Self) -> f64 {
+                (self.pos - rhs.pos).abs()
+            }
+        }
+
+        impl ::meta_heuristics::firefly::RandomStep for $name {
+            fn random_step<R: ::rand::Rng>(scale: f64, rng: &mut R) -> f64 {
+                let ::rand::Closed01(x) = rng.gen::<::rand::Closed01<f64>>();
+                scale * (x - 0.5)
+            }
+        }
+    };
+}
@@ -0,0 +1,544 @@
+extern crate meta_heuristics;
+extern crate rand;
+
+#[macro_use]
+mod common;
+
+use rand::{Closed01, Rng};
+
+use meta_heuristics::{firefly, pso, Candidate, Optimizer, RandomCandidate};
+use meta_heuristics::firefly::Firefly;
+use common::{Particle, Point};
+
+// A plateau for non-negative positions.
+point_maximizing!(Plateau, |x| x.min(0.0));
+
+#[test]
+fn greedy() {
+    use meta_heuristics::firefly::ReplacementPolicy;
+
+    let mut ff: firefly::FireflyAlg<Point, _> =
+        firefly::FireflyAlg::new_seeded(16, 1.5, 0.2, 42);
+    ff.set_replacement_policy(ReplacementPolicy::Greedy);
+
+    for _ in 0..30 {
+        let before: Vec<f64> = ff.fireflies().iter().map(|&(_, e)| e).collect();
+        ff.update();
+        for (&(_, e), b) in ff.fireflies().iter().zip(before) {
+            assert!(e >= b);
+        }
+    }
+}
+
+#[test]
+fn new_obl() {
+    use std::cell::RefCell;
+    use rand::{SeedableRng, StdRng};
+
+    thread_local!(static RNG: RefCell<StdRng> = RefCell::new(StdRng::new().unwrap()));
+
+    // A `Point` drawn from the seedable `RNG` in [-1, 3].
+    #[derive(Clone, Copy)]
+    struct Reseeded(Point);
+
+    impl Candidate for Reseeded {
+        type Pos = f64;
+        type Eval = f64;
+
+        fn eval(&self) -> f64 {
+            self.0.eval()
+        }
+
+        fn pos(&self) -> f64 {
+            self.0.pos
+        }
+
+        fn pos_mut(&mut self) -> &mut f64 {
+            &mut self.0.pos
+        }
+    }
+
+    impl RandomCandidate for Reseeded {
+        fn new_random() -> Self {
+            let Closed01(x) = RNG.with(|rng| rng.borrow_mut().gen::<Closed01<f64>>());
+            Reseeded(Point { pos: 4.0 * x - 1.0 })
+        }
+    }
+
+    impl Firefly for Reseeded {
+        fn distance(&self, rhs: &Self) -> f64 {
+            self.0.distance(&rhs.0)
+        }
+    }
+
+    let seed = |seed: usize| RNG.with(|rng| *rng.borrow_mut() = StdRng::from_seed(&[seed][..]));
+
+    for s in 0..20 {
+        seed(s);
+        let plain: firefly::FireflyAlg<Reseeded> = firefly::FireflyAlg::new(4, 0.5, 0.2);
+        seed(s);
+        let obl: firefly::FireflyAlg<Reseeded> =
+            firefly::FireflyAlg::new_obl(4, -1.0, 3.0, 0.5, 0.2);
+
+        assert_eq!((obl.len(), obl.eval_count()), (4, 8));
+        assert!(obl.best().1 >= plain.best().1);
+    }
+}
+
+#[test]
+fn from_fireflies() {
+    let a = Point { pos: 0.0 };
+    let b = Point { pos: 1.0 };
+    let c = Point { pos: 2.0 };
+    let mut forward = firefly::FireflyAlg::from_fireflies(vec![a, b, c], 0.5, 0.2);
+    let mut backward = firefly::FireflyAlg::from_fireflies(vec![c, b, a], 0.5, 0.2);
+    forward.update();
+    backward.update();
+
+    // Each firefly ends up at the same place whatever the order of its neighbors.
+    for (f, b) in forward.fireflies().iter().zip(backward.fireflies().iter().rev()) {
+        assert_eq!(f.0.pos, b.0.pos);
+    }
+}
+
+#[test]
+fn from_optimizer() {
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(12, 0.7, 1.4, 1.4, 42);
+    for _ in 0..10 {
+        pso.update();
+    }
+
+    assert!(firefly::FireflyAlg::from_optimizer(&pso, 0.5, 0.2, -1.0).is_err());
+    let ff = firefly::FireflyAlg::from_optimizer(&pso, 0.5, 0.2, 0.0).unwrap();
+
+    assert_eq!(ff.len(), pso.len());
+    assert_eq!(ff.eval_count(), 12);
+    for (f, p) in ff.fireflies().iter().zip(pso.particles()) {
+        assert_eq!((f.0.pos, f.1), (p.pos, p.eval()));
+    }
+}
+
+#[test]
+fn new_seeded() {
+    let run = |seed: u64| {
+        let mut ff: firefly::FireflyAlg<Point, _> =
+            firefly::FireflyAlg::new_seeded(8, 0.5, 0.2, seed);
+        ff.set_alpha(0.1).unwrap();
+        for _ in 0..10 {
+            ff.update();
+        }
+        ff.fireflies().iter().map(|ff| ff.0.pos).collect::<Vec<_>>()
+    };
+    assert_eq!(run(7), run(7));
+    assert_ne!(run(7), run(8));
+}
+
+#[test]
+fn new_with_rng() {
+    use rand::{SeedableRng, StdRng, XorShiftRng};
+
+    let mut ff = firefly::FireflyAlg::<Point, XorShiftRng>::new_with_rng(
+        16, 0.5, 0.2, XorShiftRng::from_seed([1, 2, 3, 4]));
+    ff.set_alpha(0.1).unwrap();
+    ff.update();
+
+    // Given the same population, the same seed reproduces the same random steps.
+    let population: Vec<Point> = (0..4).map(|i| Point { pos: i as f64 }).collect();
+    let run = |seed: usize| {
+        let rng = StdRng::from_seed(&[seed][..]);
+        let mut ff =
+            firefly::FireflyAlg::from_fireflies_with_rng(population.clone(), 0.5, 0.2, rng);
+        ff.set_alpha(0.1).unwrap();
+        ff.update();
+        ff.fireflies().iter().map(|ff| ff.0.pos).collect::<Vec<_>>()
+    };
+    assert_eq!(run(7), run(7));
+    assert_ne!(run(7), run(8));
+}
+
+#[test]
+fn update() {
+    let population = vec![Point { pos: 0.5 }, Point { pos: 1.0 }, Point { pos: 1.5 }];
+    let mut ff = firefly::FireflyAlg::from_fireflies(population.clone(), 0.5, 0.2);
+    ff.update();
+
+    // Both others are brighter than the first firefly.
+    let attraction = |to: &Point| {
+        let dist = population[0].distance(to);
+        firefly::firefly_displacement(&0.5, &to.pos, 0.5, 0.2, dist)
+    };
+    let expected = 0.5 + (attraction(&population[1]) + attraction(&population[2]));
+    assert_eq!(ff.fireflies()[0].0.pos.to_bits(), expected.to_bits());
+}
+
+#[test]
+fn forces() {
+    let mut ff: firefly::FireflyAlg<Point, _> =
+        firefly::FireflyAlg::new_seeded(8, 0.5, 0.2, 42);
+    for _ in 0..5 {
+        let before: Vec<f64> = ff.fireflies().iter().map(|ff| ff.0.pos).collect();
+        let forces = ff.forces();
+        ff.update();
+
+        for ((after, before), force) in ff.fireflies().iter().zip(before).zip(forces) {
+            assert!((after.0.pos - before - force).abs() < 1e-12);
+        }
+    }
+
+    // The brightest firefly is attracted by none.
+    let brightest = ff.fireflies().iter().map(|ff| ff.1).fold(f64::MIN, f64::max);
+    let i = ff.fireflies().iter().position(|ff| ff.1 == brightest).unwrap();
+    assert_eq!(ff.forces()[i], 0.0);
+}
+
+#[test]
+fn reset() {
+    let mut ff: firefly::FireflyAlg<Point, _> =
+        firefly::FireflyAlg::new_seeded(16, 0.5, 0.2, 42);
+    ff.set_replacement_policy(firefly::ReplacementPolicy::Greedy);
+    for _ in 0..10 {
+        ff.update();
+    }
+
+    let before: Vec<f64> = ff.fireflies().iter().map(|ff| ff.0.pos).collect();
+    ff.reset();
+
+    assert_eq!((ff.beta(), ff.absorption(), ff.iteration()), (0.5, 0.2, 0));
+    assert_eq!(ff.fireflies().len(), 16);
+    assert!(ff.fireflies().iter().all(|ff| !before.contains(&ff.0.pos)));
+}
+
+#[test]
+fn set_move_on_tie() {
+    let plateau: Vec<Plateau> = (0..4).map(|i| Plateau { pos: 10.0 + i as f64 }).collect();
+
+    let mut ff = firefly::FireflyAlg::from_fireflies(plateau.clone(), 1.0, 0.1);
+    ff.update();
+    assert!(ff.fireflies().iter().zip(&plateau).all(|(ff, p)| ff.0.pos == p.pos));
+
+    let mut ff = firefly::FireflyAlg::from_fireflies(plateau.clone(), 1.0, 0.1);
+    ff.set_move_on_tie(true);
+    ff.update();
+    assert!(ff.fireflies().iter().zip(&plateau).all(|(ff, p)| ff.0.pos != p.pos));
+}
+
+#[test]
+fn set_linear_decay() {
+    // A lone firefly only moves by the random step.
+    let mut ff = firefly::FireflyAlg::from_fireflies(vec![Point { pos: 0.0 }], 0.5, 0.2);
+    ff.set_alpha(0.4).unwrap();
+    assert_eq!(ff.progress(), None);
+
+    ff.set_linear_decay(4);
+    for i in 0..4 {
+        assert_eq!(ff.progress(), Some(i as f64 / 4.0));
+        assert!((ff.effective_alpha() - 0.1 * (4 - i) as f64).abs() < 1e-12);
+        ff.update();
+    }
+
+    assert_eq!(ff.progress(), Some(1.0));
+    assert_eq!(ff.effective_alpha(), 0.0);
+    let pos = ff.fireflies()[0].0.pos;
+    ff.update();
+    assert_eq!(ff.fireflies()[0].0.pos, pos);
+}
+
+#[test]
+fn set_min_separation() {
+    // Two peaks, at -2 and at 2, the former higher.
+    point_maximizing!(TwoPeaks, |x| {
+        (-(x + 2.0) * (x + 2.0)).exp().max(0.9 * (-(x - 2.0) * (x - 2.0)).exp())
+    });
+
+    let population: Vec<TwoPeaks> =
+        (0..16).map(|i| TwoPeaks { pos: -4.0 + 8.0 * i as f64 / 15.0 }).collect();
+    let on_peaks = |min_separation: f64| {
+        let mut ff = firefly::FireflyAlg::from_fireflies(population.clone(), 0.1, 0.3);
+        ff.set_min_separation(min_separation).unwrap();
+        for _ in 0..200 {
+            ff.update();
+        }
+        let near = |peak: f64| {
+            ff.fireflies().iter().filter(|ff| (ff.0.pos - peak).abs() < 0.5).count()
+        };
+        (near(-2.0), near(2.0))
+    };
+
+    // Pure attraction gathers the whole population on the higher peak.
+    assert_eq!(on_peaks(0.0), (16, 0));
+
+    // Crowding pushes fireflies away, and some settle on the other peak.
+    let (higher, lower) = on_peaks(0.3);
+    assert!(higher >= 4 && lower >= 4);
+
+    let mut ff = firefly::FireflyAlg::from_fireflies(population, 0.1, 0.3);
+    assert!(ff.set_min_separation(-1.0).is_err());
+}
+
+#[test]
+fn reevaluate() {
+    let mut ff: firefly::FireflyAlg<Point, _> =
+        firefly::FireflyAlg::new_seeded(8, 0.5, 0.2, 42);
+    assert_eq!(ff.eval_count(), 8);
+
+    ff.fireflies_mut()[0].0.pos = 1.5;
+    assert!(ff.fireflies()[0].1 != ff.fireflies()[0].0.eval());
+
+    ff.reevaluate();
+    assert_eq!(ff.fireflies()[0].1, ff.fireflies()[0].0.eval());
+    assert_eq!(ff.eval_count(), 16);
+}
+
+#[test]
+fn run_eval_budget() {
+    let mut ff: firefly::FireflyAlg<Point, _> =
+        firefly::FireflyAlg::new_seeded(8, 0.5, 0.2, 42);
+    let iters = ff.run_eval_budget(100);
+    assert_eq!(ff.iteration(), iters);
+    assert!(ff.eval_count() <= 100);
+
+    // The brightest firefly does not move, so at least 11 updates fit.
+    assert!(iters >= 11);
+    assert!(ff.eval_count() + 8 > 100);
+}
+
+#[test]
+fn moves_accepted() {
+    use meta_heuristics::firefly::ReplacementPolicy;
+
+    // Without randomization, the brightest firefly does not move.
+    let population = vec![Point { pos: 0.5 }, Point { pos: 1.0 }, Point { pos: 1.5 }];
+    let mut ff = firefly::FireflyAlg::from_fireflies(population, 0.5, 0.2);
+    ff.update();
+    assert_eq!((ff.moves_accepted(), ff.moves_rejected()), (2, 0));
+
+    // With it, every firefly moves, and the greedy policy rejects the worse moves.
+    let mut ff: firefly::FireflyAlg<Point, _> =
+        firefly::FireflyAlg::new_seeded(16, 1.5, 0.2, 42);
+    ff.set_alpha(0.5).unwrap();
+    ff.set_replacement_policy(ReplacementPolicy::Greedy);
+    for _ in 0..10 {
+        ff.update();
+        assert_eq!(ff.moves_accepted() + ff.moves_rejected(), 16);
+    }
+}
+
+#[test]
+fn worst() {
+    let mut ff: firefly::FireflyAlg<Point, _> =
+        firefly::FireflyAlg::new_seeded(16, 0.5, 0.2, 42);
+    ff.update();
+
+    let (worst, e) = ff.worst();
+    assert_eq!(e, worst.eval());
+    assert!(ff.fireflies().iter().all(|&(_, f)| f >= e));
+}
+
+#[test]
+fn set_alpha() {
+    let mut ff: firefly::FireflyAlg<Point, _> =
+        firefly::FireflyAlg::new_seeded(16, 0.5, 0.2, 42);
+    assert!(ff.set_absorption(-1.0).is_err());
+    assert!(ff.set_alpha(f64::NAN).is_err());
+    assert_eq!(ff.absorption(), 0.2);
+
+    ff.set_beta(1.0).unwrap();
+    ff.set_alpha(0.0).unwrap();
+    assert_eq!((ff.beta(), ff.alpha()), (1.0, 0.0));
+}
+
+#[test]
+fn set_plateau_alpha() {
+    let plateau = vec![Plateau { pos: 10.0 }; 8];
+
+    let mut ff = firefly::FireflyAlg::from_fireflies(plateau.clone(), 1.0, 0.1);
+    ff.update();
+    assert!(ff.fireflies().iter().all(|ff| ff.0.pos == 10.0));
+
+    let mut ff = firefly::FireflyAlg::from_fireflies(plateau, 1.0, 0.1);
+    assert!(ff.set_plateau_alpha(-1.0).is_err());
+    ff.set_plateau_alpha(1.0).unwrap();
+    for _ in 0..5 {
+        ff.update();
+    }
+    assert_eq!(ff.plateau_updates(), 5);
+    assert!(ff.fireflies().iter().all(|ff| ff.0.pos != 10.0));
+    assert_eq!(ff.alpha(), 0.0);
+}
+
+#[test]
+fn set_min_step() {
+    // A lone firefly has nothing to be attracted to.
+    let mut ff = firefly::FireflyAlg::from_fireflies(vec![Point { pos: 0.0 }], 0.5, 0.2);
+    ff.update();
+    assert_eq!(ff.fireflies()[0].0.pos, 0.0);
+
+    assert!(ff.set_min_step(-1.0).is_err());
+    ff.set_min_step(0.01).unwrap();
+    for _ in 0..5 {
+        let before = ff.fireflies()[0].0.pos;
+        ff.update();
+        assert!(((ff.fireflies()[0].0.pos - before).abs() - 0.01).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn set_scaled_alpha() {
+    // A lone firefly is attracted by nothing and only takes the random steps.
+    let max_step = |width: f64| {
+        let mut ff = firefly::FireflyAlg::from_fireflies(vec![Point { pos: 0.0 }], 0.5, 0.2);
+        ff.set_alpha(0.1).unwrap();
+        assert!(ff.set_scaled_alpha(true).is_err());
+
+        ff.set_bounds(0.0, width);
+        ff.set_scaled_alpha(true).unwrap();
+        let mut max_step: f64 = 0.0;
+        for _ in 0..50 {
+            let before = ff.fireflies()[0].0.pos;
+            ff.update();
+            max_step = max_step.max((ff.fireflies()[0].0.pos - before).abs());
+        }
+        max_step
+    };
+
+    // Steps are at most alpha * width / 2.
+    let narrow = max_step(10.0);
+    assert!(0.25 < narrow && narrow <= 0.5);
+    let wide = max_step(100.0);
+    assert!(2.5 < wide && wide <= 5.0);
+}
+
+#[test]
+fn set_brightness() {
+    // By eval, the firefly at 1.5 is the brightest and the one at 0.5 the dimmest.
+    let population = vec![Point { pos: 0.5 }, Point { pos: 1.0 }, Point { pos: 1.5 }];
+    let mut ff = firefly::FireflyAlg::from_fireflies(population, 0.5, 0.2);
+    ff.set_brightness(true);
+    ff.update();
+
+    // The firefly at 0.5 is now the brightest, so it stays, and the others move left.
+    let pos: Vec<f64> = ff.fireflies().iter().map(|ff| ff.0.pos).collect();
+    assert_eq!(pos[0], 0.5);
+    assert!(pos[1] < 1.0 && pos[2] < 1.5);
+
+    // The best is still the firefly with the highest eval.
+    let (best, e) = ff.best();
+    assert!(best.pos != 0.5);
+    assert!(ff.fireflies().iter().all(|ff| ff.1 <= e));
+}
+
+#[test]
+fn apply_alpha() {
+    use rand::{SeedableRng, XorShiftRng};
+
+    // A lone firefly is attracted by none, so `update` moves it by the alpha term alone.
+    let rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let population = vec![Point { pos: 1.0 }];
+    let mut ff = firefly::FireflyAlg::from_fireflies_with_rng(population, 0.5, 0.2, rng.clone());
+    ff.set_alpha(0.1).unwrap();
+    ff.update();
+
+    let mut pos = 1.0;
+    firefly::apply_alpha::<Point, _>(&mut pos, 0.1, &mut rng.clone());
+    assert_eq!(ff.fireflies()[0].0.pos, pos);
+}
+
+#[test]
+fn into_iter() {
+    let ff: firefly::FireflyAlg<Point, _> = firefly::FireflyAlg::new_seeded(16, 0.5, 0.2, 42);
+
+    let mut count = 0;
+    for &(p, e) in &ff {
+        assert_eq!(e, p.eval());
+        count += 1;
+    }
+    assert_eq!(count, ff.len());
+}
+
+#[test]
+fn debug_summary() {
+    let mut ff: firefly::FireflyAlg<Point, _> =
+        firefly::FireflyAlg::new_seeded(16, 0.5, 0.2, 42);
+    ff.update();
+
+    let best = ff.fireflies().iter().map(|&(_, e)| e).fold(f64::MIN, f64::max);
+    let summary = format!("{:?}", ff);
+    assert!(summary.contains(&format!("best_eval: Some({:?})", best)));
+}
+
+#[test]
+fn set_time_varying() {
+    use meta_heuristics::{seeded_rng, SeededCandidate};
+    use meta_heuristics::firefly::{RandomStep, ReplacementPolicy};
+
+    // A peak drifting from 0 by 0.01 per iteration.
+    fn eval_func(x: f64, iteration: usize) -> f64 {
+        -(x - 0.01 * iteration as f64).powi(2)
+    }
+
+    #[derive(Clone, Copy)]
+    struct Tracking {
+        pos: f64,
+    }
+
+    impl Candidate for Tracking {
+        type Pos = f64;
+        type Eval = f64;
+
+        fn eval(&self) -> f64 {
+            eval_func(self.pos, 0)
+        }
+
+        fn eval_at(&self, iteration: usize) -> f64 {
+            eval_func(self.pos, iteration)
+        }
+
+        fn pos(&self) -> f64 {
+            self.pos
+        }
+
+        fn pos_mut(&mut self) -> &mut f64 {
+            &mut self.pos
+        }
+    }
+
+    impl RandomCandidate for Tracking {
+        fn new_random() -> Self {
+            Self::new_random_seeded(rand::random())
+        }
+    }
+
+    impl SeededCandidate for Tracking {
+        fn new_random_seeded(seed: u64) -> Self {
+            let Closed01(x) = seeded_rng(seed).gen::<Closed01<f64>>();
+            Tracking { pos: 4.0 * x - 2.0 }
+        }
+    }
+
+    impl Firefly for Tracking {
+        fn distance(&self, rhs: &Self) -> f64 {
+            (self.pos - rhs.pos).abs()
+        }
+    }
+
+    impl RandomStep for Tracking {
+        fn random_step<R: Rng>(scale: f64, rng: &mut R) -> f64 {
+            let Closed01(x) = rng.gen::<Closed01<f64>>();
+            scale * (x - 0.5)
+        }
+    }
+
+    let mut ff: firefly::FireflyAlg<Tracking, _> =
+        firefly::FireflyAlg::new_seeded(16, 1.0, 0.5, 42);
+    ff.set_alpha(0.1).unwrap();
+    ff.set_replacement_policy(ReplacementPolicy::Greedy);
+    ff.set_time_varying(true);
+    for _ in 0..100 {
+        ff.update();
+    }
+
+    // Every firefly moves by the alpha step, and every cached eval is as of the last update.
+    assert_eq!(ff.eval_count(), 16 + 100 * 32);
+    assert!(ff.fireflies().iter().all(|&(p, e)| e == p.eval_at(100)));
+    assert!((ff.best().0.pos - 1.0).abs() < 0.1);
+}
@@ -0,0 +1,83 @@
+extern crate meta_heuristics;
+extern crate rand;
+
+mod common;
+
+use rand::Isaac64Rng;
+
+use meta_heuristics::{firefly, island, pso};
+use common::{Particle, Point};
+
+#[test]
+fn run_eval_budget() {
+    let islands = (0..3).map(|_| pso::PSO::<Particle>::new(8, 0.7, 1.4, 1.4)).collect();
+    let mut model = island::IslandModel::new(islands, 5);
+    assert_eq!(model.eval_count(), 24);
+
+    // Every step costs 24 evals, and every fifth 3 more for the migrants.
+    let iters = model.run_eval_budget(500);
+    assert_eq!(iters, 19);
+    assert_eq!(model.eval_count(), 24 + 19 * 24 + 3 * 3);
+    assert!(model.eval_count() <= 500);
+}
+
+#[test]
+fn run_parallel_fireflies() {
+    let model = || {
+        let islands = (0..4)
+            .map(|seed| {
+                let mut ff = firefly::FireflyAlg::<Point, _>::new_seeded(8, 0.5, 0.2, seed);
+                ff.set_alpha(0.1).unwrap();
+                ff
+            })
+            .collect();
+        island::IslandModel::new(islands, 7)
+    };
+    let positions = |model: &island::IslandModel<firefly::FireflyAlg<Point, Isaac64Rng>>| {
+        model.islands()
+            .iter()
+            .flat_map(|i| i.fireflies().iter().map(|ff| ff.0.pos.to_bits()))
+            .collect::<Vec<_>>()
+    };
+
+    let mut serial = model();
+    for _ in 0..30 {
+        serial.step();
+    }
+    let mut parallel = model();
+    parallel.run_parallel(30);
+
+    assert_eq!(parallel.iteration(), 30);
+    assert_eq!(positions(&parallel), positions(&serial));
+    assert_eq!(parallel.best().1.to_bits(), serial.best().1.to_bits());
+}
+
+#[test]
+fn run_parallel_particles() {
+    let model = || {
+        let islands = (0..4)
+            .map(|seed| {
+                let mut pso = pso::PSO::<Particle, _>::new_seeded(8, 0.7, 1.4, 1.4, seed);
+                pso.set_inertia_fn(|ctx| 0.9 - 0.02 * ctx.iteration().min(20) as f64);
+                pso
+            })
+            .collect();
+        island::IslandModel::new(islands, 5)
+    };
+    let positions = |model: &island::IslandModel<pso::PSO<Particle, Isaac64Rng>>| {
+        model.islands()
+            .iter()
+            .flat_map(|i| i.particles().iter().map(|p| p.pos.to_bits()))
+            .collect::<Vec<_>>()
+    };
+
+    let mut serial = model();
+    for _ in 0..20 {
+        serial.step();
+    }
+    let mut parallel = model();
+    parallel.run_parallel(20);
+
+    assert_eq!(positions(&parallel), positions(&serial));
+    assert_eq!(parallel.best().1.to_bits(), serial.best().1.to_bits());
+}
@@ -0,0 +1,60 @@
+//! The trace logging of `update`, in its own test binary since the logger is global.
+#![cfg(feature = "log")]
+
+extern crate log;
+extern crate meta_heuristics;
+extern crate rand;
+
+mod common;
+
+use meta_heuristics::pso;
+use common::Particle;
+
+#[test]
+fn update_logs() {
+    use std::sync::Mutex;
+    use log::{Level, LevelFilter, Log, Metadata, Record};
+
+    // Collects the messages logged at the trace level.
+    struct Capture(Mutex<Vec<String>>);
+
+    impl Log for Capture {
+        fn enabled(&self, metadata: &Metadata) -> bool {
+            metadata.level() == Level::Trace
+        }
+        fn log(&self, record: &Record) {
+            if self.enabled(record.metadata()) {
+                self.0.lock().unwrap().push(record.args().to_string());
+            }
+        }
+        fn flush(&self) {}
+    }
+
+    static CAPTURE: Capture = Capture(Mutex::new(Vec::new()));
+
+    log::set_logger(&CAPTURE).unwrap();
+    log::set_max_level(LevelFilter::Trace);
+
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.7, 1.4, 1.4, 42);
+    for _ in 0..3 {
+        pso.update();
+    }
+
+    {
+        let messages = CAPTURE.0.lock().unwrap();
+        assert_eq!(messages.len(), 3);
+        let expected = format!("iteration 3: 32 evaluations, stagnation {}", pso.stagnation());
+        assert_eq!(messages[2], expected);
+    }
+
+    pso.set_log_best_eval(true);
+    pso.update();
+
+    let messages = CAPTURE.0.lock().unwrap();
+    assert_eq!(messages.len(), 4);
+    let expected = format!("iteration 4: best eval {:?}, 40 evaluations, stagnation {}",
+                           pso.best().1,
+                           pso.stagnation());
+    assert_eq!(messages[3], expected);
+}
+
@@ -0,0 +1,2735 @@
+extern crate meta_heuristics;
+extern crate rand;
+#[cfg(feature = "serde")]
+extern crate serde_json;
+
+#[macro_use]
+mod common;
+
+use rand::{Closed01, Rng};
+
+use meta_heuristics::{pso, seeded_rng, Candidate, RandomCandidate, SeededCandidate};
+use common::{eval_func, Counting, Particle, P2};
+
+// Evaluated as its position.
+particle_maximizing!(Ramp, |x| x);
+// Peaked at 0.
+particle_maximizing!(Bowl, |x| -x * x);
+
+#[test]
+fn mode_cognitive_only() {
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use rand::{SeedableRng, XorShiftRng};
+
+    let draws = Rc::new(Cell::new(0));
+    let rng = Counting(XorShiftRng::from_seed([1, 2, 3, 4]), draws.clone());
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_with_rng(8, 0.0, 0.9, 0.9, rng);
+    pso.set_mode(pso::Mode::CognitiveOnly);
+
+    // Every particle starts at rest on its personal best, so without the social term nothing
+    // moves; one random number is drawn per particle and update.
+    let before: Vec<f64> = pso.particles().iter().map(|p| p.pos).collect();
+    for _ in 0..5 {
+        pso.update();
+    }
+    assert!(pso.particles().iter().zip(&before).all(|(p, &x)| p.pos == x));
+    assert_eq!(draws.get(), 5 * 8);
+
+    draws.set(0);
+    pso.set_mode(pso::Mode::Standard);
+    pso.update();
+    assert_eq!(draws.get(), 2 * 8);
+}
+
+#[test]
+fn mode_attraction() {
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(16, 0.7, 1.5, 1.5, 42);
+    pso.set_mode(pso::Mode::Attraction { rate: 0.5 });
+
+    // Every update halves each particle's distance to the best, so the spread of the swarm
+    // over [-1, 3] shrinks below 4 / 2^20.
+    for _ in 0..20 {
+        pso.update();
+    }
+    let best = pso.best().0.pos;
+    assert!(pso.particles().iter().all(|p| (p.pos - best).abs() < 1e-5));
+    assert!(pso.particles().iter().all(|p| p.vel == 0.0));
+}
+
+#[test]
+fn termination_criteria() {
+    use meta_heuristics::pso::{Any, All, DiversityBelow, MaxIters, TargetEval, Termination};
+    use rand::Isaac64Rng;
+
+    // Seeded swarms run with the generator of `seeded_rng`.
+    fn stop(target: f64, diversity: f64, iters: usize) -> Any<Particle, Isaac64Rng> {
+        let criteria: Vec<Box<dyn Termination<Particle, Isaac64Rng>>> =
+            vec![Box::new(TargetEval(target)),
+                 Box::new(DiversityBelow(diversity)),
+                 Box::new(MaxIters(iters))];
+        Any(criteria)
+    }
+
+    // The target is reached first.
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(24, 0.7, 1.4, 1.4, 42);
+    let iters = pso.run_until(&mut stop(1.6, -1.0, 1000));
+    assert!(iters < 1000 && pso.best().1 >= 1.6);
+
+    // The unreachable target never is, but the swarm collapses.
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.5, 0.9, 0.9, 42);
+    let iters = pso.run_until(&mut stop(2.0, 1e-3, 1000));
+    assert!(iters < 1000 && pso.diversity() < 1e-3);
+
+    // Only the iteration limit can stop the run.
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.7, 1.4, 1.4, 42);
+    assert_eq!(pso.run_until(&mut stop(2.0, -1.0, 5)), 5);
+
+    // Both criteria have to hold. The iteration limit includes the earlier updates.
+    let criteria: Vec<Box<dyn Termination<Particle, Isaac64Rng>>> =
+        vec![Box::new(MaxIters(8)), Box::new(TargetEval(f64::NEG_INFINITY))];
+    assert_eq!(pso.run_until(&mut All(criteria)), 3);
+    assert_eq!(pso.iteration(), 8);
+}
+
+#[test]
+fn termination_criteria_minimizing() {
+    use std::cmp::Ordering;
+    use meta_heuristics::pso::{Any, DiversityBelow, MaxIters, TargetEval, Termination};
+    use rand::Isaac64Rng;
+
+    particle_maximizing!(Square, |x| x * x);
+
+    fn stop(target: f64, iters: usize) -> Any<Square, Isaac64Rng> {
+        let criteria: Vec<Box<dyn Termination<Square, Isaac64Rng>>> =
+            vec![Box::new(TargetEval(target)),
+                 Box::new(DiversityBelow(-1.0)),
+                 Box::new(MaxIters(iters))];
+        Any(criteria)
+    }
+
+    fn minimizing() -> pso::PSO<Square, Isaac64Rng> {
+        let mut pso = pso::PSO::new_seeded(24, 0.7, 1.4, 1.4, 42);
+        pso.set_comparator(|a: &f64, b: &f64| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+        pso
+    }
+
+    // The target is reached once the best eval falls to it, not at once.
+    let mut pso = minimizing();
+    assert!(pso.best().1 > 1e-6);
+    let iters = pso.run_until(&mut stop(1e-6, 1000));
+    assert!(0 < iters && iters < 1000 && pso.best().1 <= 1e-6);
+
+    // A target below the minimum is never reached.
+    let mut pso = minimizing();
+    assert_eq!(pso.run_until(&mut stop(-1.0, 20)), 20);
+}
+
+#[test]
+fn from_iter_capped() {
+    use meta_heuristics::error::MetaHeuristicsError;
+
+    let grid = (0..).map(|i| {
+        let x = -1.0 + 0.5 * i as f64;
+        Particle { pos: x, vel: 0.0, best: (x, eval_func(x)) }
+    });
+    let pso = pso::PSO::from_iter_capped(grid, 8, 0.9, 0.9, 0.9).unwrap();
+    let positions: Vec<f64> = pso.particles().iter().map(|p| p.pos).collect();
+    assert_eq!(positions, [-1.0, -0.5, 0.0, 0.5, 1.0, 1.5, 2.0, 2.5]);
+
+    let pso = pso::PSO::from_iter_capped(std::iter::repeat_with(Particle::new_random),
+                                         8,
+                                         0.9,
+                                         0.9,
+                                         0.9);
+    assert_eq!(pso.unwrap().len(), 8);
+
+    let few = (0..3).map(|_| Particle::new_random());
+    assert_eq!(pso::PSO::from_iter_capped(few, 8, 0.9, 0.9, 0.9).unwrap_err(),
+               MetaHeuristicsError::InsufficientCandidates {
+                   required: 8,
+                   supplied: 3,
+               });
+
+    // A warm start whose first particle had found the global maximum before.
+    let resumed = (0..4).map(|i| {
+        let x = 0.1 * i as f64;
+        let best = if i == 0 { (1.64, eval_func(1.64)) } else { (x, eval_func(x)) };
+        Particle { pos: x, vel: 0.0, best }
+    });
+    let pso = pso::PSO::from_iter_capped(resumed, 4, 0.9, 0.9, 0.9).unwrap();
+    assert_eq!(pso.particles()[0].best, (1.64, eval_func(1.64)));
+    assert_eq!(pso.best().0.pos, 1.64);
+    assert_eq!(pso.best().1, eval_func(1.64));
+}
+
+#[test]
+fn new_in_region() {
+    // The optimum near 1.64 lies outside of the region.
+    let pso: pso::PSO<Particle> = pso::PSO::new_in_region(8, -1.0, 0.5, 0.9, 0.9, 0.9);
+    assert!(pso.particles().iter().all(|p| -1.0 <= p.pos && p.pos <= 0.5));
+}
+
+#[test]
+fn new_bounded() {
+    use std::ops;
+    use rand::{Closed01, Rng};
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct P2([f64; 2]);
+
+    impl P2 {
+        fn zip(self, rhs: P2, f: fn(f64, f64) -> f64) -> P2 {
+            P2([f(self.0[0], rhs.0[0]), f(self.0[1], rhs.0[1])])
+        }
+    }
+
+    impl ops::Add for P2 {
+        type Output = P2;
+        fn add(self, rhs: P2) -> P2 {
+            self.zip(rhs, |a, b| a + b)
+        }
+    }
+
+    impl ops::Sub for P2 {
+        type Output = P2;
+        fn sub(self, rhs: P2) -> P2 {
+            self.zip(rhs, |a, b| a - b)
+        }
+    }
+
+    impl ops::Mul<f64> for P2 {
+        type Output = P2;
+        fn mul(self, rhs: f64) -> P2 {
+            P2([self.0[0] * rhs, self.0[1] * rhs])
+        }
+    }
+
+    #[derive(Clone, Copy)]
+    struct Planar {
+        pos: P2,
+        vel: P2,
+        best: (P2, f64),
+    }
+
+    // Peaks at (5, -40), outside of the bounds below.
+    fn eval_func(x: &P2) -> f64 {
+        -(x.0[0] - 5.0).powi(2) - (x.0[1] + 40.0).powi(2)
+    }
+
+    impl Candidate for Planar {
+        type Pos = P2;
+        type Eval = f64;
+
+        fn eval(&self) -> f64 {
+            eval_func(&self.pos)
+        }
+        fn pos(&self) -> P2 {
+            self.pos
+        }
+        fn pos_mut(&mut self) -> &mut P2 {
+            &mut self.pos
+        }
+        fn dim(&self) -> usize {
+            2
+        }
+    }
+
+    impl RandomCandidate for Planar {
+        fn new_random() -> Self {
+            let (lower, upper) = (P2([0.0, -100.0]), P2([1.0, -50.0]));
+            <Self as pso::RegionSampling>::random_in(&lower, &upper, &mut rand::thread_rng())
+        }
+    }
+
+    impl pso::Particle for Planar {
+        fn vel(&self) -> P2 {
+            self.vel
+        }
+        fn best(&self) -> (P2, f64) {
+            self.best
+        }
+        fn vel_mut(&mut self) -> &mut P2 {
+            &mut self.vel
+        }
+        fn best_mut(&mut self) -> &mut (P2, f64) {
+            &mut self.best
+        }
+    }
+
+    impl pso::RegionSampling for Planar {
+        fn random_in<R: Rng>(lower: &P2, upper: &P2, rng: &mut R) -> Self {
+            let pos = P2([0, 1].map(|i| {
+                lower.0[i] + (upper.0[i] - lower.0[i]) * rng.gen::<Closed01<f64>>().0
+            }));
+            Self { pos, vel: P2([0.0; 2]), best: (pos, eval_func(&pos)) }
+        }
+    }
+
+    impl pso::ComponentBounds for Planar {
+        fn component_min(a: &P2, b: &P2) -> P2 {
+            a.zip(*b, f64::min)
+        }
+        fn component_max(a: &P2, b: &P2) -> P2 {
+            a.zip(*b, f64::max)
+        }
+    }
+
+    // A narrow first dimension and a wide, negative second one.
+    let (lower, upper) = (P2([0.0, -100.0]), P2([1.0, -50.0]));
+    let within = |x: &P2| {
+        (0..2).all(|i| lower.0[i] <= x.0[i] && x.0[i] <= upper.0[i])
+    };
+
+    let mut pso: pso::PSO<Planar> =
+        pso::PSO::new_bounded(20, lower, upper, 0.7, 1.4, 1.4);
+    assert_eq!(pso.bounds(), Some((lower, upper)));
+    assert!(pso.particles().iter().all(|p| within(&p.pos)));
+
+    for _ in 0..100 {
+        pso.update();
+        assert!(pso.particles().iter().all(|p| within(&p.pos)));
+    }
+    // Clamped to the upper bound of the second dimension, the one nearest to the peak.
+    assert_eq!(pso.best().0.pos.0[1], -50.0);
+}
+
+#[test]
+fn rng_state() {
+    let mut original: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.7, 1.4, 1.4, 42);
+    let mut captured: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.7, 1.4, 1.4, 42);
+    for _ in 0..10 {
+        original.update();
+        captured.update();
+    }
+
+    // Capture the run at iteration 10 and replay it from there.
+    let rng = captured.rng_state();
+    let mut replay = pso::PSO::from_state(captured.into_state(), rng);
+
+    for _ in 0..20 {
+        original.update();
+        replay.update();
+        for (a, b) in original.particles().iter().zip(replay.particles()) {
+            assert_eq!((a.pos, a.vel, a.best), (b.pos, b.vel, b.best));
+        }
+    }
+    assert_eq!(original.best().1, replay.best().1);
+}
+
+#[test]
+fn from_state() {
+    use std::cmp;
+
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.9, 0.9, 0.9, 42);
+    pso.set_topology(pso::Topology::Ring(1));
+    pso.set_mode(pso::Mode::SocialOnly);
+    pso.set_boundary_behavior(pso::BoundaryBehavior::Reflect);
+    pso.set_teleport(0.1).unwrap();
+    pso.set_linear_decay(50);
+    pso.set_elitism(true);
+    pso.freeze(3);
+    pso.set_parallel_threshold(100);
+    pso.set_comparator(|a: &f64, b: &f64| b.partial_cmp(a).unwrap_or(cmp::Ordering::Equal));
+    pso.update();
+
+    let state = pso.into_state();
+    let best = state.best.1;
+    assert_eq!(state.teleport, Some(0.1));
+    assert_eq!(state.decay_iters, Some(50));
+
+    let mut pso = pso::PSO::from_state(state, rand::thread_rng());
+    assert_eq!(pso.best().1, best);
+    assert_eq!(pso.iteration(), 1);
+    assert_eq!(pso.topology(), pso::Topology::Ring(1));
+    assert_eq!(pso.mode(), pso::Mode::SocialOnly);
+    assert_eq!(pso.boundary_behavior(), pso::BoundaryBehavior::Reflect);
+    assert!(pso.is_frozen(3));
+    assert_eq!(pso.parallel_threshold(), 100);
+
+    // The comparator is a closure, so the resumed swarm maximizes again.
+    let frozen = pso.particles()[3];
+    for _ in 0..20 {
+        pso.update();
+    }
+    assert_eq!(pso.particles()[3], frozen);
+    assert!(pso.best().1 >= best);
+}
+
+#[test]
+fn from_state_with_sampler() {
+    let pso: pso::PSO<Particle> =
+        pso::PSO::new_with_sampler(4, || Particle::at(0.5), 0.9, 0.9, 0.9);
+    let state = pso.into_state();
+
+    // The restarted particles come from the sampler rather than `RandomCandidate`.
+    let sampler = || Particle::at(2.0);
+    let mut pso = pso::PSO::from_state_with_sampler(state, sampler, rand::thread_rng());
+    assert!(pso.particles().iter().all(|p| p.pos == 0.5));
+
+    pso.restart(0);
+    assert!(pso.particles().iter().all(|p| p.pos == 2.0));
+}
+
+#[test]
+fn update_draws() {
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use rand::{SeedableRng, XorShiftRng};
+
+    let draws = Rc::new(Cell::new(0));
+    let rng = Counting(XorShiftRng::from_seed([1, 2, 3, 4]), draws.clone());
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_with_rng(8, 0.7, 1.4, 1.4, rng);
+    assert_eq!(draws.get(), 0);
+
+    for _ in 0..10 {
+        pso.update();
+    }
+    assert_eq!(draws.get(), 10 * 2 * 8);
+
+    // Even flights which are never taken draw their probability.
+    draws.set(0);
+    pso.set_levy(0.0, 1.5).unwrap();
+    pso.update();
+    assert_eq!(draws.get(), 3 * 8);
+
+    draws.set(0);
+    pso.unset_levy();
+    pso.set_mode(pso::Mode::Attraction { rate: 0.5 });
+    pso.update();
+    assert_eq!(draws.get(), 0);
+}
+
+#[test]
+fn try_update() {
+    use std::error::Error;
+    use std::fmt;
+
+    #[derive(Debug)]
+    struct Diverged;
+
+    impl fmt::Display for Diverged {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "the simulation diverged")
+        }
+    }
+
+    impl Error for Diverged {}
+
+    #[derive(Clone, Copy)]
+    struct Fragile {
+        pos: f64,
+        vel: f64,
+        best: (f64, f64),
+    }
+
+    impl Candidate for Fragile {
+        type Pos = f64;
+        type Eval = f64;
+
+        fn eval(&self) -> f64 {
+            -self.pos * self.pos
+        }
+        // The simulation behind the objective diverges beyond 1.5.
+        fn try_eval(&self) -> Result<f64, Box<dyn Error>> {
+            if self.pos > 1.5 {
+                Err(Box::new(Diverged))
+            } else {
+                Ok(self.eval())
+            }
+        }
+        fn pos(&self) -> f64 {
+            self.pos
+        }
+        fn pos_mut(&mut self) -> &mut f64 {
+            &mut self.pos
+        }
+    }
+
+    impl RandomCandidate for Fragile {
+        fn new_random() -> Self {
+            Self { pos: 0.0, vel: 1.0, best: (0.0, 0.0) }
+        }
+    }
+
+    impl pso::Particle for Fragile {
+        fn vel(&self) -> f64 {
+            self.vel
+        }
+        fn best(&self) -> (f64, f64) {
+            self.best
+        }
+        fn vel_mut(&mut self) -> &mut f64 {
+            &mut self.vel
+        }
+        fn best_mut(&mut self) -> &mut (f64, f64) {
+            &mut self.best
+        }
+    }
+
+    // Without any attraction, every particle moves by its velocity of 1 per update, and
+    // the inertia is kept at 1 for the first update.
+    let mut pso: pso::PSO<Fragile> = pso::PSO::new(4, 1.0, 0.0, 0.0);
+    pso.set_inertia_fn(|ctx| if ctx.iteration() == 0 { 1.0 } else { 0.5 });
+
+    assert!(pso.try_update().is_ok());
+    assert!(pso.particles().iter().all(|p| p.pos == 1.0));
+
+    let err = pso.try_update().unwrap_err();
+    assert_eq!(err.to_string(), "the simulation diverged");
+    assert_eq!(pso.iteration(), 1);
+    assert!(pso.particles().iter().all(|p| p.pos == 1.0 && p.vel == 1.0));
+    assert_eq!(pso.config().inertia, 1.0);
+    assert_eq!(pso.eval_count(), 4 + 4 + 1);
+}
+
+#[test]
+fn run() {
+    use std::ops::ControlFlow;
+
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.9, 0.9, 0.9, 42);
+    let mut restarts = 0;
+
+    let iters = pso.run(100, |pso| {
+        if pso.stagnation() >= 3 {
+            pso.restart(1);
+            restarts += 1;
+        }
+        ControlFlow::Continue(())
+    });
+
+    assert_eq!((iters, pso.iteration()), (100, 100));
+    assert!(restarts > 0);
+
+    // Stop once the best eval exceeds a threshold.
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.9, 0.9, 0.9, 42);
+    let threshold = 0.9;
+    let iters = pso.run(100, |pso| if pso.best().1 > threshold {
+        ControlFlow::Break(())
+    } else {
+        ControlFlow::Continue(())
+    });
+
+    assert!(pso.best().1 > threshold);
+    assert_eq!(iters, pso.iteration());
+}
+
+#[test]
+fn run_until_pred() {
+    // Stop once the best lies in the basin of the global maximum, whatever its eval.
+    let in_basin = |p: &Particle, _: &f64| p.pos > 1.0 && p.pos < 2.0;
+
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(16, 0.7, 1.4, 1.4, 42);
+    let iters = pso.run_until_pred(200, in_basin).unwrap();
+    assert_eq!(pso.iteration(), iters);
+    assert!(in_basin(&pso.best().0, &pso.best().1));
+
+    // A condition the swarm cannot meet runs into the cap.
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(16, 0.7, 1.4, 1.4, 42);
+    assert_eq!(pso.run_until_pred(20, |p, _| p.pos > 10.0), None);
+    assert_eq!(pso.iteration(), 20);
+}
+
+#[test]
+fn improved_mask() {
+    // Without attraction, the particle drifting up the eval `pos` improves every update and
+    // the one at rest never does.
+    let mut vels = vec![0.0, 1.0];
+    let sampler = move || {
+        let vel = vels.pop().unwrap();
+        Ramp { pos: 0.0, vel, best: (0.0, 0.0) }
+    };
+    let mut pso = pso::PSO::new_with_sampler(2, sampler, 1.0, 0.0, 0.0);
+    assert_eq!(pso.improved_mask(), &[false, false]);
+
+    for _ in 0..3 {
+        pso.update();
+        assert_eq!(pso.improved_mask(), &[true, false]);
+    }
+}
+
+#[test]
+fn restart() {
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.9, 0.9, 0.9, 42);
+    pso.update();
+
+    let before: Vec<f64> = pso.particles().iter().map(|p| p.pos).collect();
+    pso.restart(3);
+
+    let kept = pso.particles().iter().filter(|p| before.contains(&p.pos)).count();
+    assert_eq!(kept, 3);
+}
+
+#[test]
+fn reset() {
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.9, 0.9, 0.9, 42);
+    pso.set_topology(pso::Topology::Ring(1));
+    for _ in 0..10 {
+        pso.update();
+    }
+
+    let config = pso.config();
+    let before: Vec<f64> = pso.particles().iter().map(|p| p.pos).collect();
+    pso.reset();
+
+    assert_eq!(pso.config(), config);
+    assert_eq!(pso.topology(), pso::Topology::Ring(1));
+    assert_eq!((pso.iteration(), pso.eval_count(), pso.stagnation()), (0, 8, 0));
+    assert!(pso.particles().iter().all(|p| !before.contains(&p.pos)));
+}
+
+#[test]
+fn reset_and_keep_archive() {
+    // Each trial starts its particles at rest at one position: near the maximum at 1.64
+    // in the first trial, and further away in the others.
+    let swarm = || {
+        let mut trials = vec![1.6, 0.5, 1.0].into_iter().flat_map(|x| vec![x; 4]);
+        let sampler = move || Particle::at(trials.next().unwrap());
+        pso::PSO::new_with_sampler(4, sampler, 0.0, 0.0, 0.0)
+    };
+
+    let mut pso = swarm();
+    let mut trial_bests = Vec::new();
+    for trial in 0..3 {
+        if trial > 0 {
+            pso.reset_and_keep_archive();
+        }
+        for _ in 0..5 {
+            pso.update();
+        }
+        trial_bests.push(pso.best().1);
+    }
+
+    assert!(trial_bests[1] < trial_bests[0]);
+    let (archived, &e) = pso.archive().best().unwrap();
+    assert_eq!((archived.pos, e), (1.6, trial_bests[0]));
+
+    // With elitism, `best` reports the archived best across the trials, and
+    // `reset_and_clear_archive` starts the archive over.
+    let mut pso = swarm();
+    pso.set_elitism(true);
+    pso.reset_and_keep_archive();
+    assert_eq!(pso.best().0.pos, 1.6);
+    pso.reset_and_clear_archive();
+    assert_eq!(pso.best().0.pos, 1.0);
+    assert_eq!(pso.archive().best().map(|(p, _)| p.pos), Some(1.0));
+}
+
+#[test]
+fn reset_with_seed() {
+    use rand::{SeedableRng, StdRng};
+
+    let rng = StdRng::from_seed(&[1][..]);
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_with_rng(8, 0.7, 1.4, 1.4, rng);
+
+    let mut trial = || {
+        pso.reset_with_seed(&[7][..]);
+        let initial: Vec<f64> = pso.particles().iter().map(|p| p.pos).collect();
+        for _ in 0..10 {
+            pso.update();
+        }
+        (initial, pso.best().1)
+    };
+    assert_eq!(trial(), trial());
+}
+
+#[test]
+fn set_teleport() {
+    // Without inertia or attraction the particles stay put, unless teleported.
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.0, 0.0, 0.0, 42);
+    assert!(pso.set_teleport(1.5).is_err());
+    pso.set_teleport(1.0).unwrap();
+
+    for _ in 0..20 {
+        let before: Vec<f64> = pso.particles().iter().map(|p| p.pos).collect();
+        let worst = pso.worst().0.pos;
+        pso.update();
+
+        let moved: Vec<f64> = before
+            .into_iter()
+            .filter(|&x| pso.particles().iter().all(|p| p.pos != x))
+            .collect();
+        assert_eq!(moved, vec![worst]);
+    }
+    assert_eq!(pso.eval_count(), 8 + 20 * 9);
+    assert!(pso.particles().iter().all(|p| p.best == (p.pos, eval_func(p.pos))));
+}
+
+#[test]
+fn freeze() {
+    // Every particle drifts by its velocity of 1 per update, but the pinned one.
+    let sampler = || Particle { pos: 0.0, vel: 1.0, best: (0.0, eval_func(0.0)) };
+    let mut pso = pso::PSO::new_with_sampler(4, sampler, 1.0, 0.0, 0.0);
+    pso.freeze(2);
+    assert!(pso.is_frozen(2));
+
+    for _ in 0..3 {
+        pso.update();
+    }
+    let positions: Vec<f64> = pso.particles().iter().map(|p| p.pos).collect();
+    assert_eq!(positions, [3.0, 3.0, 0.0, 3.0]);
+
+    pso.unfreeze(2);
+    pso.update();
+    assert_eq!(pso.particles()[2].pos, 1.0);
+}
+
+#[test]
+fn remove_worst() {
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.9, 0.9, 0.9, 42);
+    pso.add_particles(4);
+    assert_eq!(pso.particles().len(), 12);
+
+    let best = pso.best().1;
+    pso.remove_worst(6);
+    assert_eq!(pso.particles().len(), 6);
+    assert_eq!(pso.best().1, best);
+
+    pso.remove_worst(100);
+    assert_eq!(pso.particles().len(), 1);
+    assert_eq!(pso.best().1, best);
+}
+
+#[test]
+fn reinit_stale() {
+    let mut pso: pso::PSO<Particle> = pso::PSO::new(8, 0.9, 0.9, 0.9);
+
+    // A personal best no position can reach keeps this particle from ever improving.
+    pso.set_particle(0, Particle { pos: 0.5, vel: 0.0, best: (0.5, 100.0) });
+    let stuck = |pso: &pso::PSO<Particle>| pso.particles().iter().any(|p| p.best.1 == 100.0);
+
+    pso.update();
+    pso.update();
+    pso.reinit_stale(3);
+    assert!(stuck(&pso));
+
+    pso.update();
+    assert!(pso.reinit_stale(3) >= 1);
+    assert!(!stuck(&pso));
+    assert_eq!(pso.particles().len(), 8);
+}
+
+#[test]
+fn set_global_best() {
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.9, 0.9, 0.9, 42);
+    let (mut refined, _) = pso.best();
+
+    refined.pos = -1.0;
+    assert!(!pso.set_global_best(refined, false));
+    assert!(pso.set_global_best(refined, true));
+    assert_eq!(pso.best().1, eval_func(-1.0));
+
+    refined.pos = 1.64;
+    assert!(pso.set_global_best(refined, false));
+    assert_eq!(pso.best().1, eval_func(1.64));
+
+    // In a swarm at rest, no particle beats the injected peak, so it is kept.
+    let peak = (9.0 + 17f64.sqrt()) / 8.0;
+    refined.pos = peak;
+    let mut still: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.0, 0.0, 0.0, 42);
+    still.set_global_best(refined, false);
+    for _ in 0..3 {
+        still.update();
+    }
+    assert_eq!(still.best().0.pos, peak);
+
+    // A forced worse best is beaten, and replaced, by the next update.
+    refined.pos = -1.0;
+    still.set_global_best(refined, true);
+    still.update();
+    assert!(still.best().0.pos > -1.0);
+}
+
+#[test]
+fn set_personal_best() {
+    // A lone particle at rest in the local maximum at 0, pulled only by its personal best.
+    let sampler = || Particle { pos: 0.0, vel: 0.0, best: (0.0, eval_func(0.0)) };
+    let mut pso = pso::PSO::new_with_sampler(1, sampler, 0.0, 1.0, 0.0);
+    pso.update();
+    assert_eq!(pso.particles()[0].vel, 0.0);
+
+    pso.set_personal_best(0, 1.64, eval_func(1.64));
+    assert_eq!(pso.particles()[0].best, (1.64, eval_func(1.64)));
+    assert_eq!(pso.best().1, eval_func(1.64));
+
+    // The next velocity update heads for the injected personal best.
+    pso.update();
+    let vel = pso.particles()[0].vel;
+    assert!(vel > 0.0 && vel <= 1.64);
+}
+
+#[test]
+fn set_inertia_fn() {
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.9, 0.9, 0.9, 42);
+    pso.set_inertia_fn(|ctx| if ctx.iteration() % 2 == 0 {
+        0.9
+    } else {
+        0.4
+    });
+
+    for i in 0..6 {
+        pso.update();
+        assert_eq!(pso.config().inertia, if i % 2 == 0 { 0.9 } else { 0.4 });
+    }
+}
+
+#[test]
+fn set_boundary_behavior() {
+    use pso::BoundaryBehavior;
+
+    // Without any attraction, the particle at 0.9 moves by its velocity of 5 out of [0, 1].
+    let stray = Particle { pos: 0.9, vel: 5.0, best: (0.9, eval_func(0.9)) };
+    let run = |behavior| {
+        let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(4, 1.0, 0.0, 0.0, 42);
+        pso.set_bounds(0.0, 1.0);
+        pso.set_boundary_behavior(behavior);
+        pso.set_particle(0, stray);
+        pso.update();
+        pso.particles()[0]
+    };
+
+    let clamped = run(BoundaryBehavior::Clamp);
+    assert_eq!((clamped.pos, clamped.vel), (1.0, 5.0));
+
+    // Mirrored at 1 to -3.9, beyond the lower bound, and clamped to it.
+    let reflected = run(BoundaryBehavior::Reflect);
+    assert_eq!((reflected.pos, reflected.vel), (0.0, 5.0));
+}
+
+#[test]
+fn set_repair() {
+    // Weights on the unit simplex, `x >= 0` and `x[0] + x[1] == 1`, best at (0.3, 0.7).
+    fn eval_func(x: &P2) -> f64 {
+        -(x.0[0] - 0.3).powi(2) - (x.0[1] - 0.7).powi(2)
+    }
+
+    #[derive(Clone, Copy)]
+    struct Weights {
+        pos: P2,
+        vel: P2,
+        best: (P2, f64),
+    }
+
+    impl Candidate for Weights {
+        type Pos = P2;
+        type Eval = f64;
+
+        fn eval(&self) -> f64 {
+            eval_func(&self.pos)
+        }
+        fn pos(&self) -> P2 {
+            self.pos
+        }
+        fn pos_mut(&mut self) -> &mut P2 {
+            &mut self.pos
+        }
+        // Projects onto the simplex by dropping the negative weights and normalizing.
+        fn repair(&mut self) {
+            let x = [self.pos.0[0].max(0.0), self.pos.0[1].max(0.0)];
+            let sum = x[0] + x[1];
+            self.pos = if sum > 0.0 {
+                P2([x[0] / sum, x[1] / sum])
+            } else {
+                P2([0.5, 0.5])
+            };
+        }
+        fn dim(&self) -> usize {
+            2
+        }
+    }
+
+    impl RandomCandidate for Weights {
+        fn new_random() -> Self {
+            Self::new_random_seeded(rand::random())
+        }
+    }
+
+    impl SeededCandidate for Weights {
+        fn new_random_seeded(seed: u64) -> Self {
+        
+            let mut rng = seeded_rng(seed);
+            let Closed01(x) = rng.gen::<Closed01<f64>>();
+            let pos = P2([x, 1.0 - x]);
+            Self { pos, vel: P2([0.0; 2]), best: (pos, eval_func(&pos)) }
+        }
+    }
+
+    impl pso::Particle for Weights {
+        fn vel(&self) -> P2 {
+            self.vel
+        }
+        fn best(&self) -> (P2, f64) {
+            self.best
+        }
+        fn vel_mut(&mut self) -> &mut P2 {
+            &mut self.vel
+        }
+        fn best_mut(&mut self) -> &mut (P2, f64) {
+            &mut self.best
+        }
+    }
+
+    let mut pso: pso::PSO<Weights, _> = pso::PSO::new_seeded(16, 0.7, 1.5, 1.5, 42);
+    pso.set_repair(true);
+
+    for _ in 0..50 {
+        pso.update();
+        for p in pso.particles() {
+            assert!(p.pos.0.iter().all(|&x| x >= 0.0));
+            assert!((p.pos.0[0] + p.pos.0[1] - 1.0).abs() < 1e-12);
+        }
+    }
+    assert!((pso.best().0.pos.0[0] - 0.3).abs() < 1e-3);
+}
+
+#[test]
+fn set_validate() {
+    // Normalized weights, `x >= 0` and `x[0] + x[1] == 1`, best at the corner (0, 1).
+    fn eval_func(x: &P2) -> f64 {
+        x.0[1]
+    }
+
+    #[derive(Clone, Copy)]
+    struct Normalized {
+        pos: P2,
+        vel: P2,
+        best: (P2, f64),
+    }
+
+    impl Candidate for Normalized {
+        type Pos = P2;
+        type Eval = f64;
+
+        fn eval(&self) -> f64 {
+            eval_func(&self.pos)
+        }
+        fn pos(&self) -> P2 {
+            self.pos
+        }
+        fn pos_mut(&mut self) -> &mut P2 {
+            &mut self.pos
+        }
+        // Moves between normalized positions keep the sum, but can overshoot the corner.
+        fn is_valid(&self) -> bool {
+            let x = self.pos.0;
+            x[0] >= 0.0 && x[1] >= 0.0 && (x[0] + x[1] - 1.0).abs() < 1e-9
+        }
+    }
+
+    impl RandomCandidate for Normalized {
+        fn new_random() -> Self {
+            Self::new_random_seeded(rand::random())
+        }
+    }
+
+    impl SeededCandidate for Normalized {
+        fn new_random_seeded(seed: u64) -> Self {
+        
+            let mut rng = seeded_rng(seed);
+            let Closed01(x) = rng.gen::<Closed01<f64>>();
+            let pos = P2([x, 1.0 - x]);
+            Self { pos, vel: P2([0.0; 2]), best: (pos, eval_func(&pos)) }
+        }
+    }
+
+    impl pso::Particle for Normalized {
+        fn vel(&self) -> P2 {
+            self.vel
+        }
+        fn best(&self) -> (P2, f64) {
+            self.best
+        }
+        fn vel_mut(&mut self) -> &mut P2 {
+            &mut self.vel
+        }
+        fn best_mut(&mut self) -> &mut (P2, f64) {
+            &mut self.best
+        }
+    }
+
+    let mut pso: pso::PSO<Normalized, _> = pso::PSO::new_seeded(16, 0.7, 1.5, 1.5, 42);
+    pso.set_validate(true);
+
+    for _ in 0..100 {
+        pso.update();
+        assert!(pso.particles().iter().all(|p| p.is_valid()));
+    }
+    assert!(pso.best().1 > 0.99);
+}
+
+#[test]
+fn bounded() {
+    use meta_heuristics::bench;
+    use meta_heuristics::pso::BoundaryBehavior;
+
+    #[derive(Clone, Copy)]
+    struct Rastrigin {
+        pos: P2,
+        vel: P2,
+        best: (P2, f64),
+    }
+
+    impl Candidate for Rastrigin {
+        type Pos = P2;
+        type Eval = f64;
+
+        fn eval(&self) -> f64 {
+            bench::rastrigin(&self.pos.0)
+        }
+        fn pos(&self) -> P2 {
+            self.pos
+        }
+        fn pos_mut(&mut self) -> &mut P2 {
+            &mut self.pos
+        }
+        fn dim(&self) -> usize {
+            2
+        }
+    }
+
+    impl RandomCandidate for Rastrigin {
+        fn new_random() -> Self {
+            Self::new_random_seeded(rand::random())
+        }
+    }
+
+    impl SeededCandidate for Rastrigin {
+        fn new_random_seeded(seed: u64) -> Self {
+        
+            let mut rng = seeded_rng(seed);
+            let mut coord = || 10.24 * rng.gen::<Closed01<f64>>().0 - 5.12;
+            let pos = P2([coord(), coord()]);
+            Self { pos, vel: P2([0.0; 2]), best: (pos, bench::rastrigin(&pos.0)) }
+        }
+    }
+
+    impl pso::Particle for Rastrigin {
+        fn vel(&self) -> P2 {
+            self.vel
+        }
+        fn best(&self) -> (P2, f64) {
+            self.best
+        }
+        fn vel_mut(&mut self) -> &mut P2 {
+            &mut self.vel
+        }
+        fn best_mut(&mut self) -> &mut (P2, f64) {
+            &mut self.best
+        }
+    }
+
+    impl pso::ComponentBounds for Rastrigin {
+        fn component_min(a: &P2, b: &P2) -> P2 {
+            a.zip(*b, f64::min)
+        }
+        fn component_max(a: &P2, b: &P2) -> P2 {
+            a.zip(*b, f64::max)
+        }
+    }
+
+    let (lower, upper) = (P2([-5.12; 2]), P2([5.12; 2]));
+    let mut pso: pso::PSO<Rastrigin, _> =
+        pso::PSO::new_seeded(40, 0.7298, 1.49618, 1.49618, 42);
+
+    assert!(pso.bounded(lower, upper, -0.5, BoundaryBehavior::Reflect).is_err());
+    assert_eq!(pso.bounds(), None);
+
+    pso.bounded(lower, upper, pso::DEFAULT_VELOCITY_FRACTION, BoundaryBehavior::Reflect)
+        .unwrap();
+    assert_eq!(pso.velocity_limit(), Some(P2([2.048; 2])));
+    assert_eq!(pso.boundary_behavior(), BoundaryBehavior::Reflect);
+
+    for _ in 0..200 {
+        pso.update();
+        for p in pso.particles() {
+            assert!(p.pos.0.iter().all(|x| x.abs() <= 5.12));
+            assert!(p.vel.0.iter().all(|v| v.abs() <= 2.048));
+        }
+    }
+    // At the optimum 0 at the origin, or at worst in one of the nearest local optima at
+    // about -0.995, out of the dozens within the bounds.
+    assert!(pso.best().1 > -1.0);
+}
+
+#[test]
+fn set_fitness_sharing() {
+    #[derive(Clone, Copy)]
+    struct TwinPeaks { pos: f64, vel: f64, best: (f64, f64) }
+
+    // Two equally high peaks, at -2 and 2.
+    fn eval_func(x: f64) -> f64 {
+        (-4.0 * (x - 2.0).powi(2)).exp() + (-4.0 * (x + 2.0).powi(2)).exp() + 0.01
+    }
+
+    impl Candidate for TwinPeaks {
+        type Pos = f64;
+        type Eval = f64;
+
+        fn eval(&self) -> f64 { eval_func(self.pos) }
+        fn pos(&self) -> f64 { self.pos }
+        fn pos_mut(&mut self) -> &mut f64 { &mut self.pos }
+    }
+
+    impl RandomCandidate for TwinPeaks {
+        fn new_random() -> Self {
+            Self::new_random_seeded(rand::random())
+        }
+    }
+
+    impl SeededCandidate for TwinPeaks {
+        fn new_random_seeded(seed: u64) -> Self {
+            let mut rng = seeded_rng(seed);
+            let Closed01(x) = rng.gen::<Closed01<f64>>();
+            let x = 6.0 * x - 3.0;
+            Self { pos: x, vel: 0.0, best: (x, eval_func(x)) }
+        }
+    }
+
+    impl pso::Particle for TwinPeaks {
+        fn vel(&self) -> f64 { self.vel }
+        fn best(&self) -> (f64, f64) { self.best }
+        fn vel_mut(&mut self) -> &mut f64 { &mut self.vel }
+        fn best_mut(&mut self) -> &mut (f64, f64) { &mut self.best }
+    }
+
+    impl pso::Metric for TwinPeaks {
+        fn pos_distance(a: &f64, b: &f64) -> f64 { (a - b).abs() }
+    }
+
+    let mut pso: pso::PSO<TwinPeaks, _> = pso::PSO::new_seeded(30, 0.5, 1.0, 1.0, 42);
+    assert!(pso.set_fitness_sharing(0.0).is_err());
+
+    pso.set_fitness_sharing(1.0).unwrap();
+    pso.set_topology(pso::Topology::Ring(1));
+    for _ in 0..100 {
+        pso.update();
+    }
+
+    let near = |peak: f64| {
+        pso.particles().iter().filter(|p| (p.best.0 - peak).abs() < 0.3).count()
+    };
+    assert!(near(-2.0) > 0);
+    assert!(near(2.0) > 0);
+}
+
+#[test]
+fn set_fitness_sharing_checks() {
+    use meta_heuristics::error::MetaHeuristicsError;
+
+    // The objective is 1 at 0, and -5 at -1.
+    let mut pso = pso::PSO::new_with_sampler(4, || Particle::at(-1.0), 0.7, 1.4, 1.4);
+    assert_eq!(pso.set_fitness_sharing(1.0), Err(MetaHeuristicsError::NonPositiveEval));
+
+    let mut pso = pso::PSO::new_with_sampler(4, || Particle::at(0.0), 0.7, 1.4, 1.4);
+    pso.set_comparator(|a: &f64, b: &f64| b.partial_cmp(a).unwrap());
+    assert_eq!(pso.set_fitness_sharing(1.0),
+               Err(MetaHeuristicsError::IncompatibleSettings {
+                       setting: "fitness sharing",
+                       with: "a comparator",
+                   }));
+    pso.unset_comparator();
+    assert!(pso.set_fitness_sharing(1.0).is_ok());
+}
+
+#[test]
+#[should_panic(expected = "fitness sharing needs positive evals")]
+fn set_fitness_sharing_non_positive() {
+    // Without any attraction, the particles coast from 0 to -1.
+    let sampler = || Particle { pos: 0.0, vel: -1.0, best: (0.0, eval_func(0.0)) };
+    let mut pso = pso::PSO::new_with_sampler(4, sampler, 1.0, 0.0, 0.0);
+    pso.set_fitness_sharing(1.0).unwrap();
+    pso.update();
+}
+
+#[test]
+fn enable_eval_cache() {
+    use meta_heuristics::discrete::IntPos;
+
+    #[derive(Clone)]
+    struct Lattice {
+        pos: IntPos,
+        vel: IntPos,
+        best: (IntPos, i64),
+    }
+
+    // An integer quadratic peaking at (3, -2).
+    fn eval_func(x: &IntPos) -> i64 {
+        -(x.0[0] - 3).pow(2) - (x.0[1] + 2).pow(2)
+    }
+
+    impl Candidate for Lattice {
+        type Pos = IntPos;
+        type Eval = i64;
+
+        fn eval(&self) -> i64 {
+            eval_func(&self.pos)
+        }
+        fn pos(&self) -> IntPos {
+            self.pos.clone()
+        }
+        fn pos_mut(&mut self) -> &mut IntPos {
+            &mut self.pos
+        }
+    }
+
+    impl RandomCandidate for Lattice {
+        fn new_random() -> Self {
+            Self::new_random_seeded(rand::random())
+        }
+    }
+
+    impl SeededCandidate for Lattice {
+        fn new_random_seeded(seed: u64) -> Self {
+            let mut rng = seeded_rng(seed);
+            let pos = IntPos((0..2).map(|_| rng.gen_range(-10, 11)).collect());
+            let vel = IntPos((0..2).map(|_| rng.gen_range(-2, 3)).collect());
+            let e = eval_func(&pos);
+            Self { pos: pos.clone(), vel, best: (pos, e) }
+        }
+    }
+
+    impl pso::Particle for Lattice {
+        fn vel(&self) -> IntPos {
+            self.vel.clone()
+        }
+        fn best(&self) -> (IntPos, i64) {
+            self.best.clone()
+        }
+        fn vel_mut(&mut self) -> &mut IntPos {
+            &mut self.vel
+        }
+        fn best_mut(&mut self) -> &mut (IntPos, i64) {
+            &mut self.best
+        }
+    }
+
+    let mut pso: pso::PSO<Lattice, _> = pso::PSO::new_seeded(24, 0.7, 1.5, 1.5, 42);
+    pso.enable_eval_cache();
+    for _ in 0..100 {
+        pso.update();
+    }
+
+    // The converged swarm keeps landing on the positions it already visited.
+    assert!(pso.eval_cache_hits() > 0);
+    assert_eq!(pso.eval_count() + pso.eval_cache_hits(), 24 + 100 * 24);
+    assert_eq!(pso.best().1, 0);
+}
+
+#[test]
+fn grow_dimension() {
+    use std::ops;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct VecPos(Vec<f64>);
+
+    impl ops::Add for VecPos {
+        type Output = VecPos;
+        fn add(self, rhs: VecPos) -> VecPos {
+            VecPos(self.0.iter().zip(&rhs.0).map(|(a, b)| a + b).collect())
+        }
+    }
+
+    impl ops::Sub for VecPos {
+        type Output = VecPos;
+        fn sub(self, rhs: VecPos) -> VecPos {
+            VecPos(self.0.iter().zip(&rhs.0).map(|(a, b)| a - b).collect())
+        }
+    }
+
+    impl ops::Mul<f64> for VecPos {
+        type Output = VecPos;
+        fn mul(self, rhs: f64) -> VecPos {
+            VecPos(self.0.iter().map(|a| a * rhs).collect())
+        }
+    }
+
+    #[derive(Clone)]
+    struct Growing {
+        pos: VecPos,
+        vel: VecPos,
+        best: (VecPos, f64),
+    }
+
+    // Peaks at 1 in every dimension, however many there are.
+    fn eval_func(x: &VecPos) -> f64 {
+        -x.0.iter().map(|x| (x - 1.0).powi(2)).sum::<f64>()
+    }
+
+    impl Candidate for Growing {
+        type Pos = VecPos;
+        type Eval = f64;
+
+        fn eval(&self) -> f64 {
+            eval_func(&self.pos)
+        }
+        fn pos(&self) -> VecPos {
+            self.pos.clone()
+        }
+        fn pos_mut(&mut self) -> &mut VecPos {
+            &mut self.pos
+        }
+        fn dim(&self) -> usize {
+            self.pos.0.len()
+        }
+    }
+
+    impl RandomCandidate for Growing {
+        fn new_random() -> Self {
+            Self::new_random_seeded(rand::random())
+        }
+    }
+
+    impl SeededCandidate for Growing {
+        fn new_random_seeded(seed: u64) -> Self {
+        
+            let mut rng = seeded_rng(seed);
+            let pos = VecPos((0..2).map(|_| 4.0 * rng.gen::<Closed01<f64>>().0 - 2.0)
+                                   .collect());
+            let e = eval_func(&pos);
+            Self { pos: pos.clone(), vel: VecPos(vec![0.0; 2]), best: (pos, e) }
+        }
+    }
+
+    impl pso::Particle for Growing {
+        fn vel(&self) -> VecPos {
+            self.vel.clone()
+        }
+        fn best(&self) -> (VecPos, f64) {
+            self.best.clone()
+        }
+        fn vel_mut(&mut self) -> &mut VecPos {
+            &mut self.vel
+        }
+        fn best_mut(&mut self) -> &mut (VecPos, f64) {
+            &mut self.best
+        }
+    }
+
+    impl pso::GrowDimension for Growing {
+        fn push_dimension<R: Rng>(&mut self, value: f64, rng: &mut R) {
+            self.pos.0.push(value);
+            self.vel.0.push(rng.gen::<Closed01<f64>>().0 - 0.5);
+        }
+    }
+
+    let mut pso: pso::PSO<Growing, _> = pso::PSO::new_seeded(24, 0.7, 1.4, 1.4, 42);
+    for _ in 0..20 {
+        pso.update();
+    }
+    assert!(pso.particles().iter().all(|p| p.dim() == 2));
+
+    pso.grow_dimension(0.0);
+    for p in pso.particles() {
+        assert_eq!(p.dim(), 3);
+        assert_eq!(p.pos.0[2], 0.0);
+        assert_eq!(p.best, (p.pos.clone(), eval_func(&p.pos)));
+    }
+    assert_eq!(pso.best().0.dim(), 3);
+
+    for _ in 0..200 {
+        pso.update();
+    }
+    assert!(pso.best().1 > -1e-2);
+}
+
+#[test]
+fn set_parallel_threshold() {
+    use std::sync::Mutex;
+    use std::thread::{self, ThreadId};
+
+    // The threads which evaluated particles.
+    static EVALUATORS: Mutex<Vec<ThreadId>> = Mutex::new(Vec::new());
+
+    #[derive(Clone, Copy)]
+    struct Traced(Particle);
+
+    impl Candidate for Traced {
+        type Pos = f64;
+        type Eval = f64;
+
+        fn eval(&self) -> f64 {
+            EVALUATORS.lock().unwrap().push(thread::current().id());
+            self.0.eval()
+        }
+
+        fn pos(&self) -> f64 {
+            self.0.pos
+        }
+
+        fn pos_mut(&mut self) -> &mut f64 {
+            &mut self.0.pos
+        }
+    }
+
+    impl RandomCandidate for Traced {
+        fn new_random() -> Self {
+            Traced(Particle::new_random())
+        }
+    }
+
+    impl pso::Particle for Traced {
+        fn vel(&self) -> f64 {
+            self.0.vel
+        }
+
+        fn best(&self) -> (f64, f64) {
+            self.0.best
+        }
+
+        fn vel_mut(&mut self) -> &mut f64 {
+            &mut self.0.vel
+        }
+
+        fn best_mut(&mut self) -> &mut (f64, f64) {
+            &mut self.0.best
+        }
+    }
+
+    let evaluators = |pso: &mut pso::PSO<Traced>| {
+        EVALUATORS.lock().unwrap().clear();
+        pso.update();
+        EVALUATORS.lock().unwrap().clone()
+    };
+    let me = thread::current().id();
+
+    let mut small: pso::PSO<Traced> = pso::PSO::new(16, 0.7, 1.4, 1.4);
+    small.enable_parallel_eval(4);
+    assert!(evaluators(&mut small).iter().all(|&id| id == me));
+
+    let mut large: pso::PSO<Traced> = pso::PSO::new(16, 0.7, 1.4, 1.4);
+    large.enable_parallel_eval(4);
+    large.set_parallel_threshold(8);
+    let ids = evaluators(&mut large);
+    assert_eq!(ids.len(), 16);
+    assert!(ids.iter().all(|&id| id != me));
+}
+
+#[test]
+fn set_topology() {
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(10, 0.7, 1.4, 1.4, 42);
+    pso.set_topology(pso::Topology::Ring(2));
+
+    // The ring's neighborhood bests are updated incrementally, and match the best personal
+    // best among each particle and its two neighbors on either side.
+    for _ in 0..50 {
+        pso.update();
+        let particles = pso.particles();
+        for i in 0..particles.len() {
+            let naive = (0..5)
+                .map(|d| particles[(i + particles.len() - 2 + d) % particles.len()].best.1)
+                .fold(f64::NEG_INFINITY, f64::max);
+            assert_eq!(eval_func(pso.social_attractor(i)), naive);
+        }
+    }
+}
+
+#[test]
+fn set_unification() {
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.7, 1.4, 1.4, 42);
+    pso.set_topology(pso::Topology::Ring(1));
+    for _ in 0..3 {
+        pso.update();
+    }
+
+    let lbests: Vec<f64> = (0..8).map(|i| pso.social_attractor(i)).collect();
+    let gbest = pso.best().0.pos;
+
+    assert!(pso.set_unification(1.5).is_err());
+    pso.set_unification(0.5).unwrap();
+    for (i, lbest) in lbests.iter().enumerate() {
+        assert_eq!(pso.social_attractor(i), 0.5 * gbest + 0.5 * lbest);
+    }
+
+    pso.set_unification(1.0).unwrap();
+    assert!((0..8).all(|i| pso.social_attractor(i) == gbest));
+}
+
+#[test]
+fn set_c_centroid() {
+    let mut xs = vec![0.0, 1.0, 2.0, 3.0].into_iter();
+    let sampler = move || {
+        let x = xs.next().unwrap();
+        Particle { pos: x, vel: 0.0, best: (x, eval_func(x)) }
+    };
+    // Only the centroid attracts the particles.
+    let mut pso = pso::PSO::new_with_sampler(4, sampler, 0.5, 0.0, 0.0);
+    assert!(pso.set_c_centroid(-1.0).is_err());
+    pso.set_c_centroid(1.0).unwrap();
+
+    for _ in 0..200 {
+        pso.update();
+    }
+    let centroid = pso.centroid_position();
+    assert!(0.0 < centroid && centroid < 3.0);
+    assert!(pso.particles().iter().all(|p| (p.pos - centroid).abs() < 1e-6));
+}
+
+#[test]
+fn set_topology_graph() {
+    use meta_heuristics::error::MetaHeuristicsError;
+
+    // Particles at rest at 0, 5, -1 and -2, evaluated as their position.
+    let mut xs = vec![-2.0, -1.0, 5.0, 0.0];
+    let sampler = move || {
+        let x = xs.pop().unwrap();
+        Ramp { pos: x, vel: 0.0, best: (x, x) }
+    };
+    // Only the social term moves the particles.
+    let mut pso = pso::PSO::new_with_sampler(4, sampler, 0.0, 0.0, 1.0);
+
+    // A star: the hub 0 sees every particle, and each leaf sees only the hub.
+    assert_eq!(pso.set_topology_graph(vec![vec![0]; 3]),
+               Err(MetaHeuristicsError::TopologyMismatch { particles: 4, lists: 3 }));
+    assert_eq!(pso.set_topology_graph(vec![vec![0, 1, 2, 3], vec![0], vec![], vec![0]]),
+               Err(MetaHeuristicsError::InvalidNeighbors { particle: 2 }));
+    assert_eq!(pso.set_topology_graph(vec![vec![0, 1, 2, 4], vec![0], vec![0], vec![0]]),
+               Err(MetaHeuristicsError::InvalidNeighbors { particle: 0 }));
+    assert_eq!(pso.topology(), pso::Topology::Global);
+
+    pso.set_topology_graph(vec![vec![0, 1, 2, 3], vec![0], vec![0], vec![0]]).unwrap();
+    assert_eq!(pso.topology(), pso::Topology::Graph);
+    pso.update();
+
+    // The hub is drawn up to the swarm's best at 5, but the leaves towards the hub at 0,
+    // even the leaf at 5 itself.
+    let vels: Vec<f64> = pso.particles().iter().map(|p| p.vel).collect();
+    assert!(vels[0] >= 0.0 && vels[0] <= 5.0);
+    assert!(vels[1] >= -5.0 && vels[1] < 0.0);
+    assert!(vels[2] > 0.0 && vels[2] <= 1.0);
+    assert!(vels[3] > 0.0 && vels[3] <= 2.0);
+}
+
+#[test]
+fn set_mode() {
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(16, 0.7, 1.4, 1.4, 42);
+    pso.set_mode(pso::Mode::SocialOnly);
+    assert_eq!(pso.mode(), pso::Mode::SocialOnly);
+
+    for _ in 0..50 {
+        pso.update();
+    }
+    assert!(pso.best().1 > 1.5);
+}
+
+#[test]
+fn set_consistent_rng_draws() {
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use rand::{SeedableRng, XorShiftRng};
+
+    let count = |mode: pso::Mode, consistent: bool| {
+        let draws = Rc::new(Cell::new(0));
+        let rng = Counting(XorShiftRng::from_seed([1, 2, 3, 4]), draws.clone());
+        let mut pso: pso::PSO<Particle, _> = pso::PSO::new_with_rng(8, 0.7, 1.4, 1.4, rng);
+        pso.set_mode(mode);
+        pso.set_consistent_rng_draws(consistent);
+        for _ in 0..10 {
+            pso.update();
+        }
+        draws.get()
+    };
+
+    for &mode in &[pso::Mode::Standard, pso::Mode::CognitiveOnly, pso::Mode::SocialOnly] {
+        assert_eq!(count(mode, true), 10 * 2 * 8);
+    }
+    assert_eq!(count(pso::Mode::SocialOnly, false), 10 * 8);
+}
+
+#[test]
+fn set_comparator() {
+    use std::cmp::Ordering;
+
+    // Two criteria, the first best at 1 and the second at -1.
+    fn eval_func(x: f64) -> (f64, f64) {
+        (-(x - 1.0).powi(2), -(x + 1.0).powi(2))
+    }
+
+    #[derive(Clone, Copy)]
+    struct Biobjective {
+        pos: f64,
+        vel: f64,
+        best: (f64, (f64, f64)),
+    }
+
+    impl Candidate for Biobjective {
+        type Pos = f64;
+        type Eval = (f64, f64);
+
+        fn eval(&self) -> (f64, f64) {
+            eval_func(self.pos)
+        }
+
+        fn pos(&self) -> f64 {
+            self.pos
+        }
+
+        fn pos_mut(&mut self) -> &mut f64 {
+            &mut self.pos
+        }
+    }
+
+    impl RandomCandidate for Biobjective {
+        fn new_random() -> Self {
+            Self::new_random_seeded(rand::random())
+        }
+    }
+
+    impl SeededCandidate for Biobjective {
+        fn new_random_seeded(seed: u64) -> Self {
+            let Closed01(x) = seeded_rng(seed).gen::<Closed01<f64>>();
+            let x = 6.0 * x - 3.0;
+            Biobjective { pos: x, vel: 0.0, best: (x, eval_func(x)) }
+        }
+    }
+
+    impl pso::Particle for Biobjective {
+        fn vel(&self) -> f64 {
+            self.vel
+        }
+
+        fn best(&self) -> (f64, (f64, f64)) {
+            self.best
+        }
+
+        fn vel_mut(&mut self) -> &mut f64 {
+            &mut self.vel
+        }
+
+        fn best_mut(&mut self) -> &mut (f64, (f64, f64)) {
+            &mut self.best
+        }
+    }
+
+    let mut pso: pso::PSO<Biobjective, _> = pso::PSO::new_seeded(16, 0.7, 1.4, 1.4, 42);
+
+    // Tuples compare lexicographically from the first criterion; prefer the second instead.
+    pso.set_comparator(|a: &(f64, f64), b: &(f64, f64)| {
+        (a.1, a.0).partial_cmp(&(b.1, b.0)).unwrap_or(Ordering::Equal)
+    });
+    for _ in 0..100 {
+        pso.update();
+    }
+    assert!((pso.best().0.pos + 1.0).abs() < 1e-3);
+}
+
+#[test]
+fn best_ref() {
+    // Particles need not be `Copy`.
+    #[derive(Clone)]
+    struct Trailed {
+        pos: f64,
+        vel: f64,
+        best: (f64, f64),
+        trail: Vec<f64>,
+    }
+
+    impl Candidate for Trailed {
+        type Pos = f64;
+        type Eval = f64;
+
+        fn eval(&self) -> f64 {
+            eval_func(self.pos)
+        }
+
+        fn pos(&self) -> f64 {
+            self.pos
+        }
+
+        fn pos_mut(&mut self) -> &mut f64 {
+            &mut self.pos
+        }
+    }
+
+    impl RandomCandidate for Trailed {
+        fn new_random() -> Self {
+            Self::new_random_seeded(rand::random())
+        }
+    }
+
+    impl SeededCandidate for Trailed {
+        fn new_random_seeded(seed: u64) -> Self {
+            let Particle { pos, vel, best } = Particle::new_random_seeded(seed);
+            Trailed { pos, vel, best, trail: vec![pos] }
+        }
+    }
+
+    impl pso::Particle for Trailed {
+        fn vel(&self) -> f64 {
+            self.vel
+        }
+
+        fn best(&self) -> (f64, f64) {
+            self.best
+        }
+
+        fn vel_mut(&mut self) -> &mut f64 {
+            &mut self.vel
+        }
+
+        fn best_mut(&mut self) -> &mut (f64, f64) {
+            &mut self.best
+        }
+    }
+
+    let mut pso: pso::PSO<Trailed, _> = pso::PSO::new_seeded(8, 0.9, 0.9, 0.9, 42);
+    for _ in 0..10 {
+        pso.update();
+    }
+
+    let (best, eval) = pso.best_ref();
+    assert_eq!(*eval, pso.best().1);
+    assert_eq!(best.trail.len(), 1);
+}
+
+#[test]
+fn initial_best() {
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.9, 0.9, 0.9, 42);
+    let initial = pso.particles().iter().map(|p| p.eval()).fold(f64::MIN, f64::max);
+    assert_eq!(pso.initial_best(), initial);
+
+    for _ in 0..10 {
+        pso.update();
+    }
+    assert_eq!(pso.initial_best(), initial);
+}
+
+#[test]
+fn archive() {
+    // The initial particles lie close to the optimum at 0, and the reset ones far from it.
+    let mut xs = vec![6.0, 5.0, 4.0, 3.0, 2.0, 1.0, 0.5, 0.1];
+    let sampler = move || {
+        let x = xs.pop().unwrap();
+        Bowl { pos: x, vel: 0.0, best: (x, -x * x) }
+    };
+    let mut pso = pso::PSO::new_with_sampler(4, sampler, 0.7, 1.5, 1.5);
+
+    pso.reset();
+    assert_eq!(pso.best().1, -9.0);
+
+    let (best, &e) = pso.archive().best().unwrap();
+    assert_eq!((best.pos, e), (0.1, -0.1 * 0.1));
+}
+
+#[test]
+fn worst() {
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.9, 0.9, 0.9, 42);
+    pso.update();
+
+    let (worst, e) = pso.worst();
+    assert!(e <= pso.best().1);
+    assert_eq!(e, eval_func(worst.pos));
+    assert!(pso.particles().iter().all(|p| eval_func(p.pos) >= e));
+}
+
+#[test]
+fn top_k() {
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.9, 0.9, 0.9, 42);
+    pso.update();
+
+    let mut sorted: Vec<f64> = pso.particles().iter().map(|p| eval_func(p.pos)).collect();
+    sorted.sort_by(|a, b| b.partial_cmp(a).unwrap());
+
+    let top: Vec<f64> = pso.top_k(3).into_iter().map(|(_, e)| e).collect();
+    assert_eq!(top, &sorted[..3]);
+    assert_eq!(pso.top_k(1)[0].1, pso.best().1);
+    assert_eq!(pso.top_k(100).len(), 8);
+}
+
+#[test]
+fn top_k_ties() {
+    // 0, 1 and 2 all evaluate to 1, while 0.5 evaluates to less and 3 to much less.
+    let mut xs = vec![0.5, 2.0, 1.0, 3.0, 0.0, 1.0].into_iter();
+    let sampler = move || {
+        let x = xs.next().unwrap();
+        Particle { pos: x, vel: 0.0, best: (x, eval_func(x)) }
+    };
+    let pso = pso::PSO::new_with_sampler(6, sampler, 0.7, 1.4, 1.4);
+
+    let top: Vec<f64> = pso.top_k(5).into_iter().map(|(p, _)| p.pos).collect();
+    assert_eq!(top, vec![2.0, 1.0, 0.0, 1.0, 0.5]);
+    let top: Vec<f64> = pso.top_k(2).into_iter().map(|(p, _)| p.pos).collect();
+    assert_eq!(top, vec![2.0, 1.0]);
+}
+
+#[test]
+fn centroid_position() {
+    // Pairs of positions symmetric about 1.
+    let mut xs = vec![-1.0, 3.0, 0.5, 1.5].into_iter();
+    let sampler = move || {
+        let x = xs.next().unwrap();
+        Particle { pos: x, vel: 0.0, best: (x, eval_func(x)) }
+    };
+    let pso = pso::PSO::new_with_sampler(4, sampler, 0.7, 1.4, 1.4);
+
+    assert_eq!(pso.centroid_position(), 1.0);
+    assert_eq!(pso.centroid_eval(), eval_func(1.0));
+}
+
+#[test]
+fn run_eval_budget() {
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.7, 1.4, 1.4, 42);
+    assert_eq!(pso.run_eval_budget(100), 11);
+    assert_eq!(pso.eval_count(), 8 + 11 * 8);
+
+    pso.set_local_search(5, 0.1).unwrap();
+    let iters = pso.run_eval_budget(200);
+    assert_eq!((iters, pso.eval_count()), (8, 96 + 8 * 13));
+    assert!(pso.eval_count() <= 200);
+}
+
+#[test]
+fn reconfigure() {
+    // A lone coasting particle, whose velocity only decays by the inertia.
+    let sampler = || Particle { pos: 0.0, vel: 1.0, best: (0.0, eval_func(0.0)) };
+    let mut pso = pso::PSO::new_with_sampler(1, sampler, 0.5, 0.0, 0.0);
+    pso.update();
+    assert_eq!(pso.particles()[0].vel, 0.5);
+
+    let mut config = pso.config();
+    config.inertia = 0.2;
+    pso.reconfigure(config).unwrap();
+    assert_eq!(pso.config(), config);
+    pso.update();
+    assert_eq!(pso.particles()[0].vel, 0.1);
+
+    let invalid = pso::PsoConfig { inertia: 0.9, c_local: -1.0, ..config };
+    assert!(pso.reconfigure(invalid).is_err());
+    let invalid = pso::PsoConfig { particles_num: 2, ..config };
+    assert!(pso.reconfigure(invalid).is_err());
+    assert_eq!(pso.config(), config);
+}
+
+#[test]
+fn snapshot() {
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.7, 1.4, 1.4, 42);
+    for _ in 0..5 {
+        pso.update();
+    }
+
+    let snapshot = pso.snapshot();
+    assert_eq!(snapshot.len(), pso.particles().len());
+    for (view, p) in snapshot.iter().zip(pso.particles()) {
+        assert_eq!((view.pos, view.vel), (p.pos(), pso::Particle::vel(p)));
+        assert_eq!((view.best_pos, view.best_eval), pso::Particle::best(p));
+    }
+}
+
+#[test]
+fn write_csv() {
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.7, 1.4, 1.4, 42);
+    pso.update();
+
+    let mut buf = Vec::new();
+    pso.write_csv(&mut buf).unwrap();
+    let csv = String::from_utf8(buf).unwrap();
+
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "x0,eval");
+    assert_eq!(lines.len(), 1 + 8);
+
+    let p = &pso.particles()[0];
+    assert_eq!(lines[1], format!("{},{}", p.pos, eval_func(p.pos)));
+}
+
+#[test]
+fn set_time_varying() {
+    // A peak drifting from 0 by 0.01 per iteration.
+    fn eval_func(x: f64, iteration: usize) -> f64 {
+        -(x - 0.01 * iteration as f64).powi(2)
+    }
+
+    #[derive(Clone, Copy)]
+    struct Tracking {
+        pos: f64,
+        vel: f64,
+        best: (f64, f64),
+    }
+
+    impl Candidate for Tracking {
+        type Pos = f64;
+        type Eval = f64;
+
+        fn eval(&self) -> f64 {
+            eval_func(self.pos, 0)
+        }
+
+        fn eval_at(&self, iteration: usize) -> f64 {
+            eval_func(self.pos, iteration)
+        }
+
+        fn pos(&self) -> f64 {
+            self.pos
+        }
+
+        fn pos_mut(&mut self) -> &mut f64 {
+            &mut self.pos
+        }
+    }
+
+    impl RandomCandidate for Tracking {
+        fn new_random() -> Self {
+            Self::new_random_seeded(rand::random())
+        }
+    }
+
+    impl SeededCandidate for Tracking {
+        fn new_random_seeded(seed: u64) -> Self {
+            let Closed01(x) = seeded_rng(seed).gen::<Closed01<f64>>();
+            let x = 4.0 * x - 2.0;
+            Tracking { pos: x, vel: 0.0, best: (x, eval_func(x, 0)) }
+        }
+    }
+
+    impl pso::Particle for Tracking {
+        fn vel(&self) -> f64 {
+            self.vel
+        }
+
+        fn best(&self) -> (f64, f64) {
+            self.best
+        }
+
+        fn vel_mut(&mut self) -> &mut f64 {
+            &mut self.vel
+        }
+
+        fn best_mut(&mut self) -> &mut (f64, f64) {
+            &mut self.best
+        }
+    }
+
+    let mut pso: pso::PSO<Tracking, _> = pso::PSO::new_seeded(32, 0.9, 0.9, 0.9, 42);
+    pso.set_time_varying(true);
+    for _ in 0..100 {
+        pso.update();
+    }
+
+    assert_eq!(pso.eval_count(), 32 + 100 * 64);
+    assert!((pso.best().0.pos - 1.0).abs() < 0.3);
+}
+
+#[test]
+fn set_elitism() {
+    // Every particle takes a Lévy step after each move.
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.7, 1.4, 1.4, 42);
+    pso.set_levy(1.0, 1.5).unwrap();
+    pso.set_elitism(true);
+
+    let mut prev = pso.best().1;
+    for _ in 0..50 {
+        pso.update();
+        let e = pso.best().1;
+        assert!(e >= prev);
+        prev = e;
+    }
+    assert_eq!(Some(prev), pso.archive().best().map(|(_, &e)| e));
+
+    // The fresh swarm is only reported where it does better.
+    pso.reset();
+    assert!(pso.best().1 >= prev);
+}
+
+#[test]
+fn set_adaptive_mutation() {
+    // A single particle at rest next to the optimum at about 1.6404.
+    let sampler = || Particle { pos: 1.64, vel: 0.0, best: (1.64, eval_func(1.64)) };
+    let mut pso = pso::PSO::new_with_sampler_seeded(1, sampler, 0.0, 0.0, 0.0, 42);
+    pso.set_local_search(10, 1.0).unwrap();
+    assert!(pso.set_adaptive_mutation(1.5).is_err());
+    pso.set_adaptive_mutation(0.2).unwrap();
+
+    // Steps of up to 1 almost always overshoot, so the step size shrinks.
+    for _ in 0..20 {
+        pso.update();
+    }
+    assert!(pso.mutation_step_size().unwrap() < 0.1);
+    assert!((pso.best().0.pos - 1.6404).abs() < 1e-3);
+}
+
+#[test]
+fn set_linear_decay() {
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.7, 1.4, 1.4, 42);
+    pso.set_local_search(3, 0.4).unwrap();
+    assert_eq!(pso.progress(), None);
+
+    pso.set_linear_decay(4);
+    for i in 0..4 {
+        assert_eq!(pso.progress(), Some(i as f64 / 4.0));
+        assert!((pso.mutation_step_size().unwrap() - 0.1 * (4 - i) as f64).abs() < 1e-12);
+        pso.update();
+    }
+    assert_eq!(pso.progress(), Some(1.0));
+    assert_eq!(pso.mutation_step_size(), Some(0.0));
+}
+
+#[test]
+fn set_record_history() {
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.9, 0.9, 0.9, 42);
+    pso.update();
+    assert!(pso.history().is_empty());
+
+    pso.set_record_history(true);
+    for _ in 0..5 {
+        pso.update();
+    }
+    assert_eq!(pso.history().len(), 5);
+    assert_eq!(pso.history()[4], pso.best().1);
+}
+
+#[test]
+fn reserve_history() {
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.9, 0.9, 0.9, 42);
+    pso.set_record_history(true);
+    pso.reserve_history(100);
+    assert!(pso.history().capacity() >= 100);
+
+    let capacity = pso.history().capacity();
+    for _ in 0..100 {
+        pso.update();
+    }
+    assert_eq!(pso.history().capacity(), capacity);
+}
+
+#[test]
+fn set_record_best_positions() {
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.9, 0.9, 0.9, 42);
+    pso.set_record_best_positions(true);
+    for _ in 0..5 {
+        pso.update();
+    }
+
+    assert!(pso.history().is_empty());
+    assert_eq!(pso.best_position_history().len(), 5);
+    assert_eq!(pso.best_position_history()[4], pso.best().0.pos());
+}
+
+#[test]
+fn set_improvement_epsilon() {
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.9, 0.9, 0.9, 42);
+    let initial: Vec<(f64, f64)> = pso.particles().iter().map(|p| p.best).collect();
+
+    // Nothing beats the initial personal bests, all above -20, by 100.
+    pso.set_improvement_epsilon(100.0);
+    for _ in 0..5 {
+        pso.update();
+    }
+
+    assert_eq!(pso.stagnation(), 5);
+    for (p, &best) in pso.particles().iter().zip(&initial) {
+        assert_eq!(p.best, best);
+    }
+
+    // A lone particle climbing from 1 by 0.05 per update, first by about 0.05 and then by
+    // about 0.11 over its eval of 1 there.
+    let sampler = || Particle { pos: 1.0, vel: 0.05, best: (1.0, eval_func(1.0)) };
+    let mut pso = pso::PSO::new_with_sampler(1, sampler, 1.0, 0.0, 0.0);
+    pso.set_improvement_epsilon(0.1);
+
+    pso.update();
+    assert_eq!(pso.particles()[0].best.0, 1.0);
+    assert_eq!(pso.best().0.pos, 1.0);
+
+    pso.update();
+    let p = pso.particles()[0];
+    assert_eq!(p.best.0, p.pos);
+    assert_eq!(pso.best().0.pos, p.pos);
+
+    // The same holds for the bests offered to the swarm.
+    let mut offered = p;
+    offered.pos = 1.15;
+    assert!(eval_func(1.15) > eval_func(p.pos));
+    assert!(!pso.set_global_best(offered, false));
+    offered.pos = 1.3;
+    assert!(pso.set_global_best(offered, false));
+
+    // Minimizing instead, the particle descends from 1 by about 0.05 and then by about 0.04.
+    let sampler = || Particle { pos: 1.0, vel: -0.05, best: (1.0, eval_func(1.0)) };
+    let mut pso = pso::PSO::new_with_sampler(1, sampler, 1.0, 0.0, 0.0);
+    pso.set_comparator(|a: &f64, b: &f64| b.partial_cmp(a).unwrap());
+    pso.set_improvement_epsilon(0.045);
+
+    pso.update();
+    let pos = pso.particles()[0].pos;
+    assert_eq!(pso.best().0.pos, pos);
+    assert_eq!(pso.stagnation(), 0);
+    assert!((pso.improvement_delta() - (eval_func(1.0) - eval_func(pos))).abs() < 1e-12);
+
+    pso.update();
+    assert_eq!(pso.best().0.pos, pos);
+    assert_eq!(pso.stagnation(), 1);
+    assert_eq!(pso.improvement_delta(), 0.0);
+}
+
+#[test]
+fn improvement_delta() {
+    use std::ops::ControlFlow;
+
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.9, 0.9, 0.9, 42);
+    assert_eq!(pso.improvement_delta(), 0.0);
+
+    let initial = pso.best().1;
+    let mut best_so_far = initial;
+    let mut total = 0.0;
+    pso.run(30, |pso| {
+        let delta = pso.improvement_delta();
+        assert!(delta >= 0.0);
+        total += delta;
+        best_so_far = best_so_far.max(pso.best().1);
+        ControlFlow::Continue(())
+    });
+    assert!((total - (best_so_far - initial)).abs() < 1e-9);
+}
+
+#[test]
+fn eval_stats() {
+    // Particles at 0, 1, 2 and 6, evaluated as their position.
+    let mut xs = vec![6.0, 2.0, 1.0, 0.0];
+    let sampler = move || {
+        let x = xs.pop().unwrap();
+        Ramp { pos: x, vel: 0.0, best: (x, x) }
+    };
+    let pso = pso::PSO::new_with_sampler(4, sampler, 0.9, 0.9, 0.9);
+
+    let stats = pso.eval_stats();
+    assert_eq!(stats.min, 0.0);
+    assert_eq!(stats.max, 6.0);
+    assert_eq!(stats.mean, 2.25);
+    // The squared deviations are 5.0625, 1.5625, 0.0625 and 14.0625.
+    assert!((stats.std - (20.75f64 / 4.0).sqrt()).abs() < 1e-12);
+}
+
+#[test]
+fn has_converged() {
+    // Particles drifting at constant velocity up the eval `pos` keep improving.
+    let moving = || Ramp { pos: 0.0, vel: 1.0, best: (0.0, 0.0) };
+    let mut pso = pso::PSO::new_with_sampler(4, moving, 1.0, 0.0, 0.0);
+    for _ in 0..5 {
+        pso.update();
+        assert!(!pso.has_converged(0.5, 3));
+    }
+
+    // Particles at rest do not.
+    let resting = || Ramp { pos: 0.0, vel: 0.0, best: (0.0, 0.0) };
+    let mut pso = pso::PSO::new_with_sampler(4, resting, 1.0, 0.0, 0.0);
+    pso.update();
+    pso.update();
+    assert!(!pso.has_converged(0.5, 3));
+    pso.update();
+    assert!(pso.has_converged(0.5, 3));
+
+    // Under a comparator preferring smaller evals, drifting down the eval is improving.
+    let falling = || Ramp { pos: 0.0, vel: -1.0, best: (0.0, 0.0) };
+    let mut pso = pso::PSO::new_with_sampler(4, falling, 1.0, 0.0, 0.0);
+    pso.set_comparator(|a: &f64, b: &f64| b.partial_cmp(a).unwrap());
+    for _ in 0..5 {
+        pso.update();
+        assert!(!pso.has_converged(0.5, 3));
+    }
+}
+
+#[test]
+fn is_diverging() {
+    // An inertia above 1 doubles the velocities every update.
+    let sampler = || Bowl { pos: 1.0, vel: 1.0, best: (1.0, -1.0) };
+    let mut pso = pso::PSO::new_with_sampler(4, sampler, 2.0, 0.0, 0.0);
+    for _ in 0..10 {
+        pso.update();
+    }
+    assert!(!pso.is_diverging());
+
+    let mut iters = 10;
+    while !pso.is_diverging() && iters < 2000 {
+        pso.update();
+        iters += 1;
+    }
+    assert!(pso.is_diverging());
+    assert!(iters < 1100);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn run_streaming() {
+    use serde_json::Value;
+
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.7, 1.4, 1.4, 42);
+    let mut buf = Vec::new();
+    pso.run_streaming(3, &mut buf).unwrap();
+
+    let ndjson = String::from_utf8(buf).unwrap();
+    assert!(ndjson.starts_with("{\"iter\":1,\"best_eval\":"));
+    let lines: Vec<Value> = ndjson.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+    assert_eq!(lines.len(), 3);
+    for (i, line) in lines.iter().enumerate() {
+        let keys: Vec<&String> = line.as_object().unwrap().keys().collect();
+        assert_eq!(keys, ["best_eval", "diversity", "eval_count", "iter"]);
+        assert_eq!(line["iter"], i + 1);
+        assert_eq!(line["eval_count"], 8 + 8 * (i + 1));
+        assert!(line["diversity"].is_f64());
+    }
+    assert_eq!(lines[2]["best_eval"], pso.best().1);
+}
+
+#[test]
+fn run_until_optimum() {
+    let mut pso: pso::PSO<Bowl> = pso::PSO::with_config(pso::PsoConfig::recommended(1));
+    let (success, iters) = pso.run_until_optimum(0.0, 1e-6, 1000);
+
+    assert!(success);
+    assert_eq!(pso.iteration(), iters);
+    assert!(pso.best().1 >= -1e-6);
+}
+
+#[test]
+fn polish() {
+    // A smooth quadratic peaking at 1.
+    particle_maximizing!(Peak, |x| -(x - 1.0).powi(2));
+
+    let sampler = || Peak::at(0.5);
+    let mut pso = pso::PSO::new_with_sampler(4, sampler, 0.7, 1.4, 1.4);
+    let before = pso.best().1;
+
+    // Each step halves the distance to the peak.
+    assert!(pso.polish(3, 1e-4, 0.25));
+    assert!(pso.best().1 > before);
+    assert!((pso.best().0.pos - 0.9375).abs() < 1e-6);
+    assert_eq!(pso.eval_count(), 4 + 3 * 3);
+
+    pso.polish(100, 1e-4, 0.25);
+    assert!((pso.best().0.pos - 1.0).abs() < 1e-6);
+    assert!(pso.eval_count() < 4 + 103 * 3);
+}
+
+#[cfg(feature = "plotters")]
+#[test]
+fn plot_convergence() {
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.7, 1.4, 1.4, 42);
+    pso.set_record_history(true);
+    for _ in 0..20 {
+        pso.update();
+    }
+
+    let dir = std::env::temp_dir();
+    pso.plot_convergence(dir.join("convergence.svg")).unwrap();
+    pso.plot_particles(dir.join("particles.png")).unwrap();
+    assert!(dir.join("convergence.svg").exists() && dir.join("particles.png").exists());
+}
+
+#[test]
+fn set_resampling() {
+    use std::cell::RefCell;
+    use rand::Isaac64Rng;
+
+    fn true_eval(x: f64) -> f64 {
+        -(x - 1.0) * (x - 1.0)
+    }
+
+    // The noise is drawn from a seeded generator, so that the trials are reproducible.
+    thread_local!(static NOISE: RefCell<Isaac64Rng> = RefCell::new(seeded_rng(7)));
+
+    #[derive(Clone, Copy)]
+    struct Noisy {
+        pos: f64,
+        vel: f64,
+        best: (f64, f64),
+    }
+
+    impl Candidate for Noisy {
+        type Pos = f64;
+        type Eval = f64;
+
+        // Uniform noise in [-1, 1].
+        fn eval(&self) -> f64 {
+            true_eval(self.pos) + NOISE.with(|rng| 2.0 * rng.borrow_mut().gen::<f64>() - 1.0)
+        }
+
+        fn pos(&self) -> f64 {
+            self.pos
+        }
+
+        fn pos_mut(&mut self) -> &mut f64 {
+            &mut self.pos
+        }
+    }
+
+    impl RandomCandidate for Noisy {
+        fn new_random() -> Self {
+            Self::new_random_seeded(rand::random())
+        }
+    }
+
+    impl SeededCandidate for Noisy {
+        fn new_random_seeded(seed: u64) -> Self {
+            let Closed01(x) = seeded_rng(seed).gen::<Closed01<f64>>();
+            let x = 4.0 * x - 1.0;
+            Noisy { pos: x, vel: 0.0, best: (x, true_eval(x)) }
+        }
+    }
+
+    impl pso::Particle for Noisy {
+        fn vel(&self) -> f64 {
+            self.vel
+        }
+
+        fn best(&self) -> (f64, f64) {
+            self.best
+        }
+
+        fn vel_mut(&mut self) -> &mut f64 {
+            &mut self.vel
+        }
+
+        fn best_mut(&mut self) -> &mut (f64, f64) {
+            &mut self.best
+        }
+    }
+
+    // How much the best eval overstates the true eval of the best particle, over 5 trials.
+    let overstatement = |samples: usize| {
+        let mut total = 0.0;
+        for trial in 0..5 {
+            let mut pso: pso::PSO<Noisy, _> = pso::PSO::new_seeded(16, 0.7, 1.4, 1.4, trial);
+            pso.set_resampling(samples).unwrap();
+            for _ in 0..50 {
+                pso.update();
+            }
+            assert_eq!(pso.eval_count(), 16 + 50 * 16 * samples);
+
+            let (best, eval) = pso.best();
+            total += eval - true_eval(best.pos);
+        }
+        total / 5.0
+    };
+
+    assert!(overstatement(1) > 0.5);
+    assert!(overstatement(64) < 0.3);
+
+    let mut pso: pso::PSO<Noisy, _> = pso::PSO::new_seeded(16, 0.7, 1.4, 1.4, 0);
+    assert!(pso.set_resampling(0).is_err());
+    assert_eq!(pso.resampling(), 1);
+}
+
+#[test]
+fn set_total_order_f64() {
+    // -0, +0 and negative NaN, by index.
+    fn eval_func(x: f64) -> f64 {
+        [-0.0, 0.0, -f64::NAN][x as usize]
+    }
+
+    #[derive(Clone, Copy)]
+    struct Signed {
+        pos: f64,
+        vel: f64,
+        best: (f64, f64),
+    }
+
+    impl Candidate for Signed {
+        type Pos = f64;
+        type Eval = f64;
+
+        fn eval(&self) -> f64 {
+            eval_func(self.pos)
+        }
+
+        fn pos(&self) -> f64 {
+            self.pos
+        }
+
+        fn pos_mut(&mut self) -> &mut f64 {
+            &mut self.pos
+        }
+    }
+
+    impl pso::Particle for Signed {
+        fn vel(&self) -> f64 {
+            self.vel
+        }
+
+        fn best(&self) -> (f64, f64) {
+            self.best
+        }
+
+        fn vel_mut(&mut self) -> &mut f64 {
+            &mut self.vel
+        }
+
+        fn best_mut(&mut self) -> &mut (f64, f64) {
+            &mut self.best
+        }
+    }
+
+    let swarm = |order: [usize; 3]| {
+        let mut xs = Vec::from(order).into_iter();
+        let sampler = move || {
+            let x = xs.next().unwrap() as f64;
+            Signed { pos: x, vel: 0.0, best: (x, eval_func(x)) }
+        };
+        let mut pso = pso::PSO::new_with_sampler(3, sampler, 0.0, 0.0, 0.0);
+        pso.set_total_order_f64();
+        pso
+    };
+
+    // Whatever the order of the particles, +0 is the best and NaN the worst.
+    for &order in &[[0, 1, 2], [2, 1, 0], [2, 0, 1], [1, 2, 0]] {
+        let pso = swarm(order);
+        let (best, e) = pso.best();
+        assert_eq!((best.pos, e.is_sign_positive()), (1.0, true));
+        assert!(pso.worst().1.is_nan());
+
+        let ranked: Vec<f64> = pso.top_k(3).into_iter().map(|(p, _)| p.pos).collect();
+        assert_eq!(ranked, vec![1.0, 0.0, 2.0]);
+    }
+}
+
+#[test]
+fn set_levy() {
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(16, 0.9, 0.9, 0.9, 42);
+    assert!(pso.set_levy(0.5, 3.0).is_err());
+
+    pso.set_levy(0.2, 1.5).unwrap();
+    for _ in 0..20 {
+        pso.update();
+    }
+    assert!(pso.best().1 > 1.5);
+}
+
+#[test]
+fn max_velocity_magnitude() {
+    // An inertia above 1 makes velocities explode.
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 1.5, 0.9, 0.9, 42);
+    pso.update();
+    pso.update();
+
+    let early = pso.max_velocity_magnitude();
+    for _ in 0..30 {
+        pso.update();
+    }
+    assert!(pso.max_velocity_magnitude() > early);
+    assert!(pso.mean_velocity_magnitude() <= pso.max_velocity_magnitude());
+}
+
+#[test]
+fn set_apso() {
+    use meta_heuristics::pso::EvolutionaryState;
+
+    // A swarm spread over [2, 5.75], whose best lies at its edge.
+    let mut xs = (0..16).map(|i| 2.0 + 0.25 * i as f64);
+    let sampler = move || {
+        let x = xs.next().unwrap();
+        Bowl { pos: x, vel: 0.0, best: (x, Bowl::objective(x)) }
+    };
+    let mut pso = pso::PSO::new_with_sampler_seeded(16, sampler, 0.7, 1.4, 1.4, 42);
+    pso.set_apso(true);
+
+    assert_eq!(pso.evolutionary_state(), EvolutionaryState::JumpingOut);
+    pso.update();
+    let config = pso.config();
+    assert!((config.inertia - 1.0 / (1.0 + 1.5 * (-2.6f64).exp())).abs() < 1e-12);
+    assert!(1.9 <= config.c_local && config.c_local <= 1.95);
+    assert!(2.05 <= config.c_global && config.c_global <= 2.1);
+
+    let mut states = vec![];
+    for _ in 0..100 {
+        states.push(pso.evolutionary_state());
+        pso.update();
+
+        let config = pso.config();
+        assert!(0.4 <= config.inertia && config.inertia <= 0.9);
+        assert!(1.5 <= config.c_local && config.c_local <= 2.5);
+        assert!(1.5 <= config.c_global && config.c_global <= 2.5);
+        assert!(config.c_local + config.c_global <= 4.0 + 1e-12);
+    }
+
+    // The swarm gathers around the optimum at 0.
+    assert!(states[50..].iter().all(|&s| s == EvolutionaryState::Convergence));
+    assert!(pso.best().0.pos.abs() < 1e-3);
+}
+
+#[test]
+fn set_min_step() {
+    // Without inertia or attraction, the particles would never move.
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.0, 0.0, 0.0, 42);
+    assert!(pso.set_min_step(-1.0).is_err());
+    pso.set_min_step(0.01).unwrap();
+
+    for _ in 0..5 {
+        let before: Vec<f64> = pso.particles().iter().map(|p| p.pos).collect();
+        pso.update();
+        for (p, x) in pso.particles().iter().zip(before) {
+            assert!(((p.pos - x).abs() - 0.01).abs() < 1e-12);
+        }
+    }
+}
+
+#[test]
+fn set_reinitialization() {
+    use rand::{SeedableRng, XorShiftRng};
+
+    // Without any attraction, the particle at 0.9 moves by its velocity of 5 out of [0, 1].
+    let stray = Particle { pos: 0.9, vel: 5.0, best: (0.9, eval_func(0.9)) };
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(4, 1.0, 0.0, 0.0, 42);
+    pso.set_bounds(0.0, 1.0);
+    pso.set_reinitialization(true);
+    assert!(pso.reinitialization());
+    pso.set_particle(0, stray);
+    pso.update();
+
+    let fresh = pso.particles()[0];
+    assert!(fresh.pos >= 0.0 && fresh.pos <= 1.0);
+    assert_eq!(fresh.vel, 0.0);
+    assert!(fresh.best.0 >= 0.0 && fresh.best.0 <= 1.0);
+
+    // The fresh particle comes from the swarm's generator, so the seed decides it.
+    let fresh = |seed: [u32; 4]| {
+        let rng = XorShiftRng::from_seed(seed);
+        let mut pso: pso::PSO<Particle, _> = pso::PSO::new_with_rng(4, 1.0, 0.0, 0.0, rng);
+        pso.set_bounds(0.0, 1.0);
+        pso.set_reinitialization(true);
+        pso.set_particle(0, stray);
+        pso.update();
+        pso.particles()[0].pos
+    };
+    assert_eq!(fresh([1, 2, 3, 4]), fresh([1, 2, 3, 4]));
+    assert_ne!(fresh([1, 2, 3, 4]), fresh([5, 6, 7, 8]));
+}
+
+#[test]
+fn distances_to_best() {
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.7, 1.4, 1.4, 42);
+    pso.update();
+
+    let best = pso.best().0.pos;
+    let manual: Vec<f64> = pso.particles().iter().map(|p| (p.pos - best).abs()).collect();
+    let distances = pso.distances_to_best();
+    assert_eq!(distances, manual);
+    assert!(distances.contains(&0.0));
+}
+
+#[test]
+fn evolutionary_factor() {
+    use meta_heuristics::pso::EvolutionaryState;
+
+    let swarm = |xs: Vec<f64>| {
+        let mut xs = xs.into_iter();
+        let sampler = move || {
+            let x = xs.next().unwrap();
+            Bowl { pos: x, vel: 0.0, best: (x, Bowl::objective(x)) }
+        };
+        pso::PSO::new_with_sampler(5, sampler, 0.7, 1.4, 1.4)
+    };
+
+    // The best, at 0, lies in a cluster the last particle is far from.
+    let pso = swarm(vec![0.0, 0.1, 0.2, 0.3, 3.0]);
+    assert!((pso.evolutionary_factor() - 0.1 / 2.05).abs() < 1e-12);
+    assert_eq!(pso.evolutionary_state(), EvolutionaryState::Convergence);
+
+    // The best lies far from all the others.
+    let pso = swarm(vec![0.0, 3.0, 3.1, 3.2, 3.3]);
+    assert_eq!(pso.evolutionary_factor(), 1.0);
+    assert_eq!(pso.evolutionary_state(), EvolutionaryState::JumpingOut);
+}
+
+#[test]
+fn clusters() {
+    // Particles around both maxima, at 0 and about 1.64.
+    let mut xs = vec![0.0, 0.05, 1.64, -0.05, 1.6, 1.7].into_iter();
+    let sampler = move || {
+        let x = xs.next().unwrap();
+        Particle { pos: x, vel: 0.0, best: (x, eval_func(x)) }
+    };
+    let pso = pso::PSO::new_with_sampler(6, sampler, 0.7, 1.4, 1.4);
+
+    assert_eq!(pso.clusters(0.1), vec![vec![0, 1, 3], vec![2, 4, 5]]);
+    assert_eq!(pso.clusters(2.0).len(), 1);
+    assert_eq!(pso.clusters(0.01).len(), 6);
+}
+
+#[test]
+fn run_until_converged() {
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.5, 0.9, 0.9, 42);
+    let iters = pso.run_until_converged(1000, 1e-3);
+
+    assert!(iters < 1000);
+    assert_eq!(pso.iteration(), iters);
+    assert!(pso.diversity() < 1e-3);
+}
+
+#[test]
+fn summary() {
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.9, 0.9, 0.9, 42);
+    for _ in 0..10 {
+        pso.update();
+    }
+
+    let summary = pso.summary();
+    assert_eq!(summary.best_eval, pso.best().1);
+    assert_eq!((summary.iterations, summary.eval_count), (10, 88));
+    assert_eq!(summary.best_iteration, 10 - pso.stagnation());
+    assert_eq!(summary.diversity, pso.diversity());
+    assert!(summary.to_string().contains("of 10, 88 evaluations"));
+}
+
+#[test]
+fn set_velocity_limit_fraction() {
+    use meta_heuristics::error::MetaHeuristicsError;
+
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.9, 0.9, 0.9, 42);
+    assert_eq!(pso.set_velocity_limit_fraction(0.2), Err(MetaHeuristicsError::BoundsNotSet));
+
+    pso.set_bounds(-1.0, 3.0);
+    pso.set_velocity_limit_fraction(0.2).unwrap();
+    assert_eq!(pso.velocity_limit(), Some(0.8));
+
+    for _ in 0..10 {
+        pso.update();
+        assert!(pso.particles().iter().all(|p| p.vel.abs() <= 0.8));
+        assert!(pso.particles().iter().all(|p| -1.0 <= p.pos && p.pos <= 3.0));
+    }
+}
+
+#[test]
+fn position_bounds() {
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.9, 0.9, 0.9, 42);
+    pso.update();
+
+    let (lower, upper) = pso.position_bounds();
+    assert!(pso.particles().iter().all(|p| lower <= p.pos && p.pos <= upper));
+    assert!(pso.particles().iter().any(|p| p.pos == lower));
+    assert!(pso.particles().iter().any(|p| p.pos == upper));
+}
+
+#[test]
+fn randomize_velocities() {
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.9, 0.9, 0.9, 42);
+    assert!(pso.particles().iter().all(|p| p.vel == 0.0));
+
+    pso.randomize_velocities(0.5);
+    assert!(pso.particles().iter().all(|p| p.vel != 0.0 && p.vel.abs() <= 0.5));
+}
+
+#[test]
+fn kick_velocities() {
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.3, 0.9, 0.9, 42);
+    for _ in 0..300 {
+        pso.update();
+    }
+    assert!(pso.particles().iter().all(|p| p.vel.abs() < 1e-6));
+
+    let before: Vec<_> = pso.particles().iter().map(|p| (p.pos, p.best)).collect();
+    pso.kick_velocities(0.1);
+
+    let after: Vec<_> = pso.particles().iter().map(|p| (p.pos, p.best)).collect();
+    assert_eq!(after, before);
+    assert!(pso.particles().iter().all(|p| p.vel != 0.0 && p.vel.abs() <= 0.1));
+}
+
+#[test]
+fn restart_around_best() {
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(12, 0.7, 1.4, 1.4, 42);
+    for _ in 0..5 {
+        pso.update();
+    }
+
+    let (best, e) = pso.best();
+    pso.restart_around_best(0.1);
+
+    assert!(pso.particles().iter().all(|p| (p.pos - best.pos).abs() <= 0.1));
+    assert!(pso.particles().iter().any(|p| p.pos == best.pos));
+    assert!(pso.best().1 >= e);
+    assert_eq!(pso.eval_count(), 6 * 12 + 11);
+    assert_eq!(pso.stagnation(), 0);
+}
+
+#[test]
+fn set_local_search() {
+    // A swarm at rest near the optimum at about 1.6404, which the updates alone never reach.
+    let swarm = || {
+        let mut xs = vec![1.0, 1.2, 1.3];
+        let sampler = move || {
+            let x = xs.pop().unwrap();
+            Particle { pos: x, vel: 0.0, best: (x, eval_func(x)) }
+        };
+        pso::PSO::new_with_sampler_seeded(3, sampler, 0.0, 0.0, 0.0, 42)
+    };
+
+    let mut plain = swarm();
+    let mut memetic = swarm();
+    assert!(memetic.set_local_search(5, 0.0).is_err());
+    memetic.set_local_search(5, 0.05).unwrap();
+
+    for _ in 0..40 {
+        plain.update();
+        memetic.update();
+    }
+    assert_eq!(plain.best().0.pos, 1.3);
+    assert!((memetic.best().0.pos - 1.6404).abs() < 0.05);
+    assert!(memetic.best().1 > plain.best().1);
+    assert_eq!(memetic.eval_count(), plain.eval_count() + 40 * 5);
+}
+
+#[test]
+fn set_bare_bones() {
+    // The coefficients are unused in bare bones.
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(16, 0.0, 0.0, 0.0, 42);
+    pso.set_bare_bones(true);
+    assert!(pso.bare_bones());
+
+    for _ in 0..50 {
+        pso.update();
+    }
+    assert!(pso.best().1 > 1.5);
+    assert!(pso.particles().iter().all(|p| p.vel == 0.0));
+}
+
+#[test]
+fn set_elitist_learning() {
+    // A swarm at rest in the local maximum never leaves it on its own.
+    let sampler = || Particle { pos: 0.0, vel: 0.0, best: (0.0, eval_func(0.0)) };
+    let mut plain = pso::PSO::new_with_sampler_seeded(8, sampler, 0.7, 1.4, 1.4, 42);
+    let mut elitist = pso::PSO::new_with_sampler_seeded(8, sampler, 0.7, 1.4, 1.4, 42);
+    elitist.set_elitist_learning(2.0, 0.01).unwrap();
+    elitist.set_linear_decay(100);
+
+    for _ in 0..100 {
+        plain.update();
+        elitist.update();
+    }
+    assert_eq!(plain.best().0.pos, 0.0);
+    assert!((elitist.best().0.pos - 1.64).abs() < 0.01);
+    assert_eq!(elitist.eval_count(), plain.eval_count() + 100);
+
+    assert!(elitist.set_elitist_learning(-1.0, 0.0).is_err());
+}
+
+#[test]
+fn is_better() {
+    use meta_heuristics::Optimizer;
+
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(4, 0.7, 1.4, 1.4, 42);
+    assert!(pso.is_better(&2.0, &1.0));
+
+    pso.set_comparator(|a: &f64, b: &f64| b.partial_cmp(a).unwrap());
+    assert!(pso.is_better(&1.0, &2.0));
+}
+
+#[test]
+fn into_iter() {
+    let pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.9, 0.9, 0.9, 42);
+
+    let mut count = 0;
+    for p in &pso {
+        assert!(-1.0 <= p.pos && p.pos <= 3.0);
+        count += 1;
+    }
+    assert_eq!(count, pso.len());
+}
+
+#[test]
+fn debug_summary() {
+    let mut pso: pso::PSO<Particle, _> = pso::PSO::new_seeded(8, 0.9, 0.9, 0.9, 42);
+    pso.update();
+
+    let summary = format!("{:?}", pso);
+    assert!(summary.contains(&format!("best_eval: {:?}", pso.best().1)));
+    assert!(summary.contains("iteration: 1"));
+}
+